@@ -3,21 +3,34 @@
 
 mod main_view_model;
 mod model;
+mod tray_menu;
+use crate::main_view_model::WaveformMode;
+use crate::model::color_space::InputColorSpace;
 use crate::model::graph_plotter;
+use crate::model::graph_plotter::ReferenceMode;
+use crate::model::image_file;
 use crate::model::mouse_info;
 use crate::model::screenshot_capture;
+use crate::model::test_pattern::TestPattern;
+use crate::model::tray_icon::TrayIconMode;
+use crate::model::yuv_video;
+use crate::tray_menu::*;
 use tauri::Manager;
-use tauri::{CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem};
+use tauri::{
+    CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem,
+    SystemTraySubmenu,
+};
 
 const HTML_DIR: &str = "src/pages/";
 const WINDOW_LABEL_VECTOR_SCOPE: &str = "window_vector_scope";
 const WINDOW_LABEL_WAVEFORM: &str = "window_waveform";
 const WINDOW_LABEL_CAPTURE_AREA_SETTING: &str = "window_capture_area_setting";
+const WINDOW_LABEL_LOUPE: &str = "window_loupe";
+const WINDOW_LABEL_PALETTE: &str = "window_palette";
+const WINDOW_LABEL_FALSE_COLOR: &str = "window_false_color";
 
-const TRAY_QUIT: &str = "QUIT";
-const TRAY_VECTOR_SCOPE: &str = "VECTOR_SCOPE";
-const TRAY_WAVEFORM: &str = "WAVEFORM";
-const TRAY_CAPTURE_AREA_SETTING: &str = "CAPTURE_AREA_SETTING";
+/// starts the control socket at launch, at the given path or the default one when empty
+const CONTROL_SOCKET_ENV: &str = "VECTOR_SCOPE_CONTROL_SOCKET";
 
 #[tauri::command]
 fn get_mouse_position() -> (i32, i32) {
@@ -70,6 +83,66 @@ fn create_waveform_window(app_handle: tauri::AppHandle) {
     };
 }
 
+fn create_loupe_window(app_handle: tauri::AppHandle) {
+    let _loupe_window = match tauri::WindowBuilder::new(
+        &app_handle,
+        WINDOW_LABEL_LOUPE,
+        tauri::WindowUrl::App((HTML_DIR.to_owned() + "loupe/loupe.html").into()),
+    )
+    .build()
+    {
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("{_err}");
+            let _ = app_handle
+                .get_window(WINDOW_LABEL_LOUPE)
+                .expect("loupe window not found")
+                .set_focus();
+        }
+        Ok(_ok) => {}
+    };
+}
+
+fn create_palette_window(app_handle: tauri::AppHandle) {
+    let _palette_window = match tauri::WindowBuilder::new(
+        &app_handle,
+        WINDOW_LABEL_PALETTE,
+        tauri::WindowUrl::App((HTML_DIR.to_owned() + "palette/palette.html").into()),
+    )
+    .build()
+    {
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("{_err}");
+            let _ = app_handle
+                .get_window(WINDOW_LABEL_PALETTE)
+                .expect("palette window not found")
+                .set_focus();
+        }
+        Ok(_ok) => {}
+    };
+}
+
+fn create_false_color_window(app_handle: tauri::AppHandle) {
+    let _false_color_window = match tauri::WindowBuilder::new(
+        &app_handle,
+        WINDOW_LABEL_FALSE_COLOR,
+        tauri::WindowUrl::App((HTML_DIR.to_owned() + "false_color/false_color.html").into()),
+    )
+    .build()
+    {
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("{_err}");
+            let _ = app_handle
+                .get_window(WINDOW_LABEL_FALSE_COLOR)
+                .expect("false color window not found")
+                .set_focus();
+        }
+        Ok(_ok) => {}
+    };
+}
+
 #[tauri::command]
 fn create_capture_area_setting_window(app_handle: tauri::AppHandle) {
     let _capture_area_setting_window = match tauri::WindowBuilder::new(
@@ -94,21 +167,298 @@ fn create_capture_area_setting_window(app_handle: tauri::AppHandle) {
     };
 }
 
+fn pick_source_image(app_handle: tauri::AppHandle) {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("Image", image_file::SUPPORTED_EXTENSIONS)
+        .pick_file(move |path| {
+            if let Some(path) = path {
+                if let Err(_err) = main_view_model::open_image_file(
+                    app_handle,
+                    path.to_string_lossy().into_owned(),
+                ) {
+                    #[cfg(debug_assertions)]
+                    println!("{_err}");
+                }
+            }
+        });
+}
+
+fn pick_source_video(app_handle: tauri::AppHandle) {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("YUV4MPEG2", yuv_video::SUPPORTED_EXTENSIONS)
+        .pick_file(move |path| {
+            if let Some(path) = path {
+                if let Err(_err) = main_view_model::open_video_file(
+                    app_handle,
+                    path.to_string_lossy().into_owned(),
+                    None,
+                ) {
+                    #[cfg(debug_assertions)]
+                    println!("{_err}");
+                }
+            }
+        });
+}
+
+fn analyze_clipboard_image(app_handle: tauri::AppHandle) {
+    if let Err(err) = main_view_model::analyze_clipboard_image(app_handle.clone()) {
+        let _ = tauri::api::notification::Notification::new(
+            &app_handle.config().tauri.bundle.identifier,
+        )
+        .title("Analyze clipboard image")
+        .body(err)
+        .show();
+    }
+}
+
+fn pick_reference_image(app_handle: tauri::AppHandle) {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("Image", image_file::SUPPORTED_EXTENSIONS)
+        .pick_file(move |path| {
+            if let Some(path) = path {
+                if let Err(_err) = main_view_model::load_reference_image(
+                    app_handle,
+                    path.to_string_lossy().into_owned(),
+                ) {
+                    #[cfg(debug_assertions)]
+                    println!("{_err}");
+                }
+            }
+        });
+}
+
+fn pick_icc_profile(app_handle: tauri::AppHandle) {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("ICC profile", &["icc", "icm"])
+        .pick_file(move |path| {
+            if let Some(path) = path {
+                set_input_color_space(
+                    app_handle,
+                    InputColorSpace::Icc(path.to_string_lossy().into_owned()),
+                );
+            }
+        });
+}
+
+fn set_input_color_space(app_handle: tauri::AppHandle, space: InputColorSpace) {
+    if let Err(_err) = main_view_model::set_input_color_space(app_handle, space) {
+        #[cfg(debug_assertions)]
+        println!("{_err}");
+    }
+}
+
+fn set_tray_icon_mode(app_handle: tauri::AppHandle, mode: TrayIconMode) {
+    if let Err(_err) = main_view_model::set_tray_icon_mode(app_handle, mode) {
+        #[cfg(debug_assertions)]
+        println!("{_err}");
+    }
+}
+
+fn pick_lut(app_handle: tauri::AppHandle) {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .add_filter("Cube LUT", &["cube"])
+        .pick_file(move |path| {
+            if let Some(path) = path {
+                if let Err(_err) =
+                    main_view_model::load_lut(app_handle, path.to_string_lossy().into_owned())
+                {
+                    #[cfg(debug_assertions)]
+                    println!("{_err}");
+                }
+            }
+        });
+}
+
+fn pick_export_directory() {
+    tauri::api::dialog::FileDialogBuilder::new()
+        .set_directory(main_view_model::get_export_directory())
+        .pick_folder(|path| {
+            if let Some(path) = path {
+                main_view_model::set_export_directory(path.to_string_lossy().into_owned());
+            }
+        });
+}
+
+fn toggle_recording(app_handle: tauri::AppHandle) {
+    if main_view_model::is_recording() {
+        let _frame_count = main_view_model::stop_recording(app_handle);
+        #[cfg(debug_assertions)]
+        println!("recorded {_frame_count} frames");
+        return;
+    }
+    tauri::api::dialog::FileDialogBuilder::new()
+        .set_file_name("statistics.csv")
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON Lines", &["jsonl"])
+        .save_file(move |path| {
+            if let Some(path) = path {
+                if let Err(_err) = main_view_model::start_recording(
+                    app_handle,
+                    path.to_string_lossy().into_owned(),
+                    None,
+                ) {
+                    #[cfg(debug_assertions)]
+                    println!("{_err}");
+                }
+            }
+        });
+}
+
+fn toggle_scope_server(app_handle: tauri::AppHandle) {
+    if main_view_model::is_scope_server_running() {
+        main_view_model::stop_scope_server(app_handle);
+        return;
+    }
+    match main_view_model::start_scope_server(app_handle) {
+        Ok(_url) => {
+            #[cfg(debug_assertions)]
+            println!("streaming scopes at {_url}");
+        }
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("{_err}");
+        }
+    }
+}
+
+fn toggle_control_socket(app_handle: tauri::AppHandle, path: Option<String>) {
+    if main_view_model::is_control_socket_running() {
+        main_view_model::stop_control_socket(app_handle);
+        return;
+    }
+    match main_view_model::start_control_socket(app_handle, path) {
+        Ok(_path) => {
+            #[cfg(debug_assertions)]
+            println!("control socket at {_path}");
+        }
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("{_err}");
+        }
+    }
+}
+
+fn check_item(id: &str) -> CustomMenuItem {
+    CustomMenuItem::new(id, tray_item_title(id))
+}
+
+/// Closes the scope window opened last, or opens it again
+fn toggle_last_used_scope(app_handle: tauri::AppHandle) {
+    let label = main_view_model::get_last_used_scope();
+    if let Some(window) = app_handle.get_window(label) {
+        let _ = window.close();
+        return;
+    }
+    match label {
+        WINDOW_LABEL_WAVEFORM => create_waveform_window(app_handle),
+        WINDOW_LABEL_FALSE_COLOR => create_false_color_window(app_handle),
+        _ => create_vector_scope_window(app_handle),
+    }
+}
+
 fn main() {
     let quit = CustomMenuItem::new(TRAY_QUIT, "Quit");
     let vector_scope = CustomMenuItem::new(TRAY_VECTOR_SCOPE, "Vector Scope");
     let waveform = CustomMenuItem::new(TRAY_WAVEFORM, "Waveform");
-    let capture_area_setting =
-        CustomMenuItem::new(TRAY_CAPTURE_AREA_SETTING, "Capture area setting");
+    let loupe = CustomMenuItem::new(TRAY_LOUPE, "Loupe");
+    let palette = CustomMenuItem::new(TRAY_PALETTE, "Palette");
+    let false_color = CustomMenuItem::new(TRAY_FALSE_COLOR, "False color");
+    let auto_refresh = check_item(TRAY_AUTO_REFRESH);
+    let hold = check_item(TRAY_HOLD);
+    let refresh_interval_menu = TRAY_REFRESH_INTERVALS
+        .iter()
+        .fold(SystemTrayMenu::new(), |menu, (id, ..)| {
+            menu.add_item(check_item(id))
+        });
+    let capture_area_menu = SystemTrayMenu::new()
+        .add_item(check_item(TRAY_CAPTURE_FULL_SCREEN))
+        .add_item(check_item(TRAY_CAPTURE_AREA_SETTING))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(check_item(TRAY_EXCLUDE_OWN_WINDOWS));
+    let source_menu = SystemTrayMenu::new()
+        .add_item(check_item(TRAY_SOURCE_SCREEN))
+        .add_item(check_item(TRAY_SOURCE_OPEN_IMAGE))
+        .add_item(check_item(TRAY_SOURCE_OPEN_VIDEO))
+        .add_item(check_item(TRAY_SOURCE_CLIPBOARD))
+        .add_native_item(SystemTrayMenuItem::Separator);
+    let source_menu = TRAY_SOURCE_PATTERNS
+        .iter()
+        .fold(source_menu, |menu, (id, _)| menu.add_item(check_item(id)));
+    let reference_menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(
+            TRAY_REFERENCE_FREEZE,
+            "Freeze current frame",
+        ))
+        .add_item(CustomMenuItem::new(TRAY_REFERENCE_LOAD, "Load image..."))
+        .add_item(CustomMenuItem::new(TRAY_REFERENCE_CLEAR, "Clear"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(check_item(TRAY_REFERENCE_OVERLAY))
+        .add_item(check_item(TRAY_REFERENCE_SPLIT));
+    let tray_icon_menu = TRAY_ICON_MODES
+        .iter()
+        .fold(SystemTrayMenu::new(), |menu, (id, _)| {
+            menu.add_item(check_item(id))
+        });
+    let waveform_mode_menu = TRAY_WAVEFORM_MODES
+        .iter()
+        .fold(SystemTrayMenu::new(), |menu, (id, _)| {
+            menu.add_item(check_item(id))
+        });
+    let color_space_menu = TRAY_COLOR_SPACES
+        .iter()
+        .fold(SystemTrayMenu::new(), |menu, (id, _)| {
+            menu.add_item(check_item(id))
+        })
+        .add_item(check_item(TRAY_COLOR_SPACE_ICC));
+    let lut_menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(TRAY_LUT_LOAD, "Load .cube..."))
+        .add_item(CustomMenuItem::new(TRAY_LUT_CLEAR, "Clear"));
+    let export = CustomMenuItem::new(TRAY_EXPORT, "Export");
+    let export_directory = CustomMenuItem::new(TRAY_EXPORT_DIRECTORY, "Export folder...");
+    let recording = CustomMenuItem::new(TRAY_RECORDING, "Record statistics...");
+    let legal_range = check_item(TRAY_LEGAL_RANGE);
+    let scope_server = CustomMenuItem::new(TRAY_SCOPE_SERVER, "Stream scopes");
+    let control_socket = check_item(TRAY_CONTROL_SOCKET);
     let tray_menu = SystemTrayMenu::new()
         .add_item(quit)
         .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(vector_scope)
         .add_item(waveform)
-        .add_item(capture_area_setting);
+        .add_item(loupe)
+        .add_item(palette)
+        .add_item(false_color)
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(auto_refresh)
+        .add_submenu(SystemTraySubmenu::new(
+            "Refresh interval",
+            refresh_interval_menu,
+        ))
+        .add_item(hold)
+        .add_submenu(SystemTraySubmenu::new("Capture area", capture_area_menu))
+        .add_submenu(SystemTraySubmenu::new("Source", source_menu))
+        .add_submenu(SystemTraySubmenu::new("Reference", reference_menu))
+        .add_submenu(SystemTraySubmenu::new(
+            "Input color space",
+            color_space_menu,
+        ))
+        .add_submenu(SystemTraySubmenu::new("Waveform mode", waveform_mode_menu))
+        .add_submenu(SystemTraySubmenu::new("Tray icon", tray_icon_menu))
+        .add_submenu(SystemTraySubmenu::new("LUT", lut_menu))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(export)
+        .add_item(export_directory)
+        .add_item(recording)
+        .add_item(legal_range)
+        .add_item(scope_server)
+        .add_item(control_socket);
+
+    let system_tray = SystemTray::new().with_menu(tray_menu);
+    // the menu stays on the right click
+    #[cfg(target_os = "macos")]
+    let system_tray = system_tray.with_menu_on_left_click(false);
 
     let mut app = tauri::Builder::default()
-        .system_tray(SystemTray::new().with_menu(tray_menu))
+        .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
             SystemTrayEvent::LeftClick {
                 position: _,
@@ -117,6 +467,7 @@ fn main() {
             } => {
                 #[cfg(debug_assertions)]
                 println!("system tray received a left click");
+                toggle_last_used_scope(app.app_handle());
             }
             SystemTrayEvent::RightClick {
                 position: _,
@@ -134,27 +485,182 @@ fn main() {
                 #[cfg(debug_assertions)]
                 println!("system tray received a double click");
             }
-            SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
-                TRAY_QUIT => {
-                    std::process::exit(0);
-                }
-                TRAY_VECTOR_SCOPE => {
-                    #[cfg(debug_assertions)]
-                    println!("system tray VECTOR_SCOPE click");
-                    create_vector_scope_window(app.app_handle());
+            SystemTrayEvent::MenuItemClick { id, .. } => {
+                match id.as_str() {
+                    TRAY_QUIT => {
+                        std::process::exit(0);
+                    }
+                    TRAY_VECTOR_SCOPE => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray VECTOR_SCOPE click");
+                        create_vector_scope_window(app.app_handle());
+                    }
+                    TRAY_WAVEFORM => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray WAVEFORM click");
+                        create_waveform_window(app.app_handle());
+                    }
+                    TRAY_LOUPE => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray LOUPE click");
+                        create_loupe_window(app.app_handle());
+                    }
+                    TRAY_PALETTE => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray PALETTE click");
+                        create_palette_window(app.app_handle());
+                    }
+                    TRAY_FALSE_COLOR => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray FALSE_COLOR click");
+                        create_false_color_window(app.app_handle());
+                    }
+                    TRAY_AUTO_REFRESH => {
+                        main_view_model::set_manual_mode(
+                            app.app_handle(),
+                            !main_view_model::is_manual_mode(),
+                        );
+                    }
+                    TRAY_HOLD => {
+                        main_view_model::set_hold(app.app_handle(), !main_view_model::is_hold_on());
+                    }
+                    TRAY_CAPTURE_FULL_SCREEN => {
+                        main_view_model::initialize_capture_area(app.app_handle());
+                    }
+                    TRAY_WAVEFORM_MODE_LUMA => {
+                        main_view_model::set_waveform_mode(app.app_handle(), WaveformMode::Luma);
+                    }
+                    TRAY_WAVEFORM_MODE_LINEAR => {
+                        main_view_model::set_waveform_mode(app.app_handle(), WaveformMode::Linear);
+                    }
+                    TRAY_WAVEFORM_MODE_PQ => {
+                        main_view_model::set_waveform_mode(app.app_handle(), WaveformMode::Pq);
+                    }
+                    TRAY_WAVEFORM_MODE_HLG => {
+                        main_view_model::set_waveform_mode(app.app_handle(), WaveformMode::Hlg);
+                    }
+                    TRAY_ICON_DEFAULT => {
+                        set_tray_icon_mode(app.app_handle(), TrayIconMode::Default);
+                    }
+                    TRAY_ICON_AVERAGE_COLOR => {
+                        set_tray_icon_mode(app.app_handle(), TrayIconMode::AverageColor);
+                    }
+                    TRAY_ICON_HISTOGRAM => {
+                        set_tray_icon_mode(app.app_handle(), TrayIconMode::Histogram);
+                    }
+                    TRAY_SOURCE_SCREEN => {
+                        main_view_model::use_screen_source(app.app_handle());
+                    }
+                    TRAY_SOURCE_OPEN_IMAGE => {
+                        pick_source_image(app.app_handle());
+                    }
+                    TRAY_SOURCE_OPEN_VIDEO => {
+                        pick_source_video(app.app_handle());
+                    }
+                    TRAY_SOURCE_CLIPBOARD => {
+                        analyze_clipboard_image(app.app_handle());
+                    }
+                    TRAY_SOURCE_PATTERN_COLOR_BARS => {
+                        main_view_model::open_test_pattern(
+                            app.app_handle(),
+                            TestPattern::ColorBars,
+                        );
+                    }
+                    TRAY_SOURCE_PATTERN_GRAY_RAMP => {
+                        main_view_model::open_test_pattern(app.app_handle(), TestPattern::GrayRamp);
+                    }
+                    TRAY_REFERENCE_FREEZE => {
+                        main_view_model::freeze_reference_image(app.app_handle());
+                    }
+                    TRAY_REFERENCE_LOAD => {
+                        pick_reference_image(app.app_handle());
+                    }
+                    TRAY_REFERENCE_OVERLAY => {
+                        main_view_model::set_reference_mode(
+                            app.app_handle(),
+                            ReferenceMode::Overlay,
+                        );
+                    }
+                    TRAY_REFERENCE_SPLIT => {
+                        main_view_model::set_reference_mode(app.app_handle(), ReferenceMode::Split);
+                    }
+                    TRAY_REFERENCE_CLEAR => {
+                        main_view_model::clear_reference_image(app.app_handle());
+                    }
+                    TRAY_COLOR_SPACE_SRGB => {
+                        set_input_color_space(app.app_handle(), InputColorSpace::Srgb);
+                    }
+                    TRAY_COLOR_SPACE_DISPLAY_P3 => {
+                        set_input_color_space(app.app_handle(), InputColorSpace::DisplayP3);
+                    }
+                    TRAY_COLOR_SPACE_ADOBE_RGB => {
+                        set_input_color_space(app.app_handle(), InputColorSpace::AdobeRgb);
+                    }
+                    TRAY_COLOR_SPACE_REC709 => {
+                        set_input_color_space(app.app_handle(), InputColorSpace::Rec709);
+                    }
+                    TRAY_COLOR_SPACE_REC2020 => {
+                        set_input_color_space(app.app_handle(), InputColorSpace::Rec2020);
+                    }
+                    TRAY_COLOR_SPACE_ICC => {
+                        pick_icc_profile(app.app_handle());
+                    }
+                    TRAY_LUT_LOAD => {
+                        pick_lut(app.app_handle());
+                    }
+                    TRAY_LUT_CLEAR => {
+                        main_view_model::clear_lut(app.app_handle());
+                    }
+                    TRAY_EXPORT => match main_view_model::export_current(app.app_handle()) {
+                        Ok(_folder) => {
+                            #[cfg(debug_assertions)]
+                            println!("exported to {_folder}");
+                        }
+                        Err(_err) => {
+                            #[cfg(debug_assertions)]
+                            println!("{_err}");
+                        }
+                    },
+                    TRAY_EXPORT_DIRECTORY => {
+                        pick_export_directory();
+                    }
+                    TRAY_RECORDING => {
+                        toggle_recording(app.app_handle());
+                    }
+                    TRAY_LEGAL_RANGE => {
+                        main_view_model::set_legal_range_check(
+                            app.app_handle(),
+                            !main_view_model::is_legal_range_check_on(),
+                        );
+                    }
+                    TRAY_SCOPE_SERVER => {
+                        toggle_scope_server(app.app_handle());
+                    }
+                    TRAY_CONTROL_SOCKET => {
+                        toggle_control_socket(app.app_handle(), None);
+                    }
+                    TRAY_CAPTURE_AREA_SETTING => {
+                        #[cfg(debug_assertions)]
+                        println!("system tray CAPTURE_AREA_SETTING click");
+                        create_capture_area_setting_window(app.app_handle());
+                    }
+                    TRAY_EXCLUDE_OWN_WINDOWS => {
+                        main_view_model::set_exclude_own_windows(
+                            app.app_handle(),
+                            !main_view_model::is_excluding_own_windows(),
+                        );
+                    }
+                    id => {
+                        if let Some((_, _, interval)) = TRAY_REFRESH_INTERVALS
+                            .iter()
+                            .find(|(interval_id, ..)| *interval_id == id)
+                        {
+                            main_view_model::set_refresh_interval(app.app_handle(), *interval);
+                        }
+                    }
                 }
-                TRAY_WAVEFORM => {
-                    #[cfg(debug_assertions)]
-                    println!("system tray WAVEFORM click");
-                    create_waveform_window(app.app_handle());
-                }
-                TRAY_CAPTURE_AREA_SETTING => {
-                    #[cfg(debug_assertions)]
-                    println!("system tray CAPTURE_AREA_SETTING click");
-                    create_capture_area_setting_window(app.app_handle());
-                }
-                _ => {}
-            },
+                main_view_model::update_tray_menu(&app.app_handle());
+            }
             _ => {}
         })
         .invoke_handler(tauri::generate_handler![
@@ -162,9 +668,79 @@ fn main() {
             get_mouse_position,
             main_view_model::initialize_capture_area,
             main_view_model::set_capture_area,
+            main_view_model::snap_capture_area_to_window,
+            main_view_model::set_exclude_own_windows,
+            main_view_model::is_excluding_own_windows,
             main_view_model::set_is_vector_scope_window_open,
             main_view_model::set_is_waveform_window_open,
+            main_view_model::set_is_loupe_window_open,
+            main_view_model::set_loupe_zoom,
+            main_view_model::set_is_palette_window_open,
+            main_view_model::set_palette_color_count,
+            main_view_model::set_is_false_color_window_open,
+            main_view_model::set_false_color_settings,
+            main_view_model::get_false_color_settings,
+            main_view_model::get_palette,
+            main_view_model::export_palette,
+            main_view_model::open_image_file,
+            main_view_model::open_video_file,
+            main_view_model::play_video,
+            main_view_model::pause_video,
+            main_view_model::is_video_playing,
+            main_view_model::seek_video,
+            main_view_model::step_video,
+            main_view_model::analyze_clipboard_image,
+            main_view_model::open_test_pattern,
+            main_view_model::use_screen_source,
+            main_view_model::get_frame_source,
+            main_view_model::load_reference_image,
+            main_view_model::freeze_reference_image,
+            main_view_model::clear_reference_image,
+            main_view_model::set_reference_mode,
+            main_view_model::set_input_color_space,
+            main_view_model::get_input_color_space,
+            main_view_model::set_luminance_settings,
+            main_view_model::get_luminance_settings,
+            main_view_model::set_hdr_mode,
+            main_view_model::get_hdr_mode,
+            main_view_model::reset_hdr_statistics,
+            main_view_model::load_lut,
+            main_view_model::clear_lut,
+            main_view_model::get_lut_info,
+            main_view_model::set_lut_interpolation,
+            main_view_model::set_hold,
+            main_view_model::step_frame_history,
+            main_view_model::get_frame_history_status,
+            main_view_model::set_frame_history_capacity,
+            main_view_model::set_peak_hold,
+            main_view_model::reset_peak_hold,
+            main_view_model::set_export_directory,
+            main_view_model::get_export_directory,
+            main_view_model::set_export_formats,
+            main_view_model::export_current,
+            main_view_model::start_recording,
+            main_view_model::stop_recording,
+            main_view_model::is_recording,
+            main_view_model::start_scope_server,
+            main_view_model::stop_scope_server,
+            main_view_model::is_scope_server_running,
+            main_view_model::set_scope_server_settings,
+            main_view_model::get_scope_server_settings,
+            main_view_model::start_control_socket,
+            main_view_model::stop_control_socket,
+            main_view_model::is_control_socket_running,
+            main_view_model::set_legal_range_check,
+            main_view_model::set_legal_range_thresholds,
+            main_view_model::get_legal_range_thresholds,
             main_view_model::set_manual_mode,
+            main_view_model::is_manual_mode,
+            main_view_model::set_refresh_interval,
+            main_view_model::get_refresh_interval,
+            main_view_model::is_hold_on,
+            main_view_model::set_tray_icon_mode,
+            main_view_model::get_tray_icon_mode,
+            main_view_model::get_keymap,
+            main_view_model::set_shortcut,
             main_view_model::one_shot_emit,
         ])
         .build(tauri::generate_context!())
@@ -173,6 +749,18 @@ fn main() {
     #[cfg(target_os = "macos")]
     app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+    main_view_model::update_tray_menu(&app.app_handle());
+    for _err in main_view_model::load_settings(&app.app_handle()) {
+        #[cfg(debug_assertions)]
+        println!("{_err}");
+    }
+
+    // empty for the default path
+    if let Some(path) = std::env::var_os(CONTROL_SOCKET_ENV) {
+        let path = (!path.is_empty()).then(|| path.to_string_lossy().into_owned());
+        toggle_control_socket(app.app_handle(), path);
+    }
+
     app.run(|_app_handle, event| match event {
         tauri::RunEvent::ExitRequested { api, .. } => {
             api.prevent_exit();
@@ -1,26 +1,90 @@
 use crate::graph_plotter;
+use crate::model::capture_area::CaptureArea;
+use crate::model::clipboard_image;
+use crate::model::color_palette;
+use crate::model::color_palette::PaletteColor;
+use crate::model::color_space::{ColorConversion, InputColorSpace};
+use crate::model::color_statistics;
+#[cfg(unix)]
+use crate::model::control_socket;
+#[cfg(unix)]
+use crate::model::control_socket::ControlSocket;
+use crate::model::display_layout::{CoordinateSpace, DisplayLayout};
+use crate::model::false_color::FalseColorSettings;
+use crate::model::frame::Frame;
+use crate::model::frame_history::{FrameHistory, FrameHistoryStatus};
+use crate::model::frame_source::{FrameSource, FrameSourceInfo, VideoFrame, VideoSource};
+use crate::model::graph_plotter::ReferenceMode;
+use crate::model::hdr::{HdrMode, HdrStatistics, LightLevelMeter, NitsConverter};
+use crate::model::image_file;
+use crate::model::json_rpc;
+use crate::model::json_rpc::RpcError;
+use crate::model::keymap::{KeyAction, Keymap};
+use crate::model::legal_range;
+use crate::model::legal_range::{LegalRangeResult, LegalRangeThresholds};
+use crate::model::luminance::{LuminanceMeter, LuminanceMode, LuminanceSettings};
+use crate::model::lut::{Lut, LutInfo, LutInterpolation};
+use crate::model::peak_hold::PeakHold;
+use crate::model::scope_export;
+use crate::model::scope_export::ExportFormat;
+use crate::model::scope_server::{ScopeServer, ScopeServerSettings, ScopeSnapshot};
+use crate::model::settings::Settings;
+use crate::model::statistics_recorder::{RecordingFormat, StatisticsRecorder};
+use crate::model::test_pattern::TestPattern;
+use crate::model::tray_icon;
+use crate::model::tray_icon::{TrayIconImage, TrayIconMode};
+use crate::model::window_snap;
 use crate::model::worker_thread_base;
 use crate::model::worker_thread_base::WorkerTrait;
+use crate::model::yuv_video::{YuvFormat, YuvFrame, YuvVideo};
+use crate::mouse_info;
 use crate::screenshot_capture;
+use crate::tray_menu;
 use base64::{
     alphabet,
     engine::{self, general_purpose},
     Engine as _,
 };
 use once_cell::sync::Lazy;
-use screenshots::Image;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::OnceLock;
 use std::sync::RwLock;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
+use tauri::GlobalShortcutManager;
 use tauri::Manager;
 
 const PREFIX_DATA_URI: &str = "data:image/png;base64,";
 const EVENT_NAME_VECTOR_SCOPE: &str = "event-vector-scope";
 const EVENT_NAME_WAVEFORM: &str = "event-waveform";
+const EVENT_NAME_LOUPE: &str = "event-loupe";
+const EVENT_NAME_PALETTE: &str = "event-palette";
+const EVENT_NAME_LEGAL_RANGE: &str = "event-legal-range";
+const EVENT_NAME_FALSE_COLOR: &str = "event-false-color";
+const EVENT_NAME_HDR_STATISTICS: &str = "event-hdr-statistics";
+const LEGAL_RANGE_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(60);
+const TRAY_ICON_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+const LOUPE_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+const REFRESH_INTERVAL_DEFAULT_MS: u64 = 1000;
+const REFRESH_INTERVAL_MIN_MS: u64 = 100;
+const LOUPE_ZOOM_DEFAULT: u32 = 8;
+const PALETTE_COLOR_COUNT_DEFAULT: usize = 8;
+const FRAME_HISTORY_CAPACITY_DEFAULT: usize = 10;
+const EXPORT_DIRECTORY_NAME: &str = "VectorScope";
+const EXPORT_NAME_VECTOR_SCOPE: &str = "vector_scope";
+const EXPORT_NAME_WAVEFORM: &str = "waveform";
+const EXPORT_NAME_PALETTE: &str = "palette";
+const EXPORT_NAME_FALSE_COLOR: &str = "false_color";
 
 static BASE64_ENGINE: OnceLock<engine::GeneralPurpose> = OnceLock::new();
 #[cold]
@@ -28,8 +92,11 @@ fn init_base64_engine() -> engine::GeneralPurpose {
     engine::GeneralPurpose::new(&alphabet::STANDARD, general_purpose::NO_PAD)
 }
 
-static CAPTURE_AREA_TOP_LEFT: Lazy<RwLock<(i32, i32)>> = Lazy::new(|| RwLock::new((0, 0)));
-static CAPTURE_AREA_BOTTOM_RIGHT: Lazy<RwLock<(i32, i32)>> = Lazy::new(|| RwLock::new((0, 0)));
+/// `None` captures the entire screen
+static CAPTURE_AREA: Lazy<RwLock<Option<CaptureArea>>> = Lazy::new(|| RwLock::new(None));
+/// leaves the app's own windows out of screen captures
+static IS_EXCLUDING_OWN_WINDOWS: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(true)));
 
 static IS_VECTOR_SCOPE_WINDOW_OPEN: Lazy<Arc<AtomicBool>> =
     Lazy::new(|| Arc::new(AtomicBool::new(false)));
@@ -37,6 +104,93 @@ static IS_WAVEFORM_WINDOW_OPEN: Lazy<Arc<AtomicBool>> =
     Lazy::new(|| Arc::new(AtomicBool::new(false)));
 static IS_MANUAL_REFRESH_MODE_ON: Lazy<Arc<AtomicBool>> =
     Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static REFRESH_INTERVAL_MS: Lazy<Arc<AtomicU64>> =
+    Lazy::new(|| Arc::new(AtomicU64::new(REFRESH_INTERVAL_DEFAULT_MS)));
+/// label of the scope window opened last, toggled by a left click on the tray icon
+static LAST_USED_SCOPE: Lazy<RwLock<&'static str>> =
+    Lazy::new(|| RwLock::new(super::WINDOW_LABEL_VECTOR_SCOPE));
+static IS_LOUPE_WINDOW_OPEN: Lazy<Arc<AtomicBool>> = Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static LOUPE_ZOOM: Lazy<Arc<AtomicU32>> =
+    Lazy::new(|| Arc::new(AtomicU32::new(LOUPE_ZOOM_DEFAULT)));
+static IS_PALETTE_WINDOW_OPEN: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static PALETTE_COLOR_COUNT: Lazy<Arc<AtomicUsize>> =
+    Lazy::new(|| Arc::new(AtomicUsize::new(PALETTE_COLOR_COUNT_DEFAULT)));
+static IS_FALSE_COLOR_WINDOW_OPEN: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static FALSE_COLOR_SETTINGS: Lazy<RwLock<FalseColorSettings>> =
+    Lazy::new(|| RwLock::new(FalseColorSettings::default()));
+static LAST_PALETTE: Lazy<RwLock<Vec<PaletteColor>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+static FRAME_SOURCE: Lazy<RwLock<FrameSource>> = Lazy::new(|| RwLock::new(FrameSource::Screen));
+
+static IS_HOLD_ON: Lazy<Arc<AtomicBool>> = Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static FRAME_HISTORY: Lazy<RwLock<FrameHistory>> =
+    Lazy::new(|| RwLock::new(FrameHistory::new(FRAME_HISTORY_CAPACITY_DEFAULT)));
+static IS_PEAK_HOLD_ON: Lazy<Arc<AtomicBool>> = Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static PEAK_HOLD_VECTOR_SCOPE: Lazy<RwLock<PeakHold>> = Lazy::new(|| RwLock::new(PeakHold::new()));
+static PEAK_HOLD_WAVEFORM: Lazy<RwLock<PeakHold>> = Lazy::new(|| RwLock::new(PeakHold::new()));
+
+static LAST_VECTOR_SCOPE_IMAGE: Lazy<RwLock<Vec<u8>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static LAST_WAVEFORM_IMAGE: Lazy<RwLock<Vec<u8>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static LAST_PALETTE_IMAGE: Lazy<RwLock<Vec<u8>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static LAST_FALSE_COLOR_IMAGE: Lazy<RwLock<Vec<u8>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+static EXPORT_DIRECTORY: Lazy<RwLock<PathBuf>> =
+    Lazy::new(|| RwLock::new(default_export_directory()));
+static EXPORT_FORMATS: Lazy<RwLock<Vec<ExportFormat>>> =
+    Lazy::new(|| RwLock::new(vec![ExportFormat::Png]));
+
+static STATISTICS_RECORDER: Lazy<RwLock<Option<StatisticsRecorder>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static KEYMAP: Lazy<RwLock<Keymap>> = Lazy::new(|| RwLock::new(Keymap::default()));
+
+static TRAY_ICON_MODE: Lazy<RwLock<TrayIconMode>> =
+    Lazy::new(|| RwLock::new(TrayIconMode::default()));
+/// `None` draws the icon with the next frame
+static LAST_TRAY_ICON_UPDATE: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+#[cfg(unix)]
+static CONTROL_SOCKET: Lazy<RwLock<Option<ControlSocket>>> = Lazy::new(|| RwLock::new(None));
+
+static SCOPE_SERVER: Lazy<RwLock<Option<ScopeServer>>> = Lazy::new(|| RwLock::new(None));
+static SCOPE_SERVER_SETTINGS: Lazy<RwLock<ScopeServerSettings>> =
+    Lazy::new(|| RwLock::new(ScopeServerSettings::default()));
+
+static IS_LEGAL_RANGE_CHECK_ON: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static IS_LEGAL_RANGE_EXCEEDED: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+static LEGAL_RANGE_THRESHOLDS: Lazy<RwLock<LegalRangeThresholds>> =
+    Lazy::new(|| RwLock::new(LegalRangeThresholds::default()));
+static LAST_LEGAL_RANGE_NOTIFICATION: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+static REFERENCE_IMAGE: Lazy<RwLock<Option<Arc<Frame>>>> = Lazy::new(|| RwLock::new(None));
+static REFERENCE_MODE: Lazy<RwLock<ReferenceMode>> =
+    Lazy::new(|| RwLock::new(ReferenceMode::Overlay));
+
+static INPUT_COLOR_SPACE: Lazy<RwLock<InputColorSpace>> =
+    Lazy::new(|| RwLock::new(InputColorSpace::default()));
+static COLOR_CONVERSION: Lazy<RwLock<ColorConversion>> = Lazy::new(|| {
+    RwLock::new(
+        ColorConversion::new(&InputColorSpace::default()).expect("Failed to create conversion"),
+    )
+});
+static LUMINANCE_SETTINGS: Lazy<RwLock<LuminanceSettings>> =
+    Lazy::new(|| RwLock::new(LuminanceSettings::default()));
+/// built with the decode table of `LUMINANCE_SETTINGS` when they change
+static LUMINANCE_METER: Lazy<RwLock<LuminanceMeter>> =
+    Lazy::new(|| RwLock::new(LuminanceMeter::new(&LuminanceSettings::default())));
+static HDR_MODE: Lazy<RwLock<HdrMode>> = Lazy::new(|| RwLock::new(HdrMode::Off));
+/// built with the decode table of `HDR_MODE` when it changes
+static NITS_CONVERTER: Lazy<RwLock<NitsConverter>> =
+    Lazy::new(|| RwLock::new(NitsConverter::new(HdrMode::Off)));
+static LIGHT_LEVEL_METER: Lazy<RwLock<LightLevelMeter>> =
+    Lazy::new(|| RwLock::new(LightLevelMeter::new()));
+static CURRENT_LUT: Lazy<RwLock<Option<Lut>>> = Lazy::new(|| RwLock::new(None));
+static LUT_INTERPOLATION: Lazy<RwLock<LutInterpolation>> =
+    Lazy::new(|| RwLock::new(LutInterpolation::Tetrahedral));
 
 static THREAD_IMAGE_PROCESS: Lazy<RwLock<ImageProcessThread>> =
     Lazy::new(|| RwLock::new(create_image_process_thread()));
@@ -65,8 +219,98 @@ impl worker_thread_base::WorkerTrait for ImageProcessThread {
             if !keep_alive.load(Ordering::Relaxed) {
                 break;
             }
-            process_and_emit_image(&app_handle);
-            thread::sleep(Duration::from_secs(1));
+            // a held frame or a still source does not change, so there is nothing to refresh
+            if !IS_HOLD_ON.load(Ordering::Relaxed) && FRAME_SOURCE.read().unwrap().is_live() {
+                process_and_emit_image(&app_handle);
+            }
+            thread::sleep(Duration::from_millis(
+                REFRESH_INTERVAL_MS.load(Ordering::Relaxed),
+            ));
+        });
+    }
+    fn stop(&self) {
+        self.worker_thread
+            .keep_alive
+            .store(false, Ordering::Relaxed);
+    }
+}
+
+static THREAD_VIDEO_PLAYBACK: Lazy<RwLock<VideoPlaybackThread>> =
+    Lazy::new(|| RwLock::new(create_video_playback_thread()));
+
+pub struct VideoPlaybackThread {
+    pub worker_thread: worker_thread_base::Worker,
+}
+#[cold]
+fn create_video_playback_thread() -> VideoPlaybackThread {
+    VideoPlaybackThread::new()
+}
+
+impl VideoPlaybackThread {
+    pub fn new() -> Self {
+        Self {
+            worker_thread: worker_thread_base::Worker::new(),
+        }
+    }
+}
+
+impl worker_thread_base::WorkerTrait for VideoPlaybackThread {
+    fn run(&self, app_handle: tauri::AppHandle) {
+        let keep_alive = Arc::clone(&self.worker_thread.keep_alive);
+        keep_alive.store(true, Ordering::Relaxed);
+        thread::spawn(move || loop {
+            if !keep_alive.load(Ordering::Relaxed) {
+                break;
+            }
+            let started_at = Instant::now();
+            match advance_video() {
+                Some(frame_interval) => {
+                    process_and_emit_image(&app_handle);
+                    // frames that take longer to analyze than to display slow playback down
+                    thread::sleep(frame_interval.saturating_sub(started_at.elapsed()));
+                }
+                None => {
+                    keep_alive.store(false, Ordering::Relaxed);
+                    break;
+                }
+            }
+        });
+    }
+    fn stop(&self) {
+        self.worker_thread
+            .keep_alive
+            .store(false, Ordering::Relaxed);
+    }
+}
+
+static THREAD_LOUPE: Lazy<RwLock<LoupeThread>> = Lazy::new(|| RwLock::new(create_loupe_thread()));
+
+pub struct LoupeThread {
+    pub worker_thread: worker_thread_base::Worker,
+}
+#[cold]
+fn create_loupe_thread() -> LoupeThread {
+    LoupeThread::new()
+}
+
+impl LoupeThread {
+    pub fn new() -> Self {
+        Self {
+            worker_thread: worker_thread_base::Worker::new(),
+        }
+    }
+}
+
+impl worker_thread_base::WorkerTrait for LoupeThread {
+    fn run(&self, app_handle: tauri::AppHandle) {
+        let keep_alive = Arc::clone(&self.worker_thread.keep_alive);
+        keep_alive.store(true, Ordering::Relaxed);
+        thread::spawn(move || loop {
+            if !keep_alive.load(Ordering::Relaxed) {
+                break;
+            }
+            process_and_emit_loupe(&app_handle);
+            thread::sleep(LOUPE_REFRESH_INTERVAL);
         });
     }
     fn stop(&self) {
@@ -76,6 +320,22 @@ impl worker_thread_base::WorkerTrait for ImageProcessThread {
     }
 }
 
+#[derive(Clone, Serialize)]
+struct LoupePayload {
+    image: String,
+    position: (i32, i32),
+    zoom: u32,
+    red: u8,
+    green: u8,
+    blue: u8,
+}
+
+#[derive(Clone, Serialize)]
+struct PalettePayload {
+    image: String,
+    colors: Vec<PaletteColor>,
+}
+
 #[tauri::command]
 pub fn one_shot_emit(app_handle: tauri::AppHandle) {
     #[cfg(debug_assertions)]
@@ -96,16 +356,52 @@ pub fn one_shot_emit(app_handle: tauri::AppHandle) {
 
 #[inline(always)]
 fn process_and_emit_image(app_handle: &tauri::AppHandle) {
-    let screenshot = capture_screenshot();
+    let frame = get_frame_to_analyze(app_handle);
+    let video_frame = FRAME_SOURCE.read().unwrap().video_frame(&frame);
+    let screenshot = apply_input_transform(Arc::clone(&frame));
+    let ycbcr = native_ycbcr(&frame, &screenshot, video_frame.as_ref());
+    let reference = reference_image();
     let mut base64_vector_scope = String::new();
     let mut base64_waveform = String::new();
+    let mut palette_payload: Option<PalettePayload> = None;
+    let mut base64_false_color = String::new();
+    let mut hdr_statistics: Option<HdrStatistics> = None;
+
+    if IS_LEGAL_RANGE_CHECK_ON.load(Ordering::Relaxed) {
+        check_legal_range(app_handle, &screenshot);
+    }
+    update_frame_tray_icon(app_handle, &screenshot);
+
+    if let Some(recorder) = STATISTICS_RECORDER.write().unwrap().as_mut() {
+        if let Err(_err) = recorder.append(
+            &color_statistics::calculate(&screenshot),
+            video_frame.as_ref().map(|video_frame| video_frame.position),
+        ) {
+            #[cfg(debug_assertions)]
+            println!("Failed to record statistics: {_err}");
+        }
+    }
 
     if IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed) {
-        base64_vector_scope = get_vector_scope_image_as_base64(&screenshot);
+        base64_vector_scope =
+            get_vector_scope_image_as_base64(&screenshot, ycbcr, reference.as_deref());
     }
 
     if IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed) {
-        base64_waveform = get_waveform_image_as_base64(&screenshot);
+        base64_waveform = get_waveform_image_as_base64(&screenshot, reference.as_deref());
+        hdr_statistics = measure_hdr_statistics(&screenshot);
+    }
+
+    if IS_FALSE_COLOR_WINDOW_OPEN.load(Ordering::Relaxed) {
+        base64_false_color = get_false_color_image_as_base64(&screenshot);
+    }
+
+    if IS_PALETTE_WINDOW_OPEN.load(Ordering::Relaxed) {
+        let colors = update_palette(&screenshot);
+        palette_payload = Some(PalettePayload {
+            image: get_palette_image_as_base64(&colors),
+            colors,
+        });
     }
 
     if !base64_vector_scope.is_empty() {
@@ -127,29 +423,253 @@ fn process_and_emit_image(app_handle: &tauri::AppHandle) {
             )
             .unwrap();
     }
+
+    if !base64_false_color.is_empty() {
+        app_handle
+            .emit_to(
+                super::WINDOW_LABEL_FALSE_COLOR,
+                EVENT_NAME_FALSE_COLOR,
+                base64_false_color,
+            )
+            .unwrap();
+    }
+
+    if let Some(statistics) = hdr_statistics {
+        app_handle
+            .emit_to(
+                super::WINDOW_LABEL_WAVEFORM,
+                EVENT_NAME_HDR_STATISTICS,
+                statistics,
+            )
+            .unwrap();
+    }
+
+    if let Some(payload) = palette_payload {
+        app_handle
+            .emit_to(super::WINDOW_LABEL_PALETTE, EVENT_NAME_PALETTE, payload)
+            .unwrap();
+    }
+
+    if let Some(server) = SCOPE_SERVER.read().unwrap().as_ref() {
+        server.publish(ScopeSnapshot {
+            // already rendered for the open windows
+            vector_scope: match IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed) {
+                true => LAST_VECTOR_SCOPE_IMAGE.read().unwrap().clone(),
+                false => render_vector_scope(&screenshot, ycbcr, reference.as_deref()),
+            },
+            waveform: match IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed) {
+                true => LAST_WAVEFORM_IMAGE.read().unwrap().clone(),
+                false => render_waveform(&screenshot, reference.as_deref()),
+            },
+            statistics: color_statistics::calculate(&screenshot),
+        });
+    }
+}
+
+fn process_and_emit_loupe(app_handle: &tauri::AppHandle) {
+    let position = mouse_info::get_mouse_position();
+    let zoom = LOUPE_ZOOM.load(Ordering::Relaxed);
+    let (screenshot, cursor) =
+        match screenshot_capture::capture_around(position, graph_plotter::loupe_capture_size(zoom))
+        {
+            Ok((image, cursor)) => (Frame::from(image), cursor),
+            // skipped, the next frame is tried again
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                println!("Failed to capture the loupe: {_err}");
+                return;
+            }
+        };
+    let (red, green, blue) = graph_plotter::get_pixel(&screenshot, cursor);
+    let loupe_image =
+        graph_plotter::draw_loupe(&screenshot, zoom, cursor).expect("Failed to draw loupe");
+    let payload = LoupePayload {
+        image: PREFIX_DATA_URI.to_string()
+            + &BASE64_ENGINE
+                .get_or_init(init_base64_engine)
+                .encode(loupe_image),
+        position,
+        zoom,
+        red,
+        green,
+        blue,
+    };
+
+    app_handle
+        .emit_to(super::WINDOW_LABEL_LOUPE, EVENT_NAME_LOUPE, payload)
+        .unwrap();
+}
+
+fn check_legal_range(app_handle: &tauri::AppHandle, screenshot: &Frame) {
+    let thresholds = *LEGAL_RANGE_THRESHOLDS.read().unwrap();
+    let result = legal_range::check(screenshot, &thresholds);
+    app_handle.emit_all(EVENT_NAME_LEGAL_RANGE, result).unwrap();
+
+    if IS_LEGAL_RANGE_EXCEEDED.swap(result.is_exceeded, Ordering::Relaxed) != result.is_exceeded {
+        update_legal_range_tray_icon(app_handle, result.is_exceeded);
+    }
+    if result.is_exceeded {
+        notify_legal_range_exceeded(app_handle, &result);
+    }
+}
+
+fn update_legal_range_tray_icon(app_handle: &tauri::AppHandle, is_exceeded: bool) {
+    if *TRAY_ICON_MODE.read().unwrap() != TrayIconMode::Default {
+        // the badge is drawn on the frame icon
+        *LAST_TRAY_ICON_UPDATE.lock().unwrap() = None;
+        return;
+    }
+    let icon = match is_exceeded {
+        true => tray_icon::alarm_icon(),
+        false => tray_icon::default_icon(),
+    };
+    set_tray_icon(app_handle, icon, !is_exceeded);
+}
+
+/// Redraws the tray icon from the analyzed frame, at most every `TRAY_ICON_UPDATE_INTERVAL`
+fn update_frame_tray_icon(app_handle: &tauri::AppHandle, screenshot: &Frame) {
+    let mode = *TRAY_ICON_MODE.read().unwrap();
+    if mode == TrayIconMode::Default {
+        return;
+    }
+    {
+        let mut last_update = LAST_TRAY_ICON_UPDATE.lock().unwrap();
+        if last_update.is_some_and(|last| last.elapsed() < TRAY_ICON_UPDATE_INTERVAL) {
+            return;
+        }
+        *last_update = Some(Instant::now());
+    }
+    let is_alarm = IS_LEGAL_RANGE_EXCEEDED.load(Ordering::Relaxed);
+    if let Some(icon) = tray_icon::render(mode, screenshot, is_alarm) {
+        set_tray_icon(app_handle, icon, false);
+    }
+}
+
+fn set_tray_icon(app_handle: &tauri::AppHandle, icon: TrayIconImage, _is_template: bool) {
+    let tray_handle = app_handle.tray_handle();
+    // a template icon is drawn monochrome, colored icons are not
+    #[cfg(target_os = "macos")]
+    let _ = tray_handle.set_icon_as_template(_is_template);
+    let _ = tray_handle.set_icon(tauri::Icon::Rgba {
+        rgba: icon.rgba,
+        width: icon.width,
+        height: icon.height,
+    });
+}
+
+/// Shows the app icon, or the average color or luma histogram of every analyzed frame
+#[tauri::command]
+pub fn set_tray_icon_mode(app_handle: tauri::AppHandle, mode: TrayIconMode) -> Result<(), String> {
+    *TRAY_ICON_MODE.write().unwrap() = mode;
+    *LAST_TRAY_ICON_UPDATE.lock().unwrap() = None;
+    match mode {
+        TrayIconMode::Default => update_legal_range_tray_icon(
+            &app_handle,
+            IS_LEGAL_RANGE_EXCEEDED.load(Ordering::Relaxed),
+        ),
+        _ => one_shot_emit(app_handle.clone()),
+    }
+    update_tray_menu(&app_handle);
+    save_settings(&app_handle)
+}
+
+#[tauri::command]
+pub fn get_tray_icon_mode() -> TrayIconMode {
+    *TRAY_ICON_MODE.read().unwrap()
+}
+
+fn notify_legal_range_exceeded(app_handle: &tauri::AppHandle, result: &LegalRangeResult) {
+    let mut last_notification = LAST_LEGAL_RANGE_NOTIFICATION.lock().unwrap();
+    if last_notification.is_some_and(|last| last.elapsed() < LEGAL_RANGE_NOTIFICATION_INTERVAL) {
+        return;
+    }
+    *last_notification = Some(Instant::now());
+
+    let _ =
+        tauri::api::notification::Notification::new(&app_handle.config().tauri.bundle.identifier)
+            .title("Legal range exceeded")
+            .body(format!(
+                "Luminance low: {:.1}%, high: {:.1}%, saturation: {:.1}%",
+                result.below_luminance_percent,
+                result.above_luminance_percent,
+                result.over_saturation_percent
+            ))
+            .show();
 }
 
 #[tauri::command]
-pub fn initialize_capture_area() {
+pub fn initialize_capture_area(app_handle: tauri::AppHandle) {
     println!("initialize_capture_area");
-    let mut top_left_writer = CAPTURE_AREA_TOP_LEFT.write().unwrap();
-    *top_left_writer = (0, 0);
-    let mut bottom_right_writer = CAPTURE_AREA_BOTTOM_RIGHT.write().unwrap();
-    *bottom_right_writer = (0, 0);
+    *CAPTURE_AREA.write().unwrap() = None;
+    update_tray_menu(&app_handle);
 }
 
+/// Captures the rectangle between two opposite corners (in any order) from now on.
+/// The corners are in logical pixels unless `space` says otherwise.
+/// Returns the area in physical pixels after rounding and cutting it to a display;
+/// the current area is kept when the rectangle is empty or off screen.
 #[tauri::command]
-pub fn set_capture_area(top_left: (i32, i32), bottom_right: (i32, i32)) {
+pub fn set_capture_area(
+    app_handle: tauri::AppHandle,
+    top_left: (f64, f64),
+    bottom_right: (f64, f64),
+    space: Option<CoordinateSpace>,
+) -> Result<CaptureArea, String> {
     #[cfg(debug_assertions)]
     println!("set_capture_area");
-    let mut top_left_writer = CAPTURE_AREA_TOP_LEFT.write().unwrap();
-    *top_left_writer = top_left;
-    let mut bottom_right_writer = CAPTURE_AREA_BOTTOM_RIGHT.write().unwrap();
-    *bottom_right_writer = bottom_right;
+    let area = CaptureArea::new(
+        top_left,
+        bottom_right,
+        space.unwrap_or_default(),
+        &DisplayLayout::current(),
+    )
+    .map_err(|err| err.to_string())?;
+    *CAPTURE_AREA.write().unwrap() = Some(area);
+    update_tray_menu(&app_handle);
+    Ok(area)
+}
+
+/// Leaves the app's own windows out of screen captures (on by default).
+/// Their pixels are not counted by any scope or statistics.
+#[tauri::command]
+pub fn set_exclude_own_windows(app_handle: tauri::AppHandle, state: bool) {
+    IS_EXCLUDING_OWN_WINDOWS.store(state, Ordering::Relaxed);
+    update_tray_menu(&app_handle);
+}
+
+#[tauri::command]
+pub fn is_excluding_own_windows() -> bool {
+    IS_EXCLUDING_OWN_WINDOWS.load(Ordering::Relaxed)
+}
+
+/// Sets the capture area to the client area of the window under the cursor,
+/// shrunk by `inset` pixels on every side, and returns it.
+#[tauri::command]
+pub fn snap_capture_area_to_window(
+    app_handle: tauri::AppHandle,
+    inset: Option<u32>,
+) -> Result<CaptureArea, String> {
+    let position = mouse_info::get_mouse_position();
+    let bounds = window_snap::window_bounds_at(position)
+        .map_err(|err| err.to_string())?
+        .ok_or("There is no window under the cursor")?;
+    let bounds = bounds
+        .inset(inset.unwrap_or(0))
+        .ok_or("The inset is larger than the window")?;
+    let (top_left, bottom_right) = bounds.corners();
+    set_capture_area(
+        app_handle,
+        (top_left.0 as f64, top_left.1 as f64),
+        (bottom_right.0 as f64, bottom_right.1 as f64),
+        Some(CoordinateSpace::Physical),
+    )
 }
 
 #[tauri::command]
 pub fn set_is_vector_scope_window_open(app_handle: tauri::AppHandle, state: bool) {
+    if state {
+        *LAST_USED_SCOPE.write().unwrap() = super::WINDOW_LABEL_VECTOR_SCOPE;
+    }
     if IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed) != state {
         IS_VECTOR_SCOPE_WINDOW_OPEN.store(state, Ordering::Relaxed);
         check_thread_need_to_be_keep_alive(app_handle);
@@ -158,6 +678,9 @@ pub fn set_is_vector_scope_window_open(app_handle: tauri::AppHandle, state: bool
 
 #[tauri::command]
 pub fn set_is_waveform_window_open(app_handle: tauri::AppHandle, state: bool) {
+    if state {
+        *LAST_USED_SCOPE.write().unwrap() = super::WINDOW_LABEL_WAVEFORM;
+    }
     if IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed) != state {
         IS_WAVEFORM_WINDOW_OPEN.store(state, Ordering::Relaxed);
         check_thread_need_to_be_keep_alive(app_handle);
@@ -165,95 +688,1568 @@ pub fn set_is_waveform_window_open(app_handle: tauri::AppHandle, state: bool) {
 }
 
 #[tauri::command]
-pub fn set_manual_mode(app_handle: tauri::AppHandle, state: bool) {
-    #[cfg(debug_assertions)]
-    println!("set_manual_mode: {state}");
-    if IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed) != state {
-        IS_MANUAL_REFRESH_MODE_ON.store(state, Ordering::Relaxed);
-        check_thread_need_to_be_keep_alive(app_handle);
-    }
-}
-
-fn check_thread_need_to_be_keep_alive(app_handle: tauri::AppHandle) {
-    if (IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed)
-        || IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed))
-        && !IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed)
-    {
-        if !THREAD_IMAGE_PROCESS
-            .try_read()
-            .expect("Failed to read thread")
-            .worker_thread
-            .keep_alive
-            .load(Ordering::Relaxed)
-        {
+pub fn set_is_loupe_window_open(app_handle: tauri::AppHandle, state: bool) {
+    if IS_LOUPE_WINDOW_OPEN.load(Ordering::Relaxed) != state {
+        IS_LOUPE_WINDOW_OPEN.store(state, Ordering::Relaxed);
+        let loupe_thread = THREAD_LOUPE.try_read().expect("Failed to read thread");
+        if state {
             #[cfg(debug_assertions)]
-            println!("Thread: Start");
-            THREAD_IMAGE_PROCESS
-                .try_read()
-                .expect("Failed to read thread")
-                .run(app_handle)
+            println!("Loupe thread: Start");
+            loupe_thread.run(app_handle);
         } else {
             #[cfg(debug_assertions)]
-            println!("Thread: Already started");
-        }
-    } else {
-        if THREAD_IMAGE_PROCESS
-            .try_read()
-            .expect("Failed to read thread")
-            .worker_thread
-            .keep_alive
-            .load(Ordering::Relaxed)
-        {
-            #[cfg(debug_assertions)]
-            println!("Thread: Stop");
-            THREAD_IMAGE_PROCESS
-                .try_read()
-                .expect("Failed to read thread")
-                .stop()
+            println!("Loupe thread: Stop");
+            loupe_thread.stop();
         }
     }
 }
 
-fn is_capture_area_valid() -> bool {
-    let top_left = CAPTURE_AREA_TOP_LEFT.try_read();
-    let bottom_right = CAPTURE_AREA_BOTTOM_RIGHT.try_read();
+/// Sets the loupe magnification and returns the value actually applied.
+#[tauri::command]
+pub fn set_loupe_zoom(zoom: u32) -> u32 {
+    let zoom = zoom.clamp(graph_plotter::LOUPE_ZOOM_MIN, graph_plotter::LOUPE_ZOOM_MAX);
+    LOUPE_ZOOM.store(zoom, Ordering::Relaxed);
+    zoom
+}
 
-    if top_left.is_err() || bottom_right.is_err() {
-        return false;
+#[tauri::command]
+pub fn set_is_palette_window_open(app_handle: tauri::AppHandle, state: bool) {
+    if IS_PALETTE_WINDOW_OPEN.load(Ordering::Relaxed) != state {
+        IS_PALETTE_WINDOW_OPEN.store(state, Ordering::Relaxed);
+        check_thread_need_to_be_keep_alive(app_handle);
     }
+}
 
-    if *top_left.unwrap() == *bottom_right.unwrap() {
-        false
-    } else {
-        true
+#[tauri::command]
+pub fn set_is_false_color_window_open(app_handle: tauri::AppHandle, state: bool) {
+    if state {
+        *LAST_USED_SCOPE.write().unwrap() = super::WINDOW_LABEL_FALSE_COLOR;
+    }
+    if IS_FALSE_COLOR_WINDOW_OPEN.load(Ordering::Relaxed) != state {
+        IS_FALSE_COLOR_WINDOW_OPEN.store(state, Ordering::Relaxed);
+        check_thread_need_to_be_keep_alive(app_handle);
     }
 }
 
-fn get_vector_scope_image_as_base64(screenshot: &Image) -> String {
-    let vector_scope_image =
-        graph_plotter::draw_vector_scope(&screenshot).expect("Failed to draw vector scope");
-    let base64_vector_scope = BASE64_ENGINE
-        .get_or_init(init_base64_engine)
-        .encode(vector_scope_image);
-    PREFIX_DATA_URI.to_string() + &base64_vector_scope
+#[tauri::command]
+pub fn set_false_color_settings(app_handle: tauri::AppHandle, settings: FalseColorSettings) {
+    *FALSE_COLOR_SETTINGS.write().unwrap() = settings;
+    one_shot_emit(app_handle);
 }
 
-fn get_waveform_image_as_base64(screenshot: &Image) -> String {
-    let waveform_image =
-        graph_plotter::draw_waveform_luminance(&screenshot).expect("Failed to draw waveform");
-    let base64_waveform = BASE64_ENGINE
-        .get_or_init(init_base64_engine)
-        .encode(waveform_image);
-    PREFIX_DATA_URI.to_string() + &base64_waveform
+#[tauri::command]
+pub fn get_false_color_settings() -> FalseColorSettings {
+    *FALSE_COLOR_SETTINGS.read().unwrap()
+}
+
+/// Sets the number of palette colors and returns the value actually applied.
+#[tauri::command]
+pub fn set_palette_color_count(count: usize) -> usize {
+    let count = count.clamp(
+        color_palette::PALETTE_COLOR_COUNT_MIN,
+        color_palette::PALETTE_COLOR_COUNT_MAX,
+    );
+    PALETTE_COLOR_COUNT.store(count, Ordering::Relaxed);
+    count
+}
+
+/// Captures the current frame and returns its dominant colors.
+#[tauri::command]
+pub fn get_palette(app_handle: tauri::AppHandle) -> Vec<PaletteColor> {
+    update_palette(&capture_screenshot(&app_handle))
+}
+
+/// Writes the last extracted palette to `path`.
+#[tauri::command]
+pub fn export_palette(
+    app_handle: tauri::AppHandle,
+    path: String,
+    format: color_palette::PaletteExportFormat,
+) -> Result<(), String> {
+    let mut palette = LAST_PALETTE.read().unwrap().clone();
+    if palette.is_empty() {
+        palette = get_palette(app_handle);
+    }
+    color_palette::export_palette(&palette, std::path::Path::new(&path), format)
+        .map_err(|err| err.to_string())
+}
+
+/// Uses the image file at `path` as the reference still.
+#[tauri::command]
+pub fn load_reference_image(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    let reference =
+        image_file::load_image(std::path::Path::new(&path)).map_err(|err| err.to_string())?;
+    *REFERENCE_IMAGE.write().unwrap() = Some(Arc::new(reference));
+    one_shot_emit(app_handle);
+    Ok(())
+}
+
+/// Uses the frame being analyzed (the opened file, the held frame or a new capture)
+/// as the reference still.
+#[tauri::command]
+pub fn freeze_reference_image(app_handle: tauri::AppHandle) {
+    *REFERENCE_IMAGE.write().unwrap() = Some(get_frame_to_analyze(&app_handle));
+    one_shot_emit(app_handle);
+}
+
+#[tauri::command]
+pub fn clear_reference_image(app_handle: tauri::AppHandle) {
+    *REFERENCE_IMAGE.write().unwrap() = None;
+    one_shot_emit(app_handle);
+}
+
+/// Analyzes the image file at `path` instead of the screen until `use_screen_source` is called.
+#[tauri::command]
+pub fn open_image_file(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<FrameSourceInfo, String> {
+    let path = PathBuf::from(path);
+    let frame = image_file::load_image(&path).map_err(|err| err.to_string())?;
+    Ok(set_frame_source(
+        app_handle,
+        FrameSource::File {
+            path,
+            frame: Arc::new(frame),
+        },
+    ))
+}
+
+/// Analyzes the video at `path` frame by frame, starting paused at the first frame.
+/// `format` is required for raw Y′CbCr files, a Y4M file is expected otherwise.
+#[tauri::command]
+pub fn open_video_file(
+    app_handle: tauri::AppHandle,
+    path: String,
+    format: Option<YuvFormat>,
+) -> Result<FrameSourceInfo, String> {
+    let path = PathBuf::from(path);
+    let video = match format {
+        Some(format) => YuvVideo::open_raw(&path, format),
+        None => YuvVideo::open_y4m(&path),
+    }
+    .and_then(|video| VideoSource::new(path, video))
+    .map_err(|err| err.to_string())?;
+    Ok(set_frame_source(app_handle, FrameSource::Video(video)))
+}
+
+/// Analyzes the image on the clipboard instead of the screen until `use_screen_source` is called.
+#[tauri::command]
+pub fn analyze_clipboard_image(app_handle: tauri::AppHandle) -> Result<FrameSourceInfo, String> {
+    let frame = clipboard_image::read_image().map_err(|err| err.to_string())?;
+    Ok(set_frame_source(
+        app_handle,
+        FrameSource::Clipboard {
+            frame: Arc::new(frame),
+        },
+    ))
+}
+
+/// Analyzes a generated test pattern instead of the screen until `use_screen_source` is called.
+#[tauri::command]
+pub fn open_test_pattern(app_handle: tauri::AppHandle, pattern: TestPattern) -> FrameSourceInfo {
+    set_frame_source(
+        app_handle,
+        FrameSource::Pattern {
+            pattern,
+            frame: Arc::new(pattern.render()),
+        },
+    )
+}
+
+/// Goes back to analyzing the screen capture.
+#[tauri::command]
+pub fn use_screen_source(app_handle: tauri::AppHandle) {
+    set_frame_source(app_handle, FrameSource::Screen);
+}
+
+#[tauri::command]
+pub fn get_frame_source() -> FrameSourceInfo {
+    FRAME_SOURCE.read().unwrap().info()
 }
 
-fn capture_screenshot() -> Image {
-    match is_capture_area_valid() {
-        true => {
-            let top_left: (i32, i32) = *CAPTURE_AREA_TOP_LEFT.try_read().unwrap();
-            let bottom_right: (i32, i32) = *CAPTURE_AREA_BOTTOM_RIGHT.try_read().unwrap();
-            screenshot_capture::capture_area(top_left, bottom_right)
+fn set_frame_source(app_handle: tauri::AppHandle, source: FrameSource) -> FrameSourceInfo {
+    pause_video();
+    let info = source.info();
+    *FRAME_SOURCE.write().unwrap() = source;
+    reset_peak_hold();
+    update_tray_menu(&app_handle);
+    one_shot_emit(app_handle);
+    info
+}
+
+/// Plays the opened video from the current frame, returns `false` when no video is opened.
+#[tauri::command]
+pub fn play_video(app_handle: tauri::AppHandle) -> bool {
+    if !matches!(*FRAME_SOURCE.read().unwrap(), FrameSource::Video(_)) {
+        return false;
+    }
+    let playback_thread = THREAD_VIDEO_PLAYBACK
+        .try_read()
+        .expect("Failed to read thread");
+    if !playback_thread
+        .worker_thread
+        .keep_alive
+        .load(Ordering::Relaxed)
+    {
+        playback_thread.run(app_handle);
+    }
+    true
+}
+
+#[tauri::command]
+pub fn pause_video() {
+    THREAD_VIDEO_PLAYBACK
+        .try_read()
+        .expect("Failed to read thread")
+        .stop();
+}
+
+#[tauri::command]
+pub fn is_video_playing() -> bool {
+    THREAD_VIDEO_PLAYBACK
+        .try_read()
+        .expect("Failed to read thread")
+        .worker_thread
+        .keep_alive
+        .load(Ordering::Relaxed)
+}
+
+/// Shows frame `position` (clamped to the last frame) of the opened video.
+#[tauri::command]
+pub fn seek_video(
+    app_handle: tauri::AppHandle,
+    position: usize,
+) -> Result<FrameSourceInfo, String> {
+    let info = {
+        let mut source = FRAME_SOURCE.write().unwrap();
+        match &mut *source {
+            FrameSource::Video(video) => video.seek(position).map_err(|err| err.to_string())?,
+            _ => return Err("No video is opened".to_string()),
+        }
+        source.info()
+    };
+    one_shot_emit(app_handle);
+    Ok(info)
+}
+
+/// Moves by `offset` frames (negative is backwards) in the opened video.
+#[tauri::command]
+pub fn step_video(app_handle: tauri::AppHandle, offset: i64) -> Result<FrameSourceInfo, String> {
+    let position = match &*FRAME_SOURCE.read().unwrap() {
+        FrameSource::Video(video) => (video.position() as i64 + offset).max(0) as usize,
+        _ => return Err("No video is opened".to_string()),
+    };
+    seek_video(app_handle, position)
+}
+
+/// Decodes the next frame of the playing video and returns how long it is displayed.
+/// `None` at the end of the video or when the source is not a video.
+fn advance_video() -> Option<Duration> {
+    let mut source = FRAME_SOURCE.write().unwrap();
+    let video = match &mut *source {
+        FrameSource::Video(video) => video,
+        _ => return None,
+    };
+    if video.position() + 1 >= video.frame_count() {
+        return None;
+    }
+    match video.seek(video.position() + 1) {
+        Ok(()) => Some(video.frame_interval()),
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            println!("Failed to read video frame: {_err}");
+            None
+        }
+    }
+}
+
+#[tauri::command]
+pub fn set_reference_mode(app_handle: tauri::AppHandle, mode: ReferenceMode) {
+    *REFERENCE_MODE.write().unwrap() = mode;
+    update_tray_menu(&app_handle);
+    one_shot_emit(app_handle);
+}
+
+pub fn get_reference_mode() -> ReferenceMode {
+    *REFERENCE_MODE.read().unwrap()
+}
+
+/// Sets the color space the captured bytes are interpreted in.
+#[tauri::command]
+pub fn set_input_color_space(
+    app_handle: tauri::AppHandle,
+    space: InputColorSpace,
+) -> Result<(), String> {
+    let conversion = ColorConversion::new(&space).map_err(|err| err.to_string())?;
+    *COLOR_CONVERSION.write().unwrap() = conversion;
+    *INPUT_COLOR_SPACE.write().unwrap() = space;
+    update_tray_menu(&app_handle);
+    one_shot_emit(app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_input_color_space() -> InputColorSpace {
+    INPUT_COLOR_SPACE.read().unwrap().clone()
+}
+
+/// Switches the waveform between luma (Y′) and linear-light luminance (Y).
+#[tauri::command]
+pub fn set_luminance_settings(app_handle: tauri::AppHandle, settings: LuminanceSettings) {
+    store_luminance_settings(settings);
+    update_tray_menu(&app_handle);
+    one_shot_emit(app_handle);
+}
+
+#[tauri::command]
+pub fn get_luminance_settings() -> LuminanceSettings {
+    *LUMINANCE_SETTINGS.read().unwrap()
+}
+
+fn store_luminance_settings(settings: LuminanceSettings) {
+    *LUMINANCE_METER.write().unwrap() = LuminanceMeter::new(&settings);
+    *LUMINANCE_SETTINGS.write().unwrap() = settings;
+}
+
+/// Interprets the input as PQ or HLG and shows the waveform in nits.
+/// MaxCLL / MaxFALL restart when the mode changes.
+#[tauri::command]
+pub fn set_hdr_mode(app_handle: tauri::AppHandle, mode: HdrMode) {
+    *NITS_CONVERTER.write().unwrap() = NitsConverter::new(mode);
+    *HDR_MODE.write().unwrap() = mode;
+    LIGHT_LEVEL_METER.write().unwrap().reset();
+    PEAK_HOLD_WAVEFORM.write().unwrap().reset();
+    update_tray_menu(&app_handle);
+    one_shot_emit(app_handle);
+}
+
+#[tauri::command]
+pub fn get_hdr_mode() -> HdrMode {
+    *HDR_MODE.read().unwrap()
+}
+
+#[tauri::command]
+pub fn reset_hdr_statistics() {
+    LIGHT_LEVEL_METER.write().unwrap().reset();
+}
+
+/// Applies the `.cube` LUT at `path` to every frame before analysis.
+#[tauri::command]
+pub fn load_lut(app_handle: tauri::AppHandle, path: String) -> Result<LutInfo, String> {
+    let lut = Lut::load(std::path::Path::new(&path)).map_err(|err| err.to_string())?;
+    let info = lut.info();
+    *CURRENT_LUT.write().unwrap() = Some(lut);
+    one_shot_emit(app_handle);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn clear_lut(app_handle: tauri::AppHandle) {
+    *CURRENT_LUT.write().unwrap() = None;
+    one_shot_emit(app_handle);
+}
+
+#[tauri::command]
+pub fn get_lut_info() -> Option<LutInfo> {
+    CURRENT_LUT.read().unwrap().as_ref().map(Lut::info)
+}
+
+#[tauri::command]
+pub fn set_lut_interpolation(app_handle: tauri::AppHandle, interpolation: LutInterpolation) {
+    *LUT_INTERPOLATION.write().unwrap() = interpolation;
+    one_shot_emit(app_handle);
+}
+
+/// Freezes the last analyzed frame (`true`) or goes back to live capture (`false`).
+#[tauri::command]
+pub fn set_hold(app_handle: tauri::AppHandle, state: bool) -> FrameHistoryStatus {
+    IS_HOLD_ON.store(state, Ordering::Relaxed);
+    update_tray_menu(&app_handle);
+    let mut frame_history = FRAME_HISTORY.write().unwrap();
+    if state {
+        frame_history.hold();
+    } else {
+        frame_history.release();
+        one_shot_emit(app_handle);
+    }
+    frame_history.status()
+}
+
+/// Moves through the frame history by `offset` frames (negative is older).
+/// Stepping implies hold.
+#[tauri::command]
+pub fn step_frame_history(app_handle: tauri::AppHandle, offset: i32) -> FrameHistoryStatus {
+    IS_HOLD_ON.store(true, Ordering::Relaxed);
+    update_tray_menu(&app_handle);
+    let status = {
+        let mut frame_history = FRAME_HISTORY.write().unwrap();
+        frame_history.step(offset);
+        frame_history.status()
+    };
+    one_shot_emit(app_handle);
+    status
+}
+
+#[tauri::command]
+pub fn get_frame_history_status() -> FrameHistoryStatus {
+    FRAME_HISTORY.read().unwrap().status()
+}
+
+#[tauri::command]
+pub fn set_frame_history_capacity(capacity: usize) -> FrameHistoryStatus {
+    let mut frame_history = FRAME_HISTORY.write().unwrap();
+    frame_history.set_capacity(capacity);
+    frame_history.status()
+}
+
+/// Accumulates scope traces over time while `state` is `true`.
+#[tauri::command]
+pub fn set_peak_hold(state: bool) {
+    IS_PEAK_HOLD_ON.store(state, Ordering::Relaxed);
+    reset_peak_hold();
+}
+
+#[tauri::command]
+pub fn reset_peak_hold() {
+    PEAK_HOLD_VECTOR_SCOPE.write().unwrap().reset();
+    PEAK_HOLD_WAVEFORM.write().unwrap().reset();
+}
+
+#[cold]
+fn default_export_directory() -> PathBuf {
+    tauri::api::path::picture_dir()
+        .or_else(tauri::api::path::home_dir)
+        .unwrap_or_default()
+        .join(EXPORT_DIRECTORY_NAME)
+}
+
+#[tauri::command]
+pub fn set_export_directory(path: String) {
+    *EXPORT_DIRECTORY.write().unwrap() = PathBuf::from(path);
+}
+
+#[tauri::command]
+pub fn get_export_directory() -> String {
+    EXPORT_DIRECTORY
+        .read()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[tauri::command]
+pub fn set_export_formats(formats: Vec<ExportFormat>) -> Result<(), String> {
+    if formats.is_empty() {
+        return Err("At least one export format is required".to_string());
+    }
+    *EXPORT_FORMATS.write().unwrap() = formats;
+    Ok(())
+}
+
+/// Writes the current frame, the rendering of each open scope and the statistics
+/// to a timestamped folder in the export directory and returns that folder.
+#[tauri::command]
+pub fn export_current(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let source_frame = FRAME_SOURCE
+        .read()
+        .unwrap()
+        .frame()
+        .or_else(|| FRAME_HISTORY.read().unwrap().current())
+        .unwrap_or_else(|| Arc::new(capture_screenshot(&app_handle)));
+    let video_frame = FRAME_SOURCE.read().unwrap().video_frame(&source_frame);
+    let frame = apply_input_transform(Arc::clone(&source_frame));
+    let ycbcr = native_ycbcr(&source_frame, &frame, video_frame.as_ref());
+    let reference = reference_image();
+
+    let mut renders: Vec<(&str, Vec<u8>)> = Vec::new();
+    if IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed) {
+        let last_image = LAST_VECTOR_SCOPE_IMAGE.read().unwrap().clone();
+        renders.push((
+            EXPORT_NAME_VECTOR_SCOPE,
+            match last_image.is_empty() {
+                true => render_vector_scope(&frame, ycbcr, reference.as_deref()),
+                false => last_image,
+            },
+        ));
+    }
+    if IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed) {
+        let last_image = LAST_WAVEFORM_IMAGE.read().unwrap().clone();
+        renders.push((
+            EXPORT_NAME_WAVEFORM,
+            match last_image.is_empty() {
+                true => render_waveform(&frame, reference.as_deref()),
+                false => last_image,
+            },
+        ));
+    }
+    if IS_FALSE_COLOR_WINDOW_OPEN.load(Ordering::Relaxed) {
+        let last_image = LAST_FALSE_COLOR_IMAGE.read().unwrap().clone();
+        renders.push((
+            EXPORT_NAME_FALSE_COLOR,
+            match last_image.is_empty() {
+                true => render_false_color(&frame),
+                false => last_image,
+            },
+        ));
+    }
+    if IS_PALETTE_WINDOW_OPEN.load(Ordering::Relaxed) {
+        let last_image = LAST_PALETTE_IMAGE.read().unwrap().clone();
+        renders.push((
+            EXPORT_NAME_PALETTE,
+            match last_image.is_empty() {
+                true => render_palette(&update_palette(&frame)),
+                false => last_image,
+            },
+        ));
+    }
+
+    let statistics = color_statistics::calculate(&frame);
+    let directory = EXPORT_DIRECTORY.read().unwrap().clone();
+    let formats = EXPORT_FORMATS.read().unwrap().clone();
+    scope_export::export(&directory, &frame, &renders, &statistics, &formats)
+        .map(|folder| folder.to_string_lossy().into_owned())
+        .map_err(|err| err.to_string())
+}
+
+/// Starts appending per-frame statistics to `path`.
+/// The format is guessed from the extension when `format` is omitted.
+#[tauri::command]
+pub fn start_recording(
+    app_handle: tauri::AppHandle,
+    path: String,
+    format: Option<RecordingFormat>,
+) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    let format = format.unwrap_or_else(|| RecordingFormat::from_path(&path));
+    let recorder = StatisticsRecorder::new(&path, format).map_err(|err| err.to_string())?;
+    *STATISTICS_RECORDER.write().unwrap() = Some(recorder);
+    update_recording_tray_item(&app_handle);
+    check_thread_need_to_be_keep_alive(app_handle);
+    Ok(())
+}
+
+/// Stops the recording and returns the number of recorded frames.
+#[tauri::command]
+pub fn stop_recording(app_handle: tauri::AppHandle) -> usize {
+    let frame_count = STATISTICS_RECORDER
+        .write()
+        .unwrap()
+        .take()
+        .map_or(0, |recorder| recorder.frame_count());
+    update_recording_tray_item(&app_handle);
+    check_thread_need_to_be_keep_alive(app_handle);
+    frame_count
+}
+
+#[tauri::command]
+pub fn is_recording() -> bool {
+    STATISTICS_RECORDER.read().unwrap().is_some()
+}
+
+fn update_recording_tray_item(app_handle: &tauri::AppHandle) {
+    let title = match is_recording() {
+        true => "● Stop recording statistics",
+        false => "Record statistics...",
+    };
+    let _ = app_handle
+        .tray_handle()
+        .get_item(tray_menu::TRAY_RECORDING)
+        .set_title(title);
+}
+
+/// Starts serving the scopes over HTTP / WebSocket and returns the URL to open.
+#[tauri::command]
+pub fn start_scope_server(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let url = {
+        let mut scope_server = SCOPE_SERVER.write().unwrap();
+        if let Some(server) = scope_server.take() {
+            server.stop();
+        }
+        let server = ScopeServer::start(&SCOPE_SERVER_SETTINGS.read().unwrap())
+            .map_err(|err| err.to_string())?;
+        let url = server.url();
+        *scope_server = Some(server);
+        url
+    };
+    update_scope_server_tray_item(&app_handle);
+    check_thread_need_to_be_keep_alive(app_handle.clone());
+    one_shot_emit(app_handle);
+    Ok(url)
+}
+
+#[tauri::command]
+pub fn stop_scope_server(app_handle: tauri::AppHandle) {
+    if let Some(server) = SCOPE_SERVER.write().unwrap().take() {
+        server.stop();
+    }
+    update_scope_server_tray_item(&app_handle);
+    check_thread_need_to_be_keep_alive(app_handle);
+}
+
+#[tauri::command]
+pub fn is_scope_server_running() -> bool {
+    SCOPE_SERVER.read().unwrap().is_some()
+}
+
+/// Applies to the next start; a running server is restarted.
+#[tauri::command]
+pub fn set_scope_server_settings(
+    app_handle: tauri::AppHandle,
+    settings: ScopeServerSettings,
+) -> Result<(), String> {
+    *SCOPE_SERVER_SETTINGS.write().unwrap() = settings;
+    if is_scope_server_running() {
+        start_scope_server(app_handle)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_scope_server_settings() -> ScopeServerSettings {
+    *SCOPE_SERVER_SETTINGS.read().unwrap()
+}
+
+fn update_scope_server_tray_item(app_handle: &tauri::AppHandle) {
+    let title = match SCOPE_SERVER.read().unwrap().as_ref() {
+        Some(server) => format!("● Stop streaming ({})", server.url()),
+        None => "Stream scopes".to_string(),
+    };
+    let _ = app_handle
+        .tray_handle()
+        .get_item(tray_menu::TRAY_SCOPE_SERVER)
+        .set_title(title);
+}
+
+/// Enables or disables the legal range check of every analyzed frame.
+#[tauri::command]
+pub fn set_legal_range_check(app_handle: tauri::AppHandle, state: bool) {
+    IS_LEGAL_RANGE_CHECK_ON.store(state, Ordering::Relaxed);
+    update_tray_menu(&app_handle);
+    if !state && IS_LEGAL_RANGE_EXCEEDED.swap(false, Ordering::Relaxed) {
+        update_legal_range_tray_icon(&app_handle, false);
+        app_handle
+            .emit_all(EVENT_NAME_LEGAL_RANGE, LegalRangeResult::default())
+            .unwrap();
+    }
+}
+
+pub fn is_legal_range_check_on() -> bool {
+    IS_LEGAL_RANGE_CHECK_ON.load(Ordering::Relaxed)
+}
+
+#[tauri::command]
+pub fn set_legal_range_thresholds(thresholds: LegalRangeThresholds) {
+    *LEGAL_RANGE_THRESHOLDS.write().unwrap() = thresholds;
+}
+
+#[tauri::command]
+pub fn get_legal_range_thresholds() -> LegalRangeThresholds {
+    *LEGAL_RANGE_THRESHOLDS.read().unwrap()
+}
+
+#[tauri::command]
+pub fn set_manual_mode(app_handle: tauri::AppHandle, state: bool) {
+    #[cfg(debug_assertions)]
+    println!("set_manual_mode: {state}");
+    if IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed) != state {
+        IS_MANUAL_REFRESH_MODE_ON.store(state, Ordering::Relaxed);
+        update_tray_menu(&app_handle);
+        check_thread_need_to_be_keep_alive(app_handle);
+    }
+}
+
+#[tauri::command]
+pub fn is_manual_mode() -> bool {
+    IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed)
+}
+
+/// Interval of the auto refresh, at least 100 ms
+#[tauri::command]
+pub fn set_refresh_interval(app_handle: tauri::AppHandle, interval_ms: u64) {
+    REFRESH_INTERVAL_MS.store(interval_ms.max(REFRESH_INTERVAL_MIN_MS), Ordering::Relaxed);
+    update_tray_menu(&app_handle);
+}
+
+#[tauri::command]
+pub fn get_refresh_interval() -> u64 {
+    REFRESH_INTERVAL_MS.load(Ordering::Relaxed)
+}
+
+#[tauri::command]
+pub fn is_hold_on() -> bool {
+    IS_HOLD_ON.load(Ordering::Relaxed)
+}
+
+pub fn get_last_used_scope() -> &'static str {
+    *LAST_USED_SCOPE.read().unwrap()
+}
+
+/// Scale of the waveform, a combination of the luminance and HDR settings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaveformMode {
+    Luma,
+    Linear,
+    Pq,
+    Hlg,
+}
+
+pub fn get_waveform_mode() -> WaveformMode {
+    match (get_hdr_mode(), get_luminance_settings().mode) {
+        (HdrMode::Off, LuminanceMode::Luma) => WaveformMode::Luma,
+        (HdrMode::Off, LuminanceMode::Linear) => WaveformMode::Linear,
+        (HdrMode::Pq, _) => WaveformMode::Pq,
+        (HdrMode::Hlg, _) => WaveformMode::Hlg,
+    }
+}
+
+pub fn set_waveform_mode(app_handle: tauri::AppHandle, mode: WaveformMode) {
+    let luminance_mode = match mode {
+        WaveformMode::Linear => LuminanceMode::Linear,
+        _ => LuminanceMode::Luma,
+    };
+    let hdr_mode = match mode {
+        WaveformMode::Pq => HdrMode::Pq,
+        WaveformMode::Hlg => HdrMode::Hlg,
+        _ => HdrMode::Off,
+    };
+    store_luminance_settings(LuminanceSettings {
+        mode: luminance_mode,
+        ..get_luminance_settings()
+    });
+    if get_hdr_mode() != hdr_mode {
+        set_hdr_mode(app_handle, hdr_mode);
+    } else {
+        update_tray_menu(&app_handle);
+        one_shot_emit(app_handle);
+    }
+}
+
+/// Syncs the check marks of the tray menu with the current state
+pub fn update_tray_menu(app_handle: &tauri::AppHandle) {
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_AUTO_REFRESH,
+        !IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed),
+    );
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_HOLD,
+        IS_HOLD_ON.load(Ordering::Relaxed),
+    );
+    let refresh_interval = REFRESH_INTERVAL_MS.load(Ordering::Relaxed);
+    for (id, _, interval) in tray_menu::TRAY_REFRESH_INTERVALS {
+        set_tray_item_checked(app_handle, id, interval == refresh_interval);
+    }
+
+    let is_full_screen = CAPTURE_AREA.read().unwrap().is_none();
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_CAPTURE_FULL_SCREEN,
+        is_full_screen,
+    );
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_CAPTURE_AREA_SETTING,
+        !is_full_screen,
+    );
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_EXCLUDE_OWN_WINDOWS,
+        IS_EXCLUDING_OWN_WINDOWS.load(Ordering::Relaxed),
+    );
+
+    let source = FRAME_SOURCE.read().unwrap().info();
+    for (id, checked) in [
+        (
+            tray_menu::TRAY_SOURCE_SCREEN,
+            matches!(source, FrameSourceInfo::Screen),
+        ),
+        (
+            tray_menu::TRAY_SOURCE_OPEN_IMAGE,
+            matches!(source, FrameSourceInfo::File { .. }),
+        ),
+        (
+            tray_menu::TRAY_SOURCE_OPEN_VIDEO,
+            matches!(source, FrameSourceInfo::Video { .. }),
+        ),
+        (
+            tray_menu::TRAY_SOURCE_CLIPBOARD,
+            matches!(source, FrameSourceInfo::Clipboard { .. }),
+        ),
+    ] {
+        set_tray_item_checked(app_handle, id, checked);
+    }
+    for (id, pattern) in tray_menu::TRAY_SOURCE_PATTERNS {
+        set_tray_item_checked(
+            app_handle,
+            id,
+            matches!(source, FrameSourceInfo::Pattern { pattern: current, .. } if current == pattern),
+        );
+    }
+
+    let reference_mode = get_reference_mode();
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_REFERENCE_OVERLAY,
+        reference_mode == ReferenceMode::Overlay,
+    );
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_REFERENCE_SPLIT,
+        reference_mode == ReferenceMode::Split,
+    );
+
+    let waveform_mode = get_waveform_mode();
+    for (id, mode) in tray_menu::TRAY_WAVEFORM_MODES {
+        set_tray_item_checked(app_handle, id, mode == waveform_mode);
+    }
+
+    let input_color_space = get_input_color_space();
+    for (id, space) in tray_menu::TRAY_COLOR_SPACES {
+        set_tray_item_checked(app_handle, id, space == input_color_space);
+    }
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_COLOR_SPACE_ICC,
+        matches!(input_color_space, InputColorSpace::Icc(_)),
+    );
+
+    let tray_icon_mode = get_tray_icon_mode();
+    for (id, mode) in tray_menu::TRAY_ICON_MODES {
+        set_tray_item_checked(app_handle, id, mode == tray_icon_mode);
+    }
+
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_LEGAL_RANGE,
+        IS_LEGAL_RANGE_CHECK_ON.load(Ordering::Relaxed),
+    );
+    set_tray_item_checked(
+        app_handle,
+        tray_menu::TRAY_CONTROL_SOCKET,
+        is_control_socket_running(),
+    );
+}
+
+/// GTK only shows check marks on items created selected, and emits a click
+/// when one is toggled, so the state is shown in the title on Linux.
+fn set_tray_item_checked(app_handle: &tauri::AppHandle, id: &str, checked: bool) {
+    let item = app_handle.tray_handle().get_item(id);
+    #[cfg(target_os = "linux")]
+    {
+        let title = tray_menu::tray_item_title(id);
+        let _ = item.set_title(match checked {
+            true => format!("✓ {title}"),
+            false => title.to_string(),
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = item.set_selected(checked);
+}
+
+/// Loads the saved settings and registers the global shortcuts.
+/// Returns the shortcuts that could not be registered (e.g. taken by another application).
+pub fn load_settings(app_handle: &tauri::AppHandle) -> Vec<String> {
+    if let Some(directory) = app_handle.path_resolver().app_config_dir() {
+        let settings = Settings::load(&directory);
+        *KEYMAP.write().unwrap() = settings.keymap;
+        *TRAY_ICON_MODE.write().unwrap() = settings.tray_icon_mode;
+        update_tray_menu(app_handle);
+    }
+    KEYMAP
+        .read()
+        .unwrap()
+        .bindings()
+        .filter_map(|(action, accelerator)| {
+            register_shortcut(app_handle, action, accelerator).err()
+        })
+        .collect()
+}
+
+fn save_settings(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = Settings {
+        keymap: KEYMAP.read().unwrap().clone(),
+        tray_icon_mode: *TRAY_ICON_MODE.read().unwrap(),
+    };
+    let directory = app_handle
+        .path_resolver()
+        .app_config_dir()
+        .ok_or("Failed to find the config directory")?;
+    settings.save(&directory).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn get_keymap() -> Keymap {
+    KEYMAP.read().unwrap().clone()
+}
+
+/// Binds `action` to `accelerator` (or unbinds it with `None`) and saves the keymap.
+/// Nothing changes when the shortcut is invalid, bound to another action
+/// or taken by another application.
+#[tauri::command]
+pub fn set_shortcut(
+    app_handle: tauri::AppHandle,
+    action: KeyAction,
+    accelerator: Option<String>,
+) -> Result<Keymap, String> {
+    {
+        let mut keymap = KEYMAP.write().unwrap();
+        let mut new_keymap = keymap.clone();
+        new_keymap
+            .rebind(action, accelerator.as_deref())
+            .map_err(|err| err.to_string())?;
+        if let Some(old_accelerator) = keymap.get(action) {
+            let _ = app_handle
+                .global_shortcut_manager()
+                .unregister(old_accelerator);
+        }
+        if let Some(new_accelerator) = new_keymap.get(action) {
+            if let Err(err) = register_shortcut(&app_handle, action, new_accelerator) {
+                if let Some(old_accelerator) = keymap.get(action) {
+                    let _ = register_shortcut(&app_handle, action, old_accelerator);
+                }
+                return Err(err);
+            }
+        }
+        *keymap = new_keymap;
+    }
+    save_settings(&app_handle)?;
+    Ok(get_keymap())
+}
+
+fn register_shortcut(
+    app_handle: &tauri::AppHandle,
+    action: KeyAction,
+    accelerator: &str,
+) -> Result<(), String> {
+    let handler_app_handle = app_handle.clone();
+    app_handle
+        .global_shortcut_manager()
+        .register(accelerator, move || {
+            run_key_action(handler_app_handle.clone(), action)
+        })
+        .map_err(|err| format!("Failed to register {accelerator}: {err}"))
+}
+
+fn run_key_action(app_handle: tauri::AppHandle, action: KeyAction) {
+    #[cfg(debug_assertions)]
+    println!("shortcut: {action:?}");
+    match action {
+        KeyAction::Refresh => {
+            set_manual_mode(app_handle.clone(), true);
+            one_shot_emit(app_handle);
+        }
+        KeyAction::ToggleManualMode => {
+            let state = !IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed);
+            set_manual_mode(app_handle, state);
+        }
+        KeyAction::ToggleHold => {
+            set_hold(app_handle, !IS_HOLD_ON.load(Ordering::Relaxed));
+        }
+        KeyAction::CycleScopeMode => cycle_scope_mode(app_handle),
+        KeyAction::Export => {
+            if let Err(_err) = export_current(app_handle) {
+                #[cfg(debug_assertions)]
+                println!("{_err}");
+            }
+        }
+        KeyAction::SnapCaptureArea => {
+            if let Err(_err) = snap_capture_area_to_window(app_handle, None) {
+                #[cfg(debug_assertions)]
+                println!("{_err}");
+            }
+        }
+    }
+}
+
+/// Steps the waveform through luma, linear luminance, PQ and HLG
+fn cycle_scope_mode(app_handle: tauri::AppHandle) {
+    let mode = match get_waveform_mode() {
+        WaveformMode::Luma => WaveformMode::Linear,
+        WaveformMode::Linear => WaveformMode::Pq,
+        WaveformMode::Pq => WaveformMode::Hlg,
+        WaveformMode::Hlg => WaveformMode::Luma,
+    };
+    set_waveform_mode(app_handle, mode);
+}
+
+/// Starts the JSON-RPC control socket at `path` (default: `$XDG_RUNTIME_DIR/vector-scope.sock`)
+/// and returns the path.
+#[tauri::command]
+pub fn start_control_socket(
+    app_handle: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<String, String> {
+    #[cfg(unix)]
+    {
+        let path = path.map_or_else(control_socket::default_path, PathBuf::from);
+        let mut control_socket = CONTROL_SOCKET.write().unwrap();
+        if let Some(socket) = control_socket.take() {
+            socket.stop();
+        }
+        let handler_app_handle = app_handle.clone();
+        let socket = ControlSocket::start(
+            &path,
+            Arc::new(move |method: &str, params: Value| {
+                handle_control_request(&handler_app_handle, method, params)
+            }),
+        )
+        .map_err(|err| err.to_string())?;
+        let path = socket.path().to_string_lossy().into_owned();
+        *control_socket = Some(socket);
+        drop(control_socket);
+        update_tray_menu(&app_handle);
+        Ok(path)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (app_handle, path);
+        Err("The control socket is only available on Unix".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn stop_control_socket(app_handle: tauri::AppHandle) {
+    #[cfg(unix)]
+    if let Some(socket) = CONTROL_SOCKET.write().unwrap().take() {
+        socket.stop();
+    }
+    update_tray_menu(&app_handle);
+}
+
+#[tauri::command]
+pub fn is_control_socket_running() -> bool {
+    #[cfg(unix)]
+    return CONTROL_SOCKET.read().unwrap().is_some();
+    #[cfg(not(unix))]
+    return false;
+}
+
+#[derive(Deserialize)]
+struct CaptureAreaParams {
+    top_left: (f64, f64),
+    bottom_right: (f64, f64),
+    space: Option<CoordinateSpace>,
+}
+
+#[derive(Deserialize)]
+struct StateParams {
+    state: bool,
+}
+
+#[derive(Deserialize)]
+struct ModeParams<T> {
+    mode: T,
+}
+
+#[derive(Deserialize)]
+struct SettingsParams<T> {
+    settings: T,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct FormatPathParams<T> {
+    path: String,
+    format: Option<T>,
+}
+
+/// Methods of the control socket. Names and params are the same as the Tauri commands.
+fn handle_control_request(
+    app_handle: &tauri::AppHandle,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let app_handle = app_handle.clone();
+    match method {
+        // capture area and refresh
+        "set_capture_area" => {
+            let params: CaptureAreaParams = json_rpc::params(params)?;
+            command_result(set_capture_area(
+                app_handle,
+                params.top_left,
+                params.bottom_right,
+                params.space,
+            ))
+        }
+        "snap_capture_area_to_window" => {
+            #[derive(Deserialize)]
+            struct InsetParams {
+                inset: Option<u32>,
+            }
+            let params: InsetParams = json_rpc::params(params)?;
+            command_result(snap_capture_area_to_window(app_handle, params.inset))
+        }
+        "initialize_capture_area" => {
+            initialize_capture_area(app_handle);
+            Ok(Value::Null)
+        }
+        "set_exclude_own_windows" => {
+            let params: StateParams = json_rpc::params(params)?;
+            set_exclude_own_windows(app_handle, params.state);
+            Ok(Value::Null)
+        }
+        "is_excluding_own_windows" => to_rpc_result(is_excluding_own_windows()),
+        "set_manual_mode" => {
+            let params: StateParams = json_rpc::params(params)?;
+            set_manual_mode(app_handle, params.state);
+            Ok(Value::Null)
+        }
+        "set_refresh_interval" => {
+            #[derive(Deserialize)]
+            struct IntervalParams {
+                interval_ms: u64,
+            }
+            let params: IntervalParams = json_rpc::params(params)?;
+            set_refresh_interval(app_handle, params.interval_ms);
+            Ok(Value::Null)
+        }
+        "get_refresh_interval" => to_rpc_result(get_refresh_interval()),
+        "one_shot_emit" => {
+            one_shot_emit(app_handle);
+            Ok(Value::Null)
+        }
+        "set_hold" => {
+            let params: StateParams = json_rpc::params(params)?;
+            to_rpc_result(set_hold(app_handle, params.state))
+        }
+        "set_peak_hold" => {
+            let params: StateParams = json_rpc::params(params)?;
+            set_peak_hold(params.state);
+            Ok(Value::Null)
+        }
+
+        // modes
+        "set_reference_mode" => {
+            let params: ModeParams<ReferenceMode> = json_rpc::params(params)?;
+            set_reference_mode(app_handle, params.mode);
+            Ok(Value::Null)
+        }
+        "get_reference_mode" => to_rpc_result(get_reference_mode()),
+        "set_hdr_mode" => {
+            let params: ModeParams<HdrMode> = json_rpc::params(params)?;
+            set_hdr_mode(app_handle, params.mode);
+            Ok(Value::Null)
+        }
+        "get_hdr_mode" => to_rpc_result(get_hdr_mode()),
+        "set_luminance_settings" => {
+            let params: SettingsParams<LuminanceSettings> = json_rpc::params(params)?;
+            set_luminance_settings(app_handle, params.settings);
+            Ok(Value::Null)
+        }
+        "get_luminance_settings" => to_rpc_result(get_luminance_settings()),
+        "set_false_color_settings" => {
+            let params: SettingsParams<FalseColorSettings> = json_rpc::params(params)?;
+            set_false_color_settings(app_handle, params.settings);
+            Ok(Value::Null)
+        }
+        "get_false_color_settings" => to_rpc_result(get_false_color_settings()),
+        "set_input_color_space" => {
+            #[derive(Deserialize)]
+            struct SpaceParams {
+                space: InputColorSpace,
+            }
+            let params: SpaceParams = json_rpc::params(params)?;
+            command_result(set_input_color_space(app_handle, params.space))
+        }
+        "get_input_color_space" => to_rpc_result(get_input_color_space()),
+        "set_legal_range_check" => {
+            let params: StateParams = json_rpc::params(params)?;
+            set_legal_range_check(app_handle, params.state);
+            Ok(Value::Null)
+        }
+        "set_legal_range_thresholds" => {
+            #[derive(Deserialize)]
+            struct ThresholdsParams {
+                thresholds: LegalRangeThresholds,
+            }
+            let params: ThresholdsParams = json_rpc::params(params)?;
+            set_legal_range_thresholds(params.thresholds);
+            Ok(Value::Null)
+        }
+        "get_legal_range_thresholds" => to_rpc_result(get_legal_range_thresholds()),
+
+        // sources
+        "open_image_file" => {
+            let params: PathParams = json_rpc::params(params)?;
+            command_result(open_image_file(app_handle, params.path))
+        }
+        "open_video_file" => {
+            let params: FormatPathParams<YuvFormat> = json_rpc::params(params)?;
+            command_result(open_video_file(app_handle, params.path, params.format))
+        }
+        "analyze_clipboard_image" => command_result(analyze_clipboard_image(app_handle)),
+        "open_test_pattern" => {
+            #[derive(Deserialize)]
+            struct PatternParams {
+                pattern: TestPattern,
+            }
+            let params: PatternParams = json_rpc::params(params)?;
+            to_rpc_result(open_test_pattern(app_handle, params.pattern))
+        }
+        "use_screen_source" => {
+            use_screen_source(app_handle);
+            Ok(Value::Null)
+        }
+        "get_frame_source" => to_rpc_result(get_frame_source()),
+        "play_video" => to_rpc_result(play_video(app_handle)),
+        "pause_video" => {
+            pause_video();
+            Ok(Value::Null)
+        }
+        "seek_video" => {
+            #[derive(Deserialize)]
+            struct PositionParams {
+                position: usize,
+            }
+            let params: PositionParams = json_rpc::params(params)?;
+            command_result(seek_video(app_handle, params.position))
+        }
+        "step_video" => {
+            #[derive(Deserialize)]
+            struct OffsetParams {
+                offset: i64,
+            }
+            let params: OffsetParams = json_rpc::params(params)?;
+            command_result(step_video(app_handle, params.offset))
+        }
+        "is_video_playing" => to_rpc_result(is_video_playing()),
+
+        // statistics
+        "get_statistics" => to_rpc_result(color_statistics::calculate(&apply_input_transform(
+            get_frame_to_analyze(&app_handle),
+        ))),
+        "get_legal_range" => to_rpc_result(legal_range::check(
+            &apply_input_transform(get_frame_to_analyze(&app_handle)),
+            &LEGAL_RANGE_THRESHOLDS.read().unwrap(),
+        )),
+        "get_palette" => to_rpc_result(get_palette(app_handle)),
+
+        // exports
+        "export_current" => command_result(export_current(app_handle)),
+        "set_export_directory" => {
+            let params: PathParams = json_rpc::params(params)?;
+            set_export_directory(params.path);
+            Ok(Value::Null)
+        }
+        "get_export_directory" => to_rpc_result(get_export_directory()),
+        "start_recording" => {
+            let params: FormatPathParams<RecordingFormat> = json_rpc::params(params)?;
+            command_result(start_recording(app_handle, params.path, params.format))
+        }
+        "stop_recording" => to_rpc_result(stop_recording(app_handle)),
+        "is_recording" => to_rpc_result(is_recording()),
+
+        _ => Err(RpcError::method_not_found(method)),
+    }
+}
+
+fn to_rpc_result<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(RpcError::server_error)
+}
+
+/// Maps the error of a command to a JSON-RPC error
+fn command_result<T: Serialize>(result: Result<T, String>) -> Result<Value, RpcError> {
+    to_rpc_result(result.map_err(RpcError::server_error)?)
+}
+
+fn check_thread_need_to_be_keep_alive(app_handle: tauri::AppHandle) {
+    if (IS_VECTOR_SCOPE_WINDOW_OPEN.load(Ordering::Relaxed)
+        || IS_WAVEFORM_WINDOW_OPEN.load(Ordering::Relaxed)
+        || IS_PALETTE_WINDOW_OPEN.load(Ordering::Relaxed)
+        || IS_FALSE_COLOR_WINDOW_OPEN.load(Ordering::Relaxed)
+        || is_recording()
+        || is_scope_server_running())
+        && !IS_MANUAL_REFRESH_MODE_ON.load(Ordering::Relaxed)
+    {
+        if !THREAD_IMAGE_PROCESS
+            .try_read()
+            .expect("Failed to read thread")
+            .worker_thread
+            .keep_alive
+            .load(Ordering::Relaxed)
+        {
+            #[cfg(debug_assertions)]
+            println!("Thread: Start");
+            THREAD_IMAGE_PROCESS
+                .try_read()
+                .expect("Failed to read thread")
+                .run(app_handle)
+        } else {
+            #[cfg(debug_assertions)]
+            println!("Thread: Already started");
+        }
+    } else {
+        if THREAD_IMAGE_PROCESS
+            .try_read()
+            .expect("Failed to read thread")
+            .worker_thread
+            .keep_alive
+            .load(Ordering::Relaxed)
+        {
+            #[cfg(debug_assertions)]
+            println!("Thread: Stop");
+            THREAD_IMAGE_PROCESS
+                .try_read()
+                .expect("Failed to read thread")
+                .stop()
+        }
+    }
+}
+
+fn render_vector_scope(
+    screenshot: &Frame,
+    ycbcr: Option<&YuvFrame>,
+    reference: Option<&Frame>,
+) -> Vec<u8> {
+    let vector_scope_image = match (reference, ycbcr) {
+        (Some(reference), _) => graph_plotter::draw_vector_scope_with_reference(
+            &screenshot,
+            reference,
+            get_reference_mode(),
+        ),
+        (None, Some(ycbcr)) => graph_plotter::draw_vector_scope_ycbcr(ycbcr),
+        (None, None) => graph_plotter::draw_vector_scope(&screenshot),
+    }
+    .expect("Failed to draw vector scope");
+    let vector_scope_image = if IS_PEAK_HOLD_ON.load(Ordering::Relaxed) {
+        PEAK_HOLD_VECTOR_SCOPE
+            .write()
+            .unwrap()
+            .accumulate(&vector_scope_image)
+            .expect("Failed to accumulate vector scope")
+    } else {
+        vector_scope_image
+    };
+    *LAST_VECTOR_SCOPE_IMAGE.write().unwrap() = vector_scope_image.clone();
+    vector_scope_image
+}
+
+fn get_vector_scope_image_as_base64(
+    screenshot: &Frame,
+    ycbcr: Option<&YuvFrame>,
+    reference: Option<&Frame>,
+) -> String {
+    let vector_scope_image = render_vector_scope(screenshot, ycbcr, reference);
+    let base64_vector_scope = BASE64_ENGINE
+        .get_or_init(init_base64_engine)
+        .encode(vector_scope_image);
+    PREFIX_DATA_URI.to_string() + &base64_vector_scope
+}
+
+fn render_waveform(screenshot: &Frame, reference: Option<&Frame>) -> Vec<u8> {
+    let hdr_mode = *HDR_MODE.read().unwrap();
+    let waveform_image = if hdr_mode != HdrMode::Off {
+        // the nits scale has no reference comparison
+        graph_plotter::draw_waveform_hdr(screenshot, &NITS_CONVERTER.read().unwrap())
+    } else {
+        match reference {
+            Some(reference) => graph_plotter::draw_waveform_luminance_with_reference(
+                &screenshot,
+                reference,
+                get_reference_mode(),
+                &LUMINANCE_METER.read().unwrap(),
+            ),
+            None => graph_plotter::draw_waveform_luminance(
+                &screenshot,
+                &LUMINANCE_METER.read().unwrap(),
+            ),
+        }
+    }
+    .expect("Failed to draw waveform");
+    let waveform_image = if IS_PEAK_HOLD_ON.load(Ordering::Relaxed) {
+        PEAK_HOLD_WAVEFORM
+            .write()
+            .unwrap()
+            .accumulate(&waveform_image)
+            .expect("Failed to accumulate waveform")
+    } else {
+        waveform_image
+    };
+    *LAST_WAVEFORM_IMAGE.write().unwrap() = waveform_image.clone();
+    waveform_image
+}
+
+/// `None` unless the waveform is in an HDR mode
+fn measure_hdr_statistics(screenshot: &Frame) -> Option<HdrStatistics> {
+    let hdr_mode = *HDR_MODE.read().unwrap();
+    if hdr_mode == HdrMode::Off {
+        return None;
+    }
+    Some(
+        LIGHT_LEVEL_METER
+            .write()
+            .unwrap()
+            .measure(screenshot, &NITS_CONVERTER.read().unwrap()),
+    )
+}
+
+fn get_waveform_image_as_base64(screenshot: &Frame, reference: Option<&Frame>) -> String {
+    let waveform_image = render_waveform(screenshot, reference);
+    let base64_waveform = BASE64_ENGINE
+        .get_or_init(init_base64_engine)
+        .encode(waveform_image);
+    PREFIX_DATA_URI.to_string() + &base64_waveform
+}
+
+fn render_false_color(screenshot: &Frame) -> Vec<u8> {
+    let false_color_image = graph_plotter::draw_false_color(
+        screenshot,
+        &FALSE_COLOR_SETTINGS.read().unwrap(),
+        &LEGAL_RANGE_THRESHOLDS.read().unwrap(),
+    )
+    .expect("Failed to draw false color");
+    *LAST_FALSE_COLOR_IMAGE.write().unwrap() = false_color_image.clone();
+    false_color_image
+}
+
+fn get_false_color_image_as_base64(screenshot: &Frame) -> String {
+    let false_color_image = render_false_color(screenshot);
+    let base64_false_color = BASE64_ENGINE
+        .get_or_init(init_base64_engine)
+        .encode(false_color_image);
+    PREFIX_DATA_URI.to_string() + &base64_false_color
+}
+
+fn render_palette(palette: &[PaletteColor]) -> Vec<u8> {
+    let palette_image = graph_plotter::draw_palette(palette).expect("Failed to draw palette");
+    *LAST_PALETTE_IMAGE.write().unwrap() = palette_image.clone();
+    palette_image
+}
+
+fn get_palette_image_as_base64(palette: &[PaletteColor]) -> String {
+    let palette_image = render_palette(palette);
+    let base64_palette = BASE64_ENGINE
+        .get_or_init(init_base64_engine)
+        .encode(palette_image);
+    PREFIX_DATA_URI.to_string() + &base64_palette
+}
+
+fn update_palette(screenshot: &Frame) -> Vec<PaletteColor> {
+    let palette =
+        color_palette::extract_palette(screenshot, PALETTE_COLOR_COUNT.load(Ordering::Relaxed));
+    *LAST_PALETTE.write().unwrap() = palette.clone();
+    palette
+}
+
+/// Returns the frame of the opened file, the held frame while hold is on,
+/// otherwise captures a new frame and records it in the frame history.
+fn get_frame_to_analyze(app_handle: &tauri::AppHandle) -> Arc<Frame> {
+    if let Some(frame) = FRAME_SOURCE.read().unwrap().frame() {
+        return frame;
+    }
+    if IS_HOLD_ON.load(Ordering::Relaxed) {
+        if let Some(frame) = FRAME_HISTORY.read().unwrap().current() {
+            return frame;
+        }
+    }
+    let frame = Arc::new(capture_screenshot(app_handle));
+    FRAME_HISTORY.write().unwrap().push(Arc::clone(&frame));
+    frame
+}
+
+/// Y′CbCr planes of a video frame for the vector scope.
+/// Only while no color conversion or LUT is active, as the planes are not transformed.
+fn native_ycbcr<'a>(
+    frame: &Arc<Frame>,
+    transformed: &Arc<Frame>,
+    video_frame: Option<&'a VideoFrame>,
+) -> Option<&'a YuvFrame> {
+    match Arc::ptr_eq(frame, transformed) {
+        true => video_frame.map(|video_frame| video_frame.ycbcr.as_ref()),
+        false => None,
+    }
+}
+
+/// Frame as seen by the scopes. The history keeps the untransformed capture
+/// so that changing the color space or the LUT also applies to held frames.
+fn apply_input_transform(frame: Arc<Frame>) -> Arc<Frame> {
+    match transform_image(&frame) {
+        Some(transformed) => Arc::new(transformed),
+        None => frame,
+    }
+}
+
+/// Reference still as seen by the scopes. It is stored untransformed like the
+/// frame history, so it goes through the same color conversion and LUT as the frame.
+fn reference_image() -> Option<Arc<Frame>> {
+    REFERENCE_IMAGE
+        .read()
+        .unwrap()
+        .clone()
+        .map(apply_input_transform)
+}
+
+/// Converts to the working color space, then applies the LUT.
+/// `None` when no transform is active
+fn transform_image(image: &Frame) -> Option<Frame> {
+    let conversion = COLOR_CONVERSION.read().unwrap();
+    let converted = match conversion.is_identity() {
+        true => None,
+        false => Some(conversion.apply_to_frame(image)),
+    };
+    match CURRENT_LUT.read().unwrap().as_ref() {
+        Some(lut) => Some(lut.apply_to_frame(
+            converted.as_ref().unwrap_or(image),
+            *LUT_INTERPOLATION.read().unwrap(),
+        )),
+        None => converted,
+    }
+}
+
+fn capture_screenshot(app_handle: &tauri::AppHandle) -> Frame {
+    let area = *CAPTURE_AREA.read().unwrap();
+    // the entire screen when the display of the area has gone
+    let (screenshot, origin) = match area
+        .and_then(|area| Some((screenshot_capture::capture_area(&area)?, area.top_left)))
+    {
+        Some(captured) => captured,
+        None => (
+            screenshot_capture::capture_entire_sreen(),
+            screenshot_capture::entire_screen_origin(),
+        ),
+    };
+    let mut frame = Frame::from(screenshot);
+    if IS_EXCLUDING_OWN_WINDOWS.load(Ordering::Relaxed) {
+        exclude_own_windows(app_handle, &mut frame, origin);
+    }
+    frame
+}
+
+/// Excludes the app's visible windows from a capture whose top left pixel is at `origin`,
+/// so that scope windows over the captured area are not analyzed.
+/// Content protection hides them from captures on macOS and Windows only.
+fn exclude_own_windows(app_handle: &tauri::AppHandle, frame: &mut Frame, origin: (i32, i32)) {
+    #[cfg(target_os = "macos")]
+    let layout = DisplayLayout::current();
+    for window in app_handle.windows().values() {
+        if !window.is_visible().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+            continue;
+        }
+        // physical pixels, as the capture
+        if let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) {
+            // macOS scales the position in points by the factor of the window's display,
+            // the desktop is laid out as in `DisplayLayout` from the same points
+            #[cfg(target_os = "macos")]
+            let position = {
+                let logical = position.to_logical::<f64>(window.scale_factor().unwrap_or(1.0));
+                layout
+                    .to_physical((logical.x, logical.y), CoordinateSpace::Logical)
+                    .map_or(position, |(x, y)| {
+                        tauri::PhysicalPosition::new(x.round() as i32, y.round() as i32)
+                    })
+            };
+            frame.exclude_area(
+                position.x - origin.0,
+                position.y - origin.1,
+                size.width,
+                size.height,
+            );
         }
-        false => screenshot_capture::capture_entire_sreen(),
     }
 }
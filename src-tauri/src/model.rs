@@ -1,4 +1,33 @@
+pub mod capture_area;
+pub mod clipboard_image;
+pub mod color_palette;
+pub mod color_space;
+pub mod color_statistics;
+#[cfg(unix)]
+pub mod control_socket;
+pub mod display_layout;
+pub mod false_color;
+pub mod frame;
+pub mod frame_history;
+pub mod frame_source;
 pub mod graph_plotter;
+pub mod hdr;
+pub mod image_file;
+pub mod json_rpc;
+pub mod keymap;
+pub mod legal_range;
+pub mod luminance;
+pub mod lut;
 pub mod mouse_info;
+pub mod peak_hold;
+pub mod scope_export;
+pub mod scope_server;
 pub mod screenshot_capture;
+pub mod settings;
+pub mod statistics_recorder;
+pub mod test_pattern;
+pub mod transfer_function;
+pub mod tray_icon;
+pub mod window_snap;
 pub mod worker_thread_base;
+pub mod yuv_video;
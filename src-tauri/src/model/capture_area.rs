@@ -0,0 +1,328 @@
+use crate::model::display_layout::{CoordinateSpace, DisplayLayout};
+use serde::Serialize;
+
+/// logical pixels scaled back to physical ones are off by float error, not by a pixel
+const ROUNDING_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, PartialEq)]
+pub enum CaptureAreaError {
+    /// a corner is not a finite number
+    InvalidCoordinate,
+    /// the corners share a row or a column
+    Empty,
+    /// no display overlaps the area
+    OffScreen,
+}
+
+impl std::fmt::Display for CaptureAreaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureAreaError::InvalidCoordinate => write!(f, "Capture area corner is not a number"),
+            CaptureAreaError::Empty => write!(f, "Capture area has no width or height"),
+            CaptureAreaError::OffScreen => write!(f, "Capture area is outside of every display"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureAreaError {}
+
+/// Rectangle on a single display, in physical pixels of the desktop with the bottom right exclusive.
+/// Only built by `new`, so it is never inverted or empty.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct CaptureArea {
+    /// display the area is captured from
+    pub display_id: u32,
+    pub top_left: (i32, i32),
+    pub bottom_right: (i32, i32),
+    /// `true` when the requested rectangle was cut to the display
+    pub clamped: bool,
+}
+
+impl CaptureArea {
+    /// Area between two opposite corners in any order, given in `space`.
+    /// The area is cut to the display it overlaps the most, as a capture can not span displays,
+    /// and converted with the scale factor of that display.
+    /// Fractional pixels are rounded outward.
+    pub fn new(
+        corner: (f64, f64),
+        opposite_corner: (f64, f64),
+        space: CoordinateSpace,
+        layout: &DisplayLayout,
+    ) -> Result<Self, CaptureAreaError> {
+        if ![corner.0, corner.1, opposite_corner.0, opposite_corner.1]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return Err(CaptureAreaError::InvalidCoordinate);
+        }
+        if corner.0 == opposite_corner.0 || corner.1 == opposite_corner.1 {
+            return Err(CaptureAreaError::Empty);
+        }
+        let top_left = (
+            corner.0.min(opposite_corner.0),
+            corner.1.min(opposite_corner.1),
+        );
+        let bottom_right = (
+            corner.0.max(opposite_corner.0),
+            corner.1.max(opposite_corner.1),
+        );
+
+        let display = layout
+            .displays()
+            .iter()
+            .filter_map(|display| {
+                let (left, top, right, bottom) = display.bounds(space);
+                let width = bottom_right.0.min(right) - top_left.0.max(left);
+                let height = bottom_right.1.min(bottom) - top_left.1.max(top);
+                (width > 0.0 && height > 0.0).then_some((display, width * height))
+            })
+            .max_by(|(_, overlap), (_, other)| overlap.total_cmp(other))
+            .map(|(display, _)| display)
+            .ok_or(CaptureAreaError::OffScreen)?;
+
+        let top_left = display.to_physical(top_left, space);
+        let bottom_right = display.to_physical(bottom_right, space);
+        let left = (top_left.0 + ROUNDING_TOLERANCE).floor() as i32;
+        let top = (top_left.1 + ROUNDING_TOLERANCE).floor() as i32;
+        // at least a pixel, however thin the area is
+        let requested = (
+            left,
+            top,
+            ((bottom_right.0 - ROUNDING_TOLERANCE).ceil() as i32).max(left + 1),
+            ((bottom_right.1 - ROUNDING_TOLERANCE).ceil() as i32).max(top + 1),
+        );
+        let clamped = (
+            requested.0.max(display.x),
+            requested.1.max(display.y),
+            requested.2.min(display.x + display.width as i32),
+            requested.3.min(display.y + display.height as i32),
+        );
+
+        Ok(Self {
+            display_id: display.id,
+            top_left: (clamped.0, clamped.1),
+            bottom_right: (clamped.2, clamped.3),
+            clamped: clamped != requested,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        (self.bottom_right.0 - self.top_left.0) as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.bottom_right.1 - self.top_left.1) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::display_layout::tests::mixed_layout;
+
+    fn area(
+        corner: (f64, f64),
+        opposite_corner: (f64, f64),
+        space: CoordinateSpace,
+    ) -> Result<CaptureArea, CaptureAreaError> {
+        CaptureArea::new(corner, opposite_corner, space, &mixed_layout())
+    }
+
+    #[test]
+    fn takes_corners_in_any_order() {
+        let expected = area((100.0, 50.0), (300.0, 150.0), CoordinateSpace::Physical);
+        assert!(expected.is_ok());
+        for (corner, opposite_corner) in [
+            ((300.0, 150.0), (100.0, 50.0)),
+            ((100.0, 150.0), (300.0, 50.0)),
+            ((300.0, 50.0), (100.0, 150.0)),
+        ] {
+            assert_eq!(
+                area(corner, opposite_corner, CoordinateSpace::Physical),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_empty_areas() {
+        assert_eq!(
+            area((100.0, 50.0), (100.0, 150.0), CoordinateSpace::Physical),
+            Err(CaptureAreaError::Empty)
+        );
+        assert_eq!(
+            area((100.0, 50.0), (300.0, 50.0), CoordinateSpace::Logical),
+            Err(CaptureAreaError::Empty)
+        );
+        // thinner than a pixel still captures one
+        assert_eq!(
+            area((100.2, 50.0), (100.4, 150.0), CoordinateSpace::Physical).map(|area| area.width()),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn refuses_invalid_coordinates() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            for (corner, opposite_corner) in [
+                ((value, 50.0), (300.0, 150.0)),
+                ((100.0, value), (300.0, 150.0)),
+                ((100.0, 50.0), (value, 150.0)),
+                ((100.0, 50.0), (300.0, value)),
+            ] {
+                assert_eq!(
+                    area(corner, opposite_corner, CoordinateSpace::Physical),
+                    Err(CaptureAreaError::InvalidCoordinate)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_areas_off_screen() {
+        for (corner, opposite_corner) in [
+            ((-200.0, -200.0), (-100.0, -100.0)),
+            ((6000.0, 0.0), (6100.0, 100.0)),
+            ((100.0, 2160.0), (200.0, 2300.0)),
+            // touching the right edge, which is exclusive
+            ((5760.0, 0.0), (5800.0, 100.0)),
+        ] {
+            assert_eq!(
+                area(corner, opposite_corner, CoordinateSpace::Physical),
+                Err(CaptureAreaError::OffScreen)
+            );
+        }
+        assert_eq!(
+            CaptureArea::new(
+                (0.0, 0.0),
+                (100.0, 100.0),
+                CoordinateSpace::Physical,
+                &DisplayLayout::default()
+            ),
+            Err(CaptureAreaError::OffScreen)
+        );
+    }
+
+    #[test]
+    fn cuts_areas_partially_off_screen() {
+        assert_eq!(
+            area((-50.0, -20.0), (100.0, 80.0), CoordinateSpace::Physical),
+            Ok(CaptureArea {
+                display_id: 2,
+                top_left: (0, 0),
+                bottom_right: (100, 80),
+                clamped: true,
+            })
+        );
+        // below the shorter display
+        assert_eq!(
+            area(
+                (5000.0, 1000.0),
+                (6000.0, 1200.0),
+                CoordinateSpace::Physical
+            ),
+            Ok(CaptureArea {
+                display_id: 1,
+                top_left: (5000, 1000),
+                bottom_right: (5760, 1080),
+                clamped: true,
+            })
+        );
+    }
+
+    #[test]
+    fn converts_logical_pixels_with_the_scale_of_the_display() {
+        assert_eq!(
+            area((100.0, 50.0), (300.0, 150.0), CoordinateSpace::Logical),
+            Ok(CaptureArea {
+                display_id: 2,
+                top_left: (200, 100),
+                bottom_right: (600, 300),
+                clamped: false,
+            })
+        );
+        assert_eq!(
+            area((4000.0, 50.0), (4200.0, 150.0), CoordinateSpace::Logical),
+            Ok(CaptureArea {
+                display_id: 1,
+                top_left: (4000, 50),
+                bottom_right: (4200, 150),
+                clamped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_physical_pixels() {
+        assert_eq!(
+            area((100.0, 50.0), (300.0, 150.0), CoordinateSpace::Physical),
+            Ok(CaptureArea {
+                display_id: 2,
+                top_left: (100, 50),
+                bottom_right: (300, 150),
+                clamped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn rounds_outward() {
+        // 20.6, 41.4 - 200.4, 100.2 in physical pixels
+        assert_eq!(
+            area((10.3, 20.7), (100.2, 50.1), CoordinateSpace::Logical),
+            Ok(CaptureArea {
+                display_id: 2,
+                top_left: (20, 41),
+                bottom_right: (201, 101),
+                clamped: false,
+            })
+        );
+        assert_eq!(
+            area((3900.5, 0.5), (3999.5, 99.5), CoordinateSpace::Physical),
+            Ok(CaptureArea {
+                display_id: 1,
+                top_left: (3900, 0),
+                bottom_right: (4000, 100),
+                clamped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn cuts_to_the_display_with_the_largest_overlap() {
+        // 840 physical pixels on the 200% display, 160 on the other one
+        assert_eq!(
+            area((3000.0, 100.0), (4000.0, 200.0), CoordinateSpace::Physical),
+            Ok(CaptureArea {
+                display_id: 2,
+                top_left: (3000, 100),
+                bottom_right: (3840, 200),
+                clamped: true,
+            })
+        );
+        // 120 logical pixels on the 200% display, 160 on the other one
+        assert_eq!(
+            area((1800.0, 100.0), (4000.0, 200.0), CoordinateSpace::Logical),
+            Ok(CaptureArea {
+                display_id: 1,
+                top_left: (3840, 100),
+                bottom_right: (4000, 200),
+                clamped: true,
+            })
+        );
+    }
+
+    #[test]
+    fn logical_gap_between_displays_is_off_screen() {
+        assert_eq!(
+            area((2000.0, 100.0), (3000.0, 200.0), CoordinateSpace::Logical),
+            Err(CaptureAreaError::OffScreen)
+        );
+        // the same numbers are on the 200% display in physical pixels
+        assert_eq!(
+            area((2000.0, 100.0), (3000.0, 200.0), CoordinateSpace::Physical)
+                .map(|area| area.display_id),
+            Ok(2)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::model::frame::{Frame, FrameSamples};
+
+#[derive(Debug)]
+pub enum ClipboardError {
+    /// the clipboard could not be opened or read
+    Unavailable(String),
+    NoImage,
+}
+
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardError::Unavailable(message) => {
+                write!(f, "Failed to read the clipboard: {message}")
+            }
+            ClipboardError::NoImage => write!(f, "The clipboard does not contain an image"),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(err: arboard::Error) -> Self {
+        match err {
+            arboard::Error::ContentNotAvailable | arboard::Error::ConversionFailure => {
+                ClipboardError::NoImage
+            }
+            _ => ClipboardError::Unavailable(err.to_string()),
+        }
+    }
+}
+
+/// Reads the image on the system clipboard as an 8-bit frame
+pub fn read_image() -> Result<Frame, ClipboardError> {
+    let image = arboard::Clipboard::new()?.get_image()?;
+    if image.width == 0 || image.height == 0 {
+        return Err(ClipboardError::NoImage);
+    }
+    Ok(Frame::new(
+        image.width as u32,
+        image.height as u32,
+        FrameSamples::Rgba8(image.bytes.into_owned()),
+    ))
+}
@@ -0,0 +1,373 @@
+use crate::model::frame::Frame;
+use serde::{Deserialize, Serialize};
+
+const PALETTE_SAMPLE_SKIP_RATIO: usize = 16;
+const KMEANS_MAX_ITERATION: usize = 24;
+const KMEANS_SEED: u64 = 0x5EED_C0105;
+pub const PALETTE_COLOR_COUNT_MIN: usize = 1;
+pub const PALETTE_COLOR_COUNT_MAX: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PaletteColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    /// share of the analyzed pixels assigned to this color (0.0 - 100.0)
+    pub coverage: f64,
+}
+
+impl PaletteColor {
+    pub fn hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaletteExportFormat {
+    Json,
+    Gpl,
+    Ase,
+}
+
+/// Small xorshift generator so the clustering result is reproducible
+/// for the same frame.
+struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[inline(always)]
+fn distance_squared(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    let d0 = a[0] - b[0];
+    let d1 = a[1] - b[1];
+    let d2 = a[2] - b[2];
+    d0 * d0 + d1 * d1 + d2 * d2
+}
+
+fn nearest_center(sample: &[f64; 3], centers: &[[f64; 3]]) -> usize {
+    let mut nearest = 0;
+    let mut nearest_distance = f64::MAX;
+    for (index, center) in centers.iter().enumerate() {
+        let distance = distance_squared(sample, center);
+        if distance < nearest_distance {
+            nearest = index;
+            nearest_distance = distance;
+        }
+    }
+    nearest
+}
+
+/// k-means++ seeding with a fixed seed
+fn initial_centers(samples: &[[f64; 3]], color_count: usize) -> Vec<[f64; 3]> {
+    let mut random = XorShift64::new(KMEANS_SEED);
+    let mut centers: Vec<[f64; 3]> = Vec::with_capacity(color_count);
+    centers.push(samples[(random.next_u64() % samples.len() as u64) as usize]);
+
+    let mut distances: Vec<f64> = samples
+        .iter()
+        .map(|sample| distance_squared(sample, &centers[0]))
+        .collect();
+    while centers.len() < color_count {
+        let total: f64 = distances.iter().sum();
+        if total <= 0.0 {
+            // fewer distinct colors than requested
+            break;
+        }
+        let mut target = random.next_f64() * total;
+        let mut chosen = samples.len() - 1;
+        for (index, distance) in distances.iter().enumerate() {
+            target -= distance;
+            if target <= 0.0 {
+                chosen = index;
+                break;
+            }
+        }
+        let center = samples[chosen];
+        for (index, sample) in samples.iter().enumerate() {
+            distances[index] = distances[index].min(distance_squared(sample, &center));
+        }
+        centers.push(center);
+    }
+    centers
+}
+
+/// Returns the dominant colors of `image` sorted by coverage (highest first).
+pub fn extract_palette(image: &Frame, color_count: usize) -> Vec<PaletteColor> {
+    let color_count = color_count.clamp(PALETTE_COLOR_COUNT_MIN, PALETTE_COLOR_COUNT_MAX);
+    let samples: Vec<[f64; 3]> = image
+        .analyzed_pixels()
+        .step_by(PALETTE_SAMPLE_SKIP_RATIO)
+        .map(|rgb| rgb.map(|value| value.clamp(0.0, 1.0) as f64 * 255.0))
+        .collect();
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let mut centers = initial_centers(&samples, color_count);
+    let mut assignments = vec![0usize; samples.len()];
+    for iteration in 0..KMEANS_MAX_ITERATION {
+        let mut changed = false;
+        for (index, sample) in samples.iter().enumerate() {
+            let nearest = nearest_center(sample, &centers);
+            if assignments[index] != nearest {
+                assignments[index] = nearest;
+                changed = true;
+            }
+        }
+        if iteration > 0 && !changed {
+            break;
+        }
+
+        let mut sums = vec![[0f64; 3]; centers.len()];
+        let mut counts = vec![0usize; centers.len()];
+        for (sample, assignment) in samples.iter().zip(assignments.iter()) {
+            sums[*assignment][0] += sample[0];
+            sums[*assignment][1] += sample[1];
+            sums[*assignment][2] += sample[2];
+            counts[*assignment] += 1;
+        }
+        for (index, center) in centers.iter_mut().enumerate() {
+            // keep empty clusters where they are
+            if counts[index] > 0 {
+                let count = counts[index] as f64;
+                *center = [
+                    sums[index][0] / count,
+                    sums[index][1] / count,
+                    sums[index][2] / count,
+                ];
+            }
+        }
+    }
+
+    let mut counts = vec![0usize; centers.len()];
+    for assignment in assignments.iter() {
+        counts[*assignment] += 1;
+    }
+    let mut palette: Vec<PaletteColor> = centers
+        .iter()
+        .zip(counts.iter())
+        .filter(|(_, count)| **count > 0)
+        .map(|(center, count)| PaletteColor {
+            red: center[0].round() as u8,
+            green: center[1].round() as u8,
+            blue: center[2].round() as u8,
+            coverage: *count as f64 * 100.0 / samples.len() as f64,
+        })
+        .collect();
+    palette.sort_by(|a, b| b.coverage.total_cmp(&a.coverage));
+    palette
+}
+
+pub fn to_json(palette: &[PaletteColor]) -> String {
+    let colors: Vec<serde_json::Value> = palette
+        .iter()
+        .map(|color| {
+            serde_json::json!({
+                "hex": color.hex(),
+                "red": color.red,
+                "green": color.green,
+                "blue": color.blue,
+                "coverage": color.coverage,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "colors": colors }))
+        .expect("Failed to serialize palette")
+}
+
+/// GIMP palette (.gpl)
+pub fn to_gpl(palette: &[PaletteColor], name: &str) -> String {
+    let mut gpl = format!(
+        "GIMP Palette\nName: {name}\nColumns: {}\n#\n",
+        palette.len()
+    );
+    for color in palette {
+        gpl += &format!(
+            "{:3} {:3} {:3}\t{} ({:.1}%)\n",
+            color.red,
+            color.green,
+            color.blue,
+            color.hex(),
+            color.coverage
+        );
+    }
+    gpl
+}
+
+/// Adobe Swatch Exchange (.ase)
+pub fn to_ase(palette: &[PaletteColor]) -> Vec<u8> {
+    const BLOCK_TYPE_COLOR_ENTRY: u16 = 0x0001;
+    const COLOR_TYPE_NORMAL: u16 = 2;
+
+    let mut ase: Vec<u8> = Vec::new();
+    ase.extend_from_slice(b"ASEF");
+    ase.extend_from_slice(&1u16.to_be_bytes());
+    ase.extend_from_slice(&0u16.to_be_bytes());
+    ase.extend_from_slice(&(palette.len() as u32).to_be_bytes());
+
+    for color in palette {
+        // name is UTF-16BE with a trailing null, length counted in code units
+        let name: Vec<u16> = color.hex().encode_utf16().chain(Some(0)).collect();
+        let mut block: Vec<u8> = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        for unit in name {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(b"RGB ");
+        for channel in [color.red, color.green, color.blue] {
+            block.extend_from_slice(&(channel as f32 / 255.0).to_be_bytes());
+        }
+        block.extend_from_slice(&COLOR_TYPE_NORMAL.to_be_bytes());
+
+        ase.extend_from_slice(&BLOCK_TYPE_COLOR_ENTRY.to_be_bytes());
+        ase.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        ase.extend_from_slice(&block);
+    }
+    ase
+}
+
+pub fn export_palette(
+    palette: &[PaletteColor],
+    path: &std::path::Path,
+    format: PaletteExportFormat,
+) -> std::io::Result<()> {
+    let contents = match format {
+        PaletteExportFormat::Json => to_json(palette).into_bytes(),
+        PaletteExportFormat::Gpl => {
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("Vector Scope");
+            to_gpl(palette, name).into_bytes()
+        }
+        PaletteExportFormat::Ase => to_ase(palette),
+    };
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+
+    const QUADRANT_COLORS: [[u8; 3]; 4] = [[255, 0, 0], [0, 128, 0], [0, 0, 255], [240, 240, 240]];
+
+    /// 64x64 frame with one color per quadrant
+    fn quadrant_frame() -> Frame {
+        let mut samples: Vec<u8> = Vec::new();
+        for y in 0..64 {
+            for x in 0..64 {
+                let [red, green, blue] = QUADRANT_COLORS[(y / 32) * 2 + x / 32];
+                samples.extend_from_slice(&[red, green, blue, 255]);
+            }
+        }
+        Frame::new(64, 64, FrameSamples::Rgba8(samples))
+    }
+
+    fn color(red: u8, green: u8, blue: u8) -> PaletteColor {
+        PaletteColor {
+            red,
+            green,
+            blue,
+            coverage: 0.0,
+        }
+    }
+
+    #[test]
+    fn extracts_the_same_palette_for_the_same_frame() {
+        let frame = quadrant_frame();
+        let palette = extract_palette(&frame, 4);
+        assert_eq!(palette, extract_palette(&frame, 4));
+
+        let mut colors: Vec<[u8; 3]> = palette
+            .iter()
+            .map(|color| [color.red, color.green, color.blue])
+            .collect();
+        colors.sort();
+        let mut expected = QUADRANT_COLORS.to_vec();
+        expected.sort();
+        assert_eq!(colors, expected);
+    }
+
+    #[test]
+    fn coverage_sums_to_100() {
+        let frame = quadrant_frame();
+        for color_count in [1, 2, 3, 4, 8] {
+            let palette = extract_palette(&frame, color_count);
+            let total: f64 = palette.iter().map(|color| color.coverage).sum();
+            assert!((total - 100.0).abs() < 1e-9, "{color_count}: {total}");
+            // sorted by coverage
+            assert!(palette
+                .windows(2)
+                .all(|pair| pair[0].coverage >= pair[1].coverage));
+        }
+    }
+
+    #[test]
+    fn stops_seeding_at_the_distinct_colors() {
+        let uniform = vec![[10.0, 20.0, 30.0]; 16];
+        assert_eq!(initial_centers(&uniform, 8), vec![[10.0, 20.0, 30.0]]);
+
+        let two_colors: Vec<[f64; 3]> = (0..16)
+            .map(|index| match index % 2 {
+                0 => [0.0, 0.0, 0.0],
+                _ => [255.0, 255.0, 255.0],
+            })
+            .collect();
+        assert_eq!(initial_centers(&two_colors, 5).len(), 2);
+
+        let frame = Frame::new(8, 8, FrameSamples::Rgba8([50, 100, 150, 255].repeat(64)));
+        let palette = extract_palette(&frame, 8);
+        assert_eq!(palette.len(), 1);
+        assert_eq!(palette[0].hex(), "#326496");
+        assert_eq!(palette[0].coverage, 100.0);
+    }
+
+    #[test]
+    fn writes_ase_blocks() {
+        let ase = to_ase(&[color(255, 128, 0), color(0, 0, 0)]);
+        assert_eq!(&ase[0..4], b"ASEF");
+        // version 1.0, two blocks
+        assert_eq!(&ase[4..12], &[0, 1, 0, 0, 0, 0, 0, 2]);
+
+        // color entry, name length, 8 name units, model, 3 floats, color type
+        let block_length = 2 + 8 * 2 + 4 + 3 * 4 + 2;
+        assert_eq!(&ase[12..14], &[0, 1]);
+        assert_eq!(&ase[14..18], &(block_length as u32).to_be_bytes());
+        let block = &ase[18..18 + block_length];
+        assert_eq!(&block[0..2], &8u16.to_be_bytes());
+        let name: Vec<u16> = block[2..18]
+            .chunks_exact(2)
+            .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+            .collect();
+        assert_eq!(String::from_utf16(&name).unwrap(), "#FF8000\0");
+        assert_eq!(&block[18..22], b"RGB ");
+        let channels: Vec<f32> = block[22..34]
+            .chunks_exact(4)
+            .map(|value| f32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+            .collect();
+        assert_eq!(channels, vec![1.0, 128.0 / 255.0, 0.0]);
+        assert_eq!(&block[34..36], &2u16.to_be_bytes());
+
+        assert_eq!(ase.len(), 12 + 2 * (6 + block_length));
+    }
+}
@@ -0,0 +1,312 @@
+use crate::model::frame::{Frame, FrameSamples};
+use crate::model::transfer_function::{DecodeTable, TransferFunction};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// CIE xy of D65, the white point of every built-in color space
+const WHITE_POINT_D65: (f64, f64) = (0.3127, 0.3290);
+/// CIE xy of the red, green and blue primaries
+const PRIMARIES_REC709: [(f64, f64); 3] = [(0.640, 0.330), (0.300, 0.600), (0.150, 0.060)];
+const PRIMARIES_DISPLAY_P3: [(f64, f64); 3] = [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)];
+const PRIMARIES_ADOBE_RGB: [(f64, f64); 3] = [(0.640, 0.330), (0.210, 0.710), (0.150, 0.060)];
+const PRIMARIES_REC2020: [(f64, f64); 3] = [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)];
+/// 563/256, as written in the Adobe RGB (1998) specification
+const GAMMA_ADOBE_RGB: f32 = 2.199_218_8;
+/// entries of the linear to 8-bit encoding table
+const ENCODE_TABLE_SIZE: usize = 65536;
+/// half an 8-bit step, conversion error of colors on the gamut boundary
+const GAMUT_TOLERANCE: f32 = 0.5 / 255.0;
+
+/// Color space the captured bytes are interpreted in.
+/// Frames are converted to the working space (sRGB) before analysis.
+/// Colors outside of sRGB keep their negative or above 1.0 components.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+    Rec709,
+    Rec2020,
+    /// path to an ICC profile
+    Icc(String),
+}
+
+#[derive(Debug)]
+pub enum ColorSpaceError {
+    Io(std::io::Error),
+    InvalidProfile,
+    UnsupportedProfile,
+}
+
+impl std::fmt::Display for ColorSpaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorSpaceError::Io(err) => write!(f, "{err}"),
+            ColorSpaceError::InvalidProfile => write!(f, "not a valid ICC profile"),
+            ColorSpaceError::UnsupportedProfile => {
+                write!(f, "ICC profile can not be converted to sRGB")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColorSpaceError {}
+
+impl From<std::io::Error> for ColorSpaceError {
+    fn from(err: std::io::Error) -> Self {
+        ColorSpaceError::Io(err)
+    }
+}
+
+type Matrix3 = [[f64; 3]; 3];
+
+fn multiply(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut result = [[0f64; 3]; 3];
+    for (row, result_row) in result.iter_mut().enumerate() {
+        for (column, value) in result_row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[row][k] * b[k][column]).sum();
+        }
+    }
+    result
+}
+
+fn invert(m: &Matrix3) -> Matrix3 {
+    let cofactor = |row: usize, column: usize| {
+        let (r0, r1) = ((row + 1) % 3, (row + 2) % 3);
+        let (c0, c1) = ((column + 1) % 3, (column + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant: f64 = (0..3)
+        .map(|column| m[0][column] * cofactor(0, column))
+        .sum();
+    let mut result = [[0f64; 3]; 3];
+    for (row, result_row) in result.iter_mut().enumerate() {
+        for (column, value) in result_row.iter_mut().enumerate() {
+            // transposed cofactor matrix
+            *value = cofactor(column, row) / determinant;
+        }
+    }
+    result
+}
+
+/// Linear RGB to CIE XYZ from the primaries and the white point
+fn rgb_to_xyz(primaries: &[(f64, f64); 3], white_point: (f64, f64)) -> Matrix3 {
+    let to_xyz = |(x, y): (f64, f64)| [x / y, 1.0, (1.0 - x - y) / y];
+    let columns = primaries.map(to_xyz);
+    let primaries_matrix = [
+        [columns[0][0], columns[1][0], columns[2][0]],
+        [columns[0][1], columns[1][1], columns[2][1]],
+        [columns[0][2], columns[1][2], columns[2][2]],
+    ];
+    // scale each primary so that RGB (1, 1, 1) becomes the white point
+    let white = to_xyz(white_point);
+    let inverse = invert(&primaries_matrix);
+    let scale: [f64; 3] =
+        [0, 1, 2].map(|row| (0..3).map(|k| inverse[row][k] * white[k]).sum::<f64>());
+    let mut result = primaries_matrix;
+    for row in result.iter_mut() {
+        for (column, value) in row.iter_mut().enumerate() {
+            *value *= scale[column];
+        }
+    }
+    result
+}
+
+enum Conversion {
+    Identity,
+    Matrix {
+        /// encoded value to linear light
+        decode: DecodeTable,
+        /// input linear RGB to working space linear RGB
+        matrix: [[f32; 3]; 3],
+        /// linear light (0.0 - 1.0) quantized to `ENCODE_TABLE_SIZE` steps to sRGB
+        encode: Vec<f32>,
+    },
+    Icc(qcms::Transform),
+}
+
+/// Conversion from an input color space to the working space (sRGB)
+pub struct ColorConversion {
+    conversion: Conversion,
+}
+
+impl ColorConversion {
+    pub fn new(space: &InputColorSpace) -> Result<Self, ColorSpaceError> {
+        let conversion = match space {
+            InputColorSpace::Srgb => Conversion::Identity,
+            InputColorSpace::DisplayP3 => {
+                matrix_conversion(&PRIMARIES_DISPLAY_P3, TransferFunction::Srgb)
+            }
+            InputColorSpace::AdobeRgb => matrix_conversion(
+                &PRIMARIES_ADOBE_RGB,
+                TransferFunction::Gamma(GAMMA_ADOBE_RGB),
+            ),
+            InputColorSpace::Rec709 => {
+                matrix_conversion(&PRIMARIES_REC709, TransferFunction::Bt1886)
+            }
+            InputColorSpace::Rec2020 => {
+                matrix_conversion(&PRIMARIES_REC2020, TransferFunction::Bt1886)
+            }
+            InputColorSpace::Icc(path) => icc_conversion(Path::new(path))?,
+        };
+        Ok(Self { conversion })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        matches!(self.conversion, Conversion::Identity)
+    }
+
+    /// Converts every pixel, keeping alpha.
+    /// Matrix conversions return float frames with colors outside of the working space gamut kept,
+    /// ICC profiles are applied at 8-bit precision and clip them.
+    pub fn apply_to_frame(&self, frame: &Frame) -> Frame {
+        match &self.conversion {
+            Conversion::Identity => frame.clone(),
+            Conversion::Matrix {
+                decode,
+                matrix,
+                encode,
+            } => {
+                let last = (ENCODE_TABLE_SIZE - 1) as f32;
+                frame.map_rgb_to_float(|rgb| {
+                    let linear = rgb.map(|value| decode.get(value));
+                    matrix.map(|row| {
+                        let value = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+                        match (0.0..=1.0).contains(&value) {
+                            true => encode[(value * last).round() as usize],
+                            false => encode_srgb_extended(value),
+                        }
+                    })
+                })
+            }
+            Conversion::Icc(transform) => {
+                let mut rgba = frame.to_rgba8().into_owned();
+                transform.apply(&mut rgba);
+                frame.with_samples(FrameSamples::Rgba8(rgba))
+            }
+        }
+    }
+}
+
+/// sRGB curve continued beyond 1.0 and mirrored below 0.0, as in scRGB
+fn encode_srgb_extended(value: f32) -> f32 {
+    match value < 0.0 {
+        true => -encode_srgb_extended(-value),
+        false if value <= 1.0 => TransferFunction::Srgb.encode(value),
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+    }
+}
+
+/// `true` when a working space color has a component outside of 0.0 - 1.0
+#[inline(always)]
+pub fn is_out_of_gamut(rgb: [f32; 3]) -> bool {
+    rgb.iter()
+        .any(|value| !(-GAMUT_TOLERANCE..=1.0 + GAMUT_TOLERANCE).contains(value))
+}
+
+fn matrix_conversion(primaries: &[(f64, f64); 3], transfer: TransferFunction) -> Conversion {
+    let input_to_xyz = rgb_to_xyz(primaries, WHITE_POINT_D65);
+    let xyz_to_working = invert(&rgb_to_xyz(&PRIMARIES_REC709, WHITE_POINT_D65));
+    let matrix = multiply(&xyz_to_working, &input_to_xyz).map(|row| row.map(|value| value as f32));
+
+    let last = (ENCODE_TABLE_SIZE - 1) as f32;
+    let encode = (0..ENCODE_TABLE_SIZE)
+        .map(|index| TransferFunction::Srgb.encode(index as f32 / last))
+        .collect();
+    Conversion::Matrix {
+        decode: transfer.decode_table(),
+        matrix,
+        encode,
+    }
+}
+
+fn icc_conversion(path: &Path) -> Result<Conversion, ColorSpaceError> {
+    let input = qcms::Profile::new_from_slice(&std::fs::read(path)?, false)
+        .ok_or(ColorSpaceError::InvalidProfile)?;
+    let working = qcms::Profile::new_sRGB();
+    qcms::Transform::new(
+        &input,
+        &working,
+        qcms::DataType::RGBA8,
+        qcms::Intent::RelativeColorimetric,
+    )
+    .map(Conversion::Icc)
+    .ok_or(ColorSpaceError::UnsupportedProfile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// one pixel per color, 8-bit like a capture
+    fn convert(space: InputColorSpace, colors: &[[u8; 3]]) -> Vec<[f32; 3]> {
+        let samples = colors
+            .iter()
+            .flat_map(|[red, green, blue]| [*red, *green, *blue, 255])
+            .collect();
+        let frame = Frame::new(colors.len() as u32, 1, FrameSamples::Rgba8(samples));
+        let converted = ColorConversion::new(&space).unwrap().apply_to_frame(&frame);
+        converted.pixels().collect()
+    }
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for channel in 0..3 {
+            assert!(
+                (actual[channel] - expected[channel]).abs() < 0.002,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_colors_outside_of_srgb() {
+        let [red, green] = convert(InputColorSpace::DisplayP3, &[[255, 0, 0], [0, 255, 0]])[..]
+        else {
+            panic!()
+        };
+        // Display P3 primaries in extended sRGB
+        assert_close(red, [1.093, -0.227, -0.150]);
+        assert_close(green, [-0.512, 1.018, -0.311]);
+        assert!(is_out_of_gamut(red));
+        assert!(is_out_of_gamut(green));
+    }
+
+    #[test]
+    fn keeps_colors_inside_of_srgb() {
+        let converted = convert(
+            InputColorSpace::DisplayP3,
+            &[[0, 0, 0], [255, 255, 255], [128, 128, 128], [200, 100, 80]],
+        );
+        assert_close(converted[0], [0.0; 3]);
+        assert_close(converted[1], [1.0; 3]);
+        assert_close(converted[2], [128.0 / 255.0; 3]);
+        assert!(converted.iter().all(|rgb| !is_out_of_gamut(*rgb)));
+    }
+
+    #[test]
+    fn identity_keeps_the_frame() {
+        let converted = convert(InputColorSpace::Srgb, &[[255, 0, 0], [12, 34, 56]]);
+        assert_eq!(converted[0], [1.0, 0.0, 0.0]);
+        assert_close(converted[1], [12.0 / 255.0, 34.0 / 255.0, 56.0 / 255.0]);
+    }
+
+    #[test]
+    fn extended_encoding_is_continuous_and_odd() {
+        assert_eq!(
+            encode_srgb_extended(1.0),
+            TransferFunction::Srgb.encode(1.0)
+        );
+        assert!((encode_srgb_extended(1.0 + 1e-6) - 1.0).abs() < 1e-5);
+        assert!(encode_srgb_extended(2.0) > 1.0);
+        assert_eq!(encode_srgb_extended(-0.5), -encode_srgb_extended(0.5));
+    }
+
+    #[test]
+    fn tolerates_conversion_error_at_the_boundary() {
+        assert!(!is_out_of_gamut([1.001, -0.001, 0.5]));
+        assert!(is_out_of_gamut([1.01, 0.0, 0.5]));
+        assert!(is_out_of_gamut([0.5, 0.5, -0.01]));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::model::frame::Frame;
+use serde::Serialize;
+
+/// Same weights as the luminance waveform.
+/// Takes normalized RGB, returns the 0 - 255 waveform scale.
+#[inline(always)]
+pub fn luminance(rgb: [f32; 3]) -> f32 {
+    // scaled before weighting so that 8-bit frames keep their exact waveform rows
+    let [red, green, blue] = rgb.map(|value| value * 255.0);
+    (0.30 * red) + (0.56 * green) + (0.14 * blue)
+}
+
+/// HSL saturation (0.0 - 100.0) of normalized RGB, same scale as the vector scope
+#[inline(always)]
+pub fn saturation([red, green, blue]: [f32; 3]) -> f32 {
+    let max = red.max(green).max(blue).clamp(0.0, 1.0);
+    let min = red.min(green).min(blue).clamp(0.0, 1.0);
+    let delta = max - min;
+    if delta == 0.0 {
+        return 0.0;
+    }
+    let lightness = (max + min) / 2.0;
+    delta / (1.0 - (2.0 * lightness - 1.0).abs()) * 100.0
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColorStatistics {
+    pub width: u32,
+    pub height: u32,
+    pub luminance_min: f32,
+    pub luminance_max: f32,
+    pub luminance_mean: f32,
+    pub saturation_mean: f32,
+    pub saturation_max: f32,
+    pub red_mean: f32,
+    pub green_mean: f32,
+    pub blue_mean: f32,
+}
+
+pub fn calculate(image: &Frame) -> ColorStatistics {
+    let mut pixel_count: usize = 0;
+    let mut luminance_min = f32::MAX;
+    let mut luminance_max = f32::MIN;
+    let mut luminance_sum: f64 = 0.0;
+    let mut saturation_max: f32 = 0.0;
+    let mut saturation_sum: f64 = 0.0;
+    let mut rgb_sum: [f64; 3] = [0.0; 3];
+    for rgb in image.analyzed_pixels() {
+        pixel_count += 1;
+        let pixel_luminance = luminance(rgb);
+        luminance_min = luminance_min.min(pixel_luminance);
+        luminance_max = luminance_max.max(pixel_luminance);
+        luminance_sum += pixel_luminance as f64;
+
+        let pixel_saturation = saturation(rgb);
+        saturation_max = saturation_max.max(pixel_saturation);
+        saturation_sum += pixel_saturation as f64;
+
+        for channel in 0..3 {
+            rgb_sum[channel] += rgb[channel] as f64;
+        }
+    }
+
+    if pixel_count == 0 {
+        return ColorStatistics {
+            width: image.width(),
+            height: image.height(),
+            ..Default::default()
+        };
+    }
+
+    let count = pixel_count as f64;
+    ColorStatistics {
+        width: image.width(),
+        height: image.height(),
+        luminance_min,
+        luminance_max,
+        luminance_mean: (luminance_sum / count) as f32,
+        saturation_mean: (saturation_sum / count) as f32,
+        saturation_max,
+        // same 0 - 255 scale as 8-bit captures
+        red_mean: (rgb_sum[0] * 255.0 / count) as f32,
+        green_mean: (rgb_sum[1] * 255.0 / count) as f32,
+        blue_mean: (rgb_sum[2] * 255.0 / count) as f32,
+    }
+}
@@ -0,0 +1,189 @@
+use crate::model::json_rpc;
+use crate::model::json_rpc::RpcError;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const SOCKET_FILE_NAME: &str = "vector-scope.sock";
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+/// owner only, any local process of the user may control the app
+const SOCKET_PERMISSIONS: u32 = 0o600;
+/// the socket is bound in here, where no other user can reach it before its permissions are set
+const PRIVATE_DIRECTORY_PERMISSIONS: u32 = 0o700;
+
+pub type Handler = dyn Fn(&str, Value) -> Result<Value, RpcError> + Send + Sync;
+
+/// `$XDG_RUNTIME_DIR/vector-scope.sock`, or in the temporary directory
+pub fn default_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join(SOCKET_FILE_NAME)
+}
+
+/// JSON-RPC 2.0 over a Unix domain socket, one message per line in each direction
+pub struct ControlSocket {
+    path: PathBuf,
+    keep_alive: Arc<AtomicBool>,
+    accept_thread: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl ControlSocket {
+    /// Replaces a socket file left behind by a previous run
+    pub fn start(path: &Path, handler: Arc<Handler>) -> std::io::Result<Self> {
+        if UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("{} is in use", path.display()),
+            ));
+        }
+        let listener = bind_private(path)?;
+        // polled so that `stop` is noticed
+        listener.set_nonblocking(true)?;
+
+        let keep_alive = Arc::new(AtomicBool::new(true));
+        let accept_keep_alive = Arc::clone(&keep_alive);
+        Ok(Self {
+            path: path.to_path_buf(),
+            keep_alive,
+            accept_thread: Mutex::new(Some(thread::spawn(move || {
+                accept_loop(listener, accept_keep_alive, handler)
+            }))),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stops accepting connections and removes the socket file.
+    /// Connected clients are served until they disconnect.
+    pub fn stop(&self) {
+        self.keep_alive.store(false, Ordering::Relaxed);
+        if let Some(accept_thread) = self.accept_thread.lock().unwrap().take() {
+            let _ = accept_thread.join();
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Binds in a new owner-only directory next to `path`, then moves the socket into place
+/// (replacing any file there), so that it is never reachable with the default permissions
+fn bind_private(path: &Path) -> std::io::Result<UnixListener> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} is not a file path", path.display()),
+        )
+    })?;
+    let private_directory = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(PRIVATE_DIRECTORY_PERMISSIONS)
+        .create(&private_directory)?;
+    let private_path = private_directory.join(file_name);
+    let listener = UnixListener::bind(&private_path).and_then(|listener| {
+        std::fs::set_permissions(
+            &private_path,
+            std::fs::Permissions::from_mode(SOCKET_PERMISSIONS),
+        )?;
+        std::fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&private_path);
+    let _ = std::fs::remove_dir(&private_directory);
+    listener
+}
+
+fn accept_loop(listener: UnixListener, keep_alive: Arc<AtomicBool>, handler: Arc<Handler>) {
+    while keep_alive.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let handler = Arc::clone(&handler);
+                thread::spawn(move || {
+                    if let Err(_err) = handle_connection(stream, handler.as_ref()) {
+                        #[cfg(debug_assertions)]
+                        println!("Control socket: {_err}");
+                    }
+                });
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_INTERVAL);
+            }
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                println!("Control socket: {_err}");
+                thread::sleep(ACCEPT_INTERVAL);
+            }
+        }
+    }
+}
+
+fn handle_connection(stream: UnixStream, handler: &Handler) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = json_rpc::handle_message(&line, handler) {
+            writeln!(writer, "{response}")?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn echo(method: &str, params: Value) -> Result<Value, RpcError> {
+        Ok(json!({ "method": method, "params": params }))
+    }
+
+    #[test]
+    fn serves_owner_only_socket() {
+        let directory =
+            std::env::temp_dir().join(format!("control-socket-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(SOCKET_FILE_NAME);
+        // left behind by a previous run
+        std::fs::write(&path, "").unwrap();
+
+        let socket = ControlSocket::start(&path, Arc::new(echo)).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, SOCKET_PERMISSIONS);
+        // only the socket is left in the directory
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        writeln!(stream, r#"{{"jsonrpc":"2.0","method":"ping","id":1}}"#).unwrap();
+        let mut response = String::new();
+        BufReader::new(&stream).read_line(&mut response).unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"]["method"], "ping");
+
+        assert!(ControlSocket::start(&path, Arc::new(echo)).is_err());
+        socket.stop();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,326 @@
+use display_info::DisplayInfo;
+use serde::{Deserialize, Serialize};
+
+/// Unit of screen coordinates passed in by the frontend or scripts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateSpace {
+    /// physical pixels divided by the scale factor of their display, as the webview reports them
+    #[default]
+    Logical,
+    /// device pixels
+    Physical,
+}
+
+/// How `display_info` reports positions and sizes
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ReportedUnit {
+    /// Windows
+    Physical,
+    /// Linux: X11 pixels divided by the Xft.dpi scale, which is the same for every display
+    Scaled,
+    /// macOS: one desktop in points, each display with its own scale factor
+    Points,
+}
+
+const REPORTED_UNIT: ReportedUnit = match (cfg!(target_os = "windows"), cfg!(target_os = "macos")) {
+    (true, _) => ReportedUnit::Physical,
+    (_, true) => ReportedUnit::Points,
+    _ => ReportedUnit::Scaled,
+};
+
+/// A display in physical pixels of the desktop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Display {
+    pub id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    /// top left in logical units, where the desktop places the display
+    pub logical_origin: (f64, f64),
+}
+
+impl Display {
+    /// Display whose logical origin is its physical one divided by its scale factor,
+    /// as on Windows and Linux
+    pub fn new(id: u32, x: i32, y: i32, width: u32, height: u32, scale_factor: f64) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            width,
+            height,
+            scale_factor,
+            logical_origin: (x as f64 / scale_factor, y as f64 / scale_factor),
+        }
+    }
+
+    /// Left, top, right and bottom (exclusive) in `space`.
+    /// Logical bounds are the size divided by the scale factor of this display,
+    /// so on mixed scale layouts there may be gaps or overlaps between displays.
+    pub fn bounds(&self, space: CoordinateSpace) -> (f64, f64, f64, f64) {
+        let (origin, scale) = self.origin_and_scale(space);
+        (
+            origin.0,
+            origin.1,
+            origin.0 + self.width as f64 / scale,
+            origin.1 + self.height as f64 / scale,
+        )
+    }
+
+    pub fn contains(&self, point: (f64, f64), space: CoordinateSpace) -> bool {
+        let (left, top, right, bottom) = self.bounds(space);
+        (left..right).contains(&point.0) && (top..bottom).contains(&point.1)
+    }
+
+    /// Physical position of a point given in `space` on this display
+    pub fn to_physical(self, point: (f64, f64), space: CoordinateSpace) -> (f64, f64) {
+        let (origin, scale) = self.origin_and_scale(space);
+        (
+            self.x as f64 + (point.0 - origin.0) * scale,
+            self.y as f64 + (point.1 - origin.1) * scale,
+        )
+    }
+
+    /// Top left in `space` and physical pixels per unit of `space`
+    fn origin_and_scale(&self, space: CoordinateSpace) -> ((f64, f64), f64) {
+        match space {
+            CoordinateSpace::Logical => (self.logical_origin, self.scale_factor),
+            CoordinateSpace::Physical => ((self.x as f64, self.y as f64), 1.0),
+        }
+    }
+}
+
+/// Connected displays, to convert between logical and physical coordinates.
+/// A point is converted with the scale factor of the display it is on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayLayout {
+    displays: Vec<Display>,
+}
+
+impl From<&[DisplayInfo]> for DisplayLayout {
+    fn from(displays: &[DisplayInfo]) -> Self {
+        Self::from_reported(displays, REPORTED_UNIT)
+    }
+}
+
+impl DisplayLayout {
+    pub fn new(displays: Vec<Display>) -> Self {
+        Self { displays }
+    }
+
+    /// Sizes are scaled by the factor of each display. On macOS the origins are scaled
+    /// by the largest factor instead: scaling each origin by its own factor would make
+    /// a 200% display overlap a 100% one next to it, a common factor only leaves gaps.
+    fn from_reported(displays: &[DisplayInfo], unit: ReportedUnit) -> Self {
+        let scale_factor = |display: &DisplayInfo| match display.scale_factor > 0.0 {
+            true => display.scale_factor as f64,
+            false => 1.0,
+        };
+        let origin_scale_max = displays.iter().map(scale_factor).fold(1.0, f64::max);
+        Self::new(
+            displays
+                .iter()
+                .map(|display| {
+                    let scale_factor = scale_factor(display);
+                    let (origin_scale, size_scale) = match unit {
+                        ReportedUnit::Physical => (1.0, 1.0),
+                        ReportedUnit::Scaled => (scale_factor, scale_factor),
+                        ReportedUnit::Points => (origin_scale_max, scale_factor),
+                    };
+                    let mut converted = Display::new(
+                        display.id,
+                        (display.x as f64 * origin_scale).round() as i32,
+                        (display.y as f64 * origin_scale).round() as i32,
+                        (display.width as f64 * size_scale).round() as u32,
+                        (display.height as f64 * size_scale).round() as u32,
+                        scale_factor,
+                    );
+                    if unit == ReportedUnit::Points {
+                        // the desktop is laid out in points
+                        converted.logical_origin = (display.x as f64, display.y as f64);
+                    }
+                    converted
+                })
+                .collect(),
+        )
+    }
+
+    /// Displays connected now, empty when they can not be listed
+    pub fn current() -> Self {
+        DisplayInfo::all()
+            .map(|displays| Self::from(displays.as_slice()))
+            .unwrap_or_default()
+    }
+
+    pub fn displays(&self) -> &[Display] {
+        &self.displays
+    }
+
+    pub fn display(&self, id: u32) -> Option<&Display> {
+        self.displays.iter().find(|display| display.id == id)
+    }
+
+    /// Display that contains the point, the first one where displays overlap
+    pub fn display_at(&self, point: (f64, f64), space: CoordinateSpace) -> Option<&Display> {
+        self.displays
+            .iter()
+            .find(|display| display.contains(point, space))
+    }
+
+    /// `None` when the point is on no display
+    #[cfg(target_os = "macos")]
+    pub fn to_physical(&self, point: (f64, f64), space: CoordinateSpace) -> Option<(f64, f64)> {
+        self.display_at(point, space)
+            .map(|display| display.to_physical(point, space))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// a 200% display with a 100% display on its right, as Windows reports them
+    pub fn mixed_layout() -> DisplayLayout {
+        DisplayLayout::new(vec![
+            Display::new(1, 3840, 0, 1920, 1080, 1.0),
+            Display::new(2, 0, 0, 3840, 2160, 2.0),
+        ])
+    }
+
+    fn display_info(id: u32, x: i32, y: i32, width: u32, height: u32, scale: f32) -> DisplayInfo {
+        DisplayInfo {
+            id,
+            x,
+            y,
+            width,
+            height,
+            rotation: 0.0,
+            scale_factor: scale,
+            is_primary: id == 1,
+        }
+    }
+
+    #[test]
+    fn bounds_in_both_spaces() {
+        let layout = mixed_layout();
+        let [standard, high] = [layout.displays()[0], layout.displays()[1]];
+        assert_eq!(
+            standard.bounds(CoordinateSpace::Physical),
+            (3840.0, 0.0, 5760.0, 1080.0)
+        );
+        assert_eq!(
+            standard.bounds(CoordinateSpace::Logical),
+            (3840.0, 0.0, 5760.0, 1080.0)
+        );
+        assert_eq!(
+            high.bounds(CoordinateSpace::Physical),
+            (0.0, 0.0, 3840.0, 2160.0)
+        );
+        assert_eq!(
+            high.bounds(CoordinateSpace::Logical),
+            (0.0, 0.0, 1920.0, 1080.0)
+        );
+    }
+
+    #[test]
+    fn finds_display_in_both_spaces() {
+        let layout = mixed_layout();
+        let id_at = |point, space| layout.display_at(point, space).map(|display| display.id);
+        assert_eq!(id_at((100.0, 100.0), CoordinateSpace::Logical), Some(2));
+        assert_eq!(id_at((3000.0, 100.0), CoordinateSpace::Physical), Some(2));
+        assert_eq!(id_at((4000.0, 100.0), CoordinateSpace::Logical), Some(1));
+        assert_eq!(id_at((4000.0, 100.0), CoordinateSpace::Physical), Some(1));
+        // between the logical right edge of the 200% display and the 100% display
+        assert_eq!(id_at((3000.0, 100.0), CoordinateSpace::Logical), None);
+        // bottom right is exclusive
+        assert_eq!(id_at((3840.0, 2160.0), CoordinateSpace::Physical), None);
+    }
+
+    #[test]
+    fn converts_with_the_scale_of_the_display() {
+        let layout = mixed_layout();
+        let high = layout.displays()[1];
+        assert_eq!(
+            high.to_physical((100.5, 20.0), CoordinateSpace::Logical),
+            (201.0, 40.0)
+        );
+        assert_eq!(
+            high.to_physical((100.5, 20.0), CoordinateSpace::Physical),
+            (100.5, 20.0)
+        );
+        let standard = layout.displays()[0];
+        assert_eq!(
+            standard.to_physical((4000.0, 20.0), CoordinateSpace::Logical),
+            (4000.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn converts_reported_units() {
+        let displays = [
+            display_info(1, 0, 0, 1920, 1080, 1.5),
+            display_info(2, 1920, 0, 1920, 1080, 1.5),
+        ];
+        let windows = DisplayLayout::from_reported(&displays, ReportedUnit::Physical);
+        assert_eq!(
+            windows.displays()[1],
+            Display::new(2, 1920, 0, 1920, 1080, 1.5)
+        );
+        let linux = DisplayLayout::from_reported(&displays, ReportedUnit::Scaled);
+        assert_eq!(
+            linux.displays()[1],
+            Display::new(2, 2880, 0, 2880, 1620, 1.5)
+        );
+        assert_eq!(
+            linux.displays()[1].bounds(CoordinateSpace::Logical),
+            (1920.0, 0.0, 3840.0, 1080.0)
+        );
+    }
+
+    #[test]
+    fn lays_out_mixed_scale_points_without_overlap() {
+        // a Retina laptop with a 100% display on its right, in points as macOS reports them
+        let layout = DisplayLayout::from_reported(
+            &[
+                display_info(1, 0, 0, 1512, 982, 2.0),
+                display_info(2, 1512, 0, 1920, 1080, 1.0),
+            ],
+            ReportedUnit::Points,
+        );
+        let [retina, standard] = [layout.displays()[0], layout.displays()[1]];
+        assert_eq!(
+            retina.bounds(CoordinateSpace::Physical),
+            (0.0, 0.0, 3024.0, 1964.0)
+        );
+        assert_eq!(
+            standard.bounds(CoordinateSpace::Physical),
+            (3024.0, 0.0, 4944.0, 1080.0)
+        );
+        // logical bounds are the points macOS reports
+        assert_eq!(
+            retina.bounds(CoordinateSpace::Logical),
+            (0.0, 0.0, 1512.0, 982.0)
+        );
+        assert_eq!(
+            standard.bounds(CoordinateSpace::Logical),
+            (1512.0, 0.0, 3432.0, 1080.0)
+        );
+
+        let id_at = |point, space| layout.display_at(point, space).map(|display| display.id);
+        assert_eq!(id_at((1511.0, 10.0), CoordinateSpace::Logical), Some(1));
+        assert_eq!(id_at((1512.0, 10.0), CoordinateSpace::Logical), Some(2));
+        assert_eq!(id_at((3023.0, 10.0), CoordinateSpace::Physical), Some(1));
+        assert_eq!(id_at((3024.0, 10.0), CoordinateSpace::Physical), Some(2));
+
+        let to_physical = |point| {
+            layout
+                .display_at(point, CoordinateSpace::Logical)
+                .map(|display| display.to_physical(point, CoordinateSpace::Logical))
+        };
+        assert_eq!(to_physical((100.0, 50.0)), Some((200.0, 100.0)));
+        assert_eq!(to_physical((1600.0, 50.0)), Some((3112.0, 50.0)));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::model::color_space;
+use crate::model::color_statistics;
+use crate::model::legal_range;
+use crate::model::legal_range::{LegalRangeThresholds, PixelLegality};
+use serde::{Deserialize, Serialize};
+
+const ZEBRA_STRIPE_WIDTH: u32 = 4;
+const ZEBRA_STRIPE_COLOR: (u8, u8, u8) = (0, 0, 0);
+const GAMUT_WARNING_COLOR: (u8, u8, u8) = (255, 0, 255);
+
+/// (luminance lower bound %, luminance upper bound %, color)
+/// Luminance outside of every zone is shown in gray scale.
+const FALSE_COLOR_ZONES: [(f32, f32, (u8, u8, u8)); 7] = [
+    (0.0, 2.5, (96, 0, 160)),      // crushed black
+    (2.5, 10.0, (0, 64, 255)),     // near black
+    (10.0, 20.0, (0, 160, 200)),   // shadows
+    (38.0, 42.0, (0, 200, 0)),     // 18% gray
+    (52.0, 56.0, (255, 128, 192)), // skin tone, one stop over gray
+    (97.0, 99.0, (255, 255, 0)),   // near white
+    (99.0, 100.1, (255, 0, 0)),    // clipped
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FalseColorMode {
+    /// only the gamut warning (when enabled) on top of the capture
+    Off,
+    FalseColor,
+    Zebra,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FalseColorSettings {
+    pub mode: FalseColorMode,
+    /// luminance (0 - 255) above which zebra stripes are drawn
+    pub zebra_threshold: f32,
+    /// highlight pixels outside the legal range or the sRGB gamut in magenta
+    pub highlight_illegal: bool,
+}
+
+impl Default for FalseColorSettings {
+    fn default() -> Self {
+        Self {
+            mode: FalseColorMode::FalseColor,
+            zebra_threshold: 235.0,
+            highlight_illegal: true,
+        }
+    }
+}
+
+fn false_color_of(luminance: f32) -> (u8, u8, u8) {
+    let percent = luminance / 255.0 * 100.0;
+    for (lower, upper, color) in FALSE_COLOR_ZONES {
+        if percent >= lower && percent < upper {
+            return color;
+        }
+    }
+    let gray = luminance.clamp(0.0, 255.0) as u8;
+    (gray, gray, gray)
+}
+
+/// Color shown for the pixel at (`x`, `y`) with normalized RGB
+#[inline(always)]
+pub fn map_pixel(
+    rgb: [f32; 3],
+    (x, y): (u32, u32),
+    settings: &FalseColorSettings,
+    thresholds: &LegalRangeThresholds,
+) -> (u8, u8, u8) {
+    if settings.highlight_illegal
+        && (color_space::is_out_of_gamut(rgb)
+            || legal_range::classify_pixel(rgb, thresholds) != PixelLegality::Legal)
+    {
+        return GAMUT_WARNING_COLOR;
+    }
+
+    let [red, green, blue] = rgb.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    let luminance = color_statistics::luminance(rgb);
+    match settings.mode {
+        FalseColorMode::Off => (red, green, blue),
+        FalseColorMode::FalseColor => false_color_of(luminance),
+        FalseColorMode::Zebra => {
+            // diagonal stripes
+            if luminance > settings.zebra_threshold && ((x + y) / ZEBRA_STRIPE_WIDTH) % 2 == 0 {
+                ZEBRA_STRIPE_COLOR
+            } else {
+                (red, green, blue)
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use screenshots::Image;
+use std::borrow::Cow;
+
+const U8_MAX: f32 = u8::MAX as f32;
+const U16_MAX: f32 = u16::MAX as f32;
+
+/// Interleaved RGBA samples
+#[derive(Clone)]
+pub enum FrameSamples {
+    Rgba8(Vec<u8>),
+    Rgba16(Vec<u16>),
+    /// linear or encoded float, may exceed 1.0
+    Rgba32F(Vec<f32>),
+}
+
+/// Frame analyzed by the scopes.
+/// Screen captures are 8-bit, image files keep their 16-bit or float samples.
+/// Areas left out with `exclude_area` (the app's own windows on a capture) are not analyzed,
+/// alpha is not taken into account.
+#[derive(Clone)]
+pub struct Frame {
+    width: u32,
+    height: u32,
+    samples: FrameSamples,
+    /// `true` for every excluded pixel, `None` when nothing is excluded
+    excluded: Option<Vec<bool>>,
+}
+
+impl From<Image> for Frame {
+    fn from(image: Image) -> Self {
+        Self::new(
+            image.width(),
+            image.height(),
+            FrameSamples::Rgba8(image.rgba().clone()),
+        )
+    }
+}
+
+impl Frame {
+    pub fn new(width: u32, height: u32, samples: FrameSamples) -> Self {
+        Self {
+            width,
+            height,
+            samples,
+            excluded: None,
+        }
+    }
+
+    /// Frame of the same size and excluded areas with other samples
+    pub fn with_samples(&self, samples: FrameSamples) -> Frame {
+        Self {
+            width: self.width,
+            height: self.height,
+            samples,
+            excluded: self.excluded.clone(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn samples(&self) -> &FrameSamples {
+        &self.samples
+    }
+
+    /// Memory taken by the samples and the excluded areas
+    pub fn byte_size(&self) -> usize {
+        let excluded = self.excluded.as_ref().map_or(0, Vec::len);
+        excluded
+            + match &self.samples {
+                FrameSamples::Rgba8(samples) => std::mem::size_of_val(samples.as_slice()),
+                FrameSamples::Rgba16(samples) => std::mem::size_of_val(samples.as_slice()),
+                FrameSamples::Rgba32F(samples) => std::mem::size_of_val(samples.as_slice()),
+            }
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        match &self.samples {
+            FrameSamples::Rgba8(samples) => samples.len() / 4,
+            FrameSamples::Rgba16(samples) => samples.len() / 4,
+            FrameSamples::Rgba32F(samples) => samples.len() / 4,
+        }
+    }
+
+    /// Normalized RGB (0.0 - 1.0, float frames may exceed the range) of the pixel at `index`
+    #[inline(always)]
+    pub fn rgb(&self, index: usize) -> [f32; 3] {
+        let offset = index * 4;
+        match &self.samples {
+            FrameSamples::Rgba8(samples) => [
+                samples[offset] as f32 / U8_MAX,
+                samples[offset + 1] as f32 / U8_MAX,
+                samples[offset + 2] as f32 / U8_MAX,
+            ],
+            FrameSamples::Rgba16(samples) => [
+                samples[offset] as f32 / U16_MAX,
+                samples[offset + 1] as f32 / U16_MAX,
+                samples[offset + 2] as f32 / U16_MAX,
+            ],
+            FrameSamples::Rgba32F(samples) => {
+                [samples[offset], samples[offset + 1], samples[offset + 2]]
+            }
+        }
+    }
+
+    /// Normalized alpha of the pixel at `index`
+    fn alpha(&self, index: usize) -> f32 {
+        let offset = index * 4 + 3;
+        match &self.samples {
+            FrameSamples::Rgba8(samples) => samples[offset] as f32 / U8_MAX,
+            FrameSamples::Rgba16(samples) => samples[offset] as f32 / U16_MAX,
+            FrameSamples::Rgba32F(samples) => samples[offset],
+        }
+    }
+
+    /// Normalized RGB of every pixel in order
+    pub fn pixels(&self) -> impl Iterator<Item = [f32; 3]> + '_ {
+        (0..self.pixel_count()).map(|index| self.rgb(index))
+    }
+
+    /// `false` for excluded pixels, which are left out of every analysis
+    #[inline(always)]
+    pub fn is_analyzed(&self, index: usize) -> bool {
+        !self
+            .excluded
+            .as_ref()
+            .is_some_and(|excluded| excluded[index])
+    }
+
+    /// Normalized RGB of the pixels that are analyzed, in order
+    pub fn analyzed_pixels(&self) -> impl Iterator<Item = [f32; 3]> + '_ {
+        (0..self.pixel_count())
+            .filter(|&index| self.is_analyzed(index))
+            .map(|index| self.rgb(index))
+    }
+
+    /// Leaves a rectangle (cut to the frame) out of every analysis. The samples are kept.
+    pub fn exclude_area(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        let left = x.clamp(0, self.width as i32) as usize;
+        let top = y.clamp(0, self.height as i32) as usize;
+        let right = x.saturating_add_unsigned(width).clamp(0, self.width as i32) as usize;
+        let bottom = y
+            .saturating_add_unsigned(height)
+            .clamp(0, self.height as i32) as usize;
+        if left == right || top == bottom {
+            return;
+        }
+        let frame_width = self.width as usize;
+        let pixel_count = self.pixel_count();
+        let excluded = self
+            .excluded
+            .get_or_insert_with(|| vec![false; pixel_count]);
+        for row in top..bottom {
+            excluded[row * frame_width + left..row * frame_width + right].fill(true);
+        }
+    }
+
+    /// 8-bit RGBA, quantized when the frame has more precision
+    pub fn to_rgba8(&self) -> Cow<'_, [u8]> {
+        match &self.samples {
+            FrameSamples::Rgba8(samples) => Cow::Borrowed(samples),
+            FrameSamples::Rgba16(samples) => Cow::Owned(
+                samples
+                    .iter()
+                    .map(|sample| ((*sample as u32 + 128) / 257) as u8)
+                    .collect(),
+            ),
+            FrameSamples::Rgba32F(samples) => Cow::Owned(
+                samples
+                    .iter()
+                    .map(|sample| (sample.clamp(0.0, 1.0) * U8_MAX).round() as u8)
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 16-bit RGBA, 8-bit frames are scaled up
+    pub fn to_rgba16(&self) -> Cow<'_, [u16]> {
+        match &self.samples {
+            FrameSamples::Rgba8(samples) => {
+                Cow::Owned(samples.iter().map(|sample| *sample as u16 * 257).collect())
+            }
+            FrameSamples::Rgba16(samples) => Cow::Borrowed(samples),
+            FrameSamples::Rgba32F(samples) => Cow::Owned(
+                samples
+                    .iter()
+                    .map(|sample| (sample.clamp(0.0, 1.0) * U16_MAX).round() as u16)
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Applies `transform` to the normalized RGB of every pixel. Alpha and excluded areas are kept.
+    /// 8-bit frames become 16-bit so that the result is not truncated again.
+    pub fn map_rgb(&self, transform: impl Fn([f32; 3]) -> [f32; 3]) -> Frame {
+        let samples = match &self.samples {
+            FrameSamples::Rgba32F(samples) => {
+                let mut output = samples.clone();
+                for (index, pixel) in output.chunks_exact_mut(4).enumerate() {
+                    let rgb = transform(self.rgb(index));
+                    pixel[..3].copy_from_slice(&rgb);
+                }
+                FrameSamples::Rgba32F(output)
+            }
+            _ => {
+                let mut output = self.to_rgba16().into_owned();
+                for (index, pixel) in output.chunks_exact_mut(4).enumerate() {
+                    let rgb = transform(self.rgb(index));
+                    for channel in 0..3 {
+                        pixel[channel] = (rgb[channel].clamp(0.0, 1.0) * U16_MAX).round() as u16;
+                    }
+                }
+                FrameSamples::Rgba16(output)
+            }
+        };
+        self.with_samples(samples)
+    }
+
+    /// Like `map_rgb`, but the result always has float samples
+    /// so that values outside of 0.0 - 1.0 are kept
+    pub fn map_rgb_to_float(&self, transform: impl Fn([f32; 3]) -> [f32; 3]) -> Frame {
+        let mut samples: Vec<f32> = Vec::with_capacity(self.pixel_count() * 4);
+        for index in 0..self.pixel_count() {
+            samples.extend(transform(self.rgb(index)));
+            samples.push(self.alpha(index));
+        }
+        self.with_samples(FrameSamples::Rgba32F(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x2 frame with alpha 0 on the first pixel, as image files may have
+    fn frame() -> Frame {
+        let samples = (0..6u8)
+            .flat_map(|index| [index * 40, 0, 0, if index == 0 { 0 } else { 255 }])
+            .collect();
+        Frame::new(3, 2, FrameSamples::Rgba8(samples))
+    }
+
+    fn analyzed(frame: &Frame) -> Vec<bool> {
+        (0..frame.pixel_count())
+            .map(|index| frame.is_analyzed(index))
+            .collect()
+    }
+
+    #[test]
+    fn analyzes_transparent_pixels() {
+        let frame = frame();
+        assert!(analyzed(&frame).iter().all(|&analyzed| analyzed));
+        assert_eq!(frame.analyzed_pixels().count(), 6);
+    }
+
+    #[test]
+    fn excludes_areas_cut_to_the_frame() {
+        let mut frame = frame();
+        frame.exclude_area(-1, -1, 2, 2);
+        assert_eq!(analyzed(&frame), [false, true, true, true, true, true]);
+        frame.exclude_area(2, 1, u32::MAX, u32::MAX);
+        assert_eq!(analyzed(&frame), [false, true, true, true, true, false]);
+        let reds: Vec<f32> = frame.analyzed_pixels().map(|rgb| rgb[0] * 255.0).collect();
+        assert_eq!(reds, [40.0, 80.0, 120.0, 160.0]);
+        // the samples are kept
+        assert_eq!(frame.to_rgba8()[..4], [0, 0, 0, 0]);
+        assert_eq!(frame.to_rgba8()[20..], [200, 0, 0, 255]);
+    }
+
+    #[test]
+    fn ignores_areas_off_the_frame() {
+        let mut frame = frame();
+        frame.exclude_area(3, 0, 10, 10);
+        frame.exclude_area(-10, 0, 10, 10);
+        frame.exclude_area(i32::MAX, i32::MAX, u32::MAX, u32::MAX);
+        assert!(frame.excluded.is_none());
+    }
+
+    #[test]
+    fn transforms_keep_excluded_areas() {
+        let mut frame = frame();
+        frame.exclude_area(1, 0, 1, 2);
+        let expected = [true, false, true, true, false, true];
+        assert_eq!(analyzed(&frame.map_rgb(|rgb| rgb)), expected);
+        assert_eq!(analyzed(&frame.map_rgb_to_float(|rgb| rgb)), expected);
+        assert_eq!(
+            analyzed(&frame.with_samples(FrameSamples::Rgba8(vec![0; 24]))),
+            expected
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::model::frame::Frame;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+pub const FRAME_HISTORY_CAPACITY_MIN: usize = 1;
+pub const FRAME_HISTORY_CAPACITY_MAX: usize = 120;
+/// 120 full HD or 8 8K captures
+pub const FRAME_HISTORY_BYTES_MAX: usize = 1 << 30;
+
+/// Ring buffer of the last analyzed frames, limited by count and by memory.
+/// The latest frame is always kept, however large.
+/// `position` is `None` while following the latest frame.
+pub struct FrameHistory {
+    frames: VecDeque<Arc<Frame>>,
+    capacity: usize,
+    bytes_max: usize,
+    /// sum of `Frame::byte_size` of `frames`
+    bytes: usize,
+    position: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct FrameHistoryStatus {
+    /// 0 is the oldest frame
+    pub index: usize,
+    pub length: usize,
+    pub is_live: bool,
+}
+
+impl FrameHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_bytes_max(capacity, FRAME_HISTORY_BYTES_MAX)
+    }
+
+    fn with_bytes_max(capacity: usize, bytes_max: usize) -> Self {
+        let capacity = capacity.clamp(FRAME_HISTORY_CAPACITY_MIN, FRAME_HISTORY_CAPACITY_MAX);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            bytes_max,
+            bytes: 0,
+            position: None,
+        }
+    }
+
+    pub fn push(&mut self, frame: Arc<Frame>) {
+        self.bytes += frame.byte_size();
+        self.frames.push_back(frame);
+        self.trim();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.clamp(FRAME_HISTORY_CAPACITY_MIN, FRAME_HISTORY_CAPACITY_MAX);
+        self.trim();
+    }
+
+    /// Drops the oldest frames until both limits are met
+    fn trim(&mut self) {
+        while self.frames.len() > self.capacity
+            || (self.frames.len() > 1 && self.bytes > self.bytes_max)
+        {
+            let Some(oldest) = self.frames.pop_front() else {
+                break;
+            };
+            self.bytes -= oldest.byte_size();
+            // keep pointing at the same frame while browsing
+            if let Some(position) = self.position {
+                self.position = Some(position.saturating_sub(1));
+            }
+        }
+    }
+
+    pub fn current(&self) -> Option<Arc<Frame>> {
+        let index = self.current_index()?;
+        self.frames.get(index).cloned()
+    }
+
+    /// Moves the position by `offset` frames (negative is older) and returns the frame there.
+    /// The position stays inside the buffer.
+    pub fn step(&mut self, offset: i32) -> Option<Arc<Frame>> {
+        let index = self.current_index()? as i64 + offset as i64;
+        let index = index.clamp(0, self.frames.len() as i64 - 1) as usize;
+        self.position = Some(index);
+        self.frames.get(index).cloned()
+    }
+
+    /// Pins the position to the latest frame
+    pub fn hold(&mut self) {
+        self.position = self.frames.len().checked_sub(1);
+    }
+
+    /// Follows the latest frame again
+    pub fn release(&mut self) {
+        self.position = None;
+    }
+
+    pub fn status(&self) -> FrameHistoryStatus {
+        FrameHistoryStatus {
+            index: self.current_index().unwrap_or(0),
+            length: self.frames.len(),
+            is_live: self.position.is_none(),
+        }
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        match self.position {
+            Some(position) => Some(position),
+            None => self.frames.len().checked_sub(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+
+    /// 1x1 frame told apart by its red value, 4 bytes
+    fn frame(red: u8) -> Arc<Frame> {
+        Arc::new(Frame::new(1, 1, FrameSamples::Rgba8(vec![red, 0, 0, 255])))
+    }
+
+    fn red(frame: Option<Arc<Frame>>) -> Option<u8> {
+        frame.map(|frame| (frame.rgb(0)[0] * 255.0).round() as u8)
+    }
+
+    fn history(capacity: usize, reds: std::ops::Range<u8>) -> FrameHistory {
+        let mut history = FrameHistory::new(capacity);
+        for value in reds {
+            history.push(frame(value));
+        }
+        history
+    }
+
+    #[test]
+    fn follows_the_latest_frame() {
+        let mut history = FrameHistory::new(3);
+        assert_eq!(red(history.current()), None);
+        assert_eq!(history.status().length, 0);
+        for value in 0..5 {
+            history.push(frame(value));
+            assert_eq!(red(history.current()), Some(value));
+        }
+        let status = history.status();
+        assert_eq!((status.index, status.length, status.is_live), (2, 3, true));
+    }
+
+    #[test]
+    fn steps_within_the_buffer() {
+        let mut history = history(3, 0..5);
+        assert_eq!(red(history.step(-1)), Some(3));
+        assert!(!history.status().is_live);
+        assert_eq!(red(history.step(-5)), Some(2));
+        assert_eq!(red(history.step(10)), Some(4));
+        history.release();
+        history.push(frame(5));
+        assert_eq!(red(history.current()), Some(5));
+    }
+
+    #[test]
+    fn keeps_the_held_frame_while_pushing() {
+        let mut history = history(3, 0..3);
+        history.hold();
+        history.push(frame(3));
+        assert_eq!(red(history.current()), Some(2));
+        assert_eq!(history.status().index, 1);
+        history.push(frame(4));
+        history.push(frame(5));
+        // the held frame was dropped, the oldest one is shown instead
+        assert_eq!(red(history.current()), Some(3));
+    }
+
+    #[test]
+    fn shrinks_to_a_new_capacity() {
+        let mut history = history(5, 0..5);
+        history.step(-1);
+        history.set_capacity(2);
+        assert_eq!(history.status().length, 2);
+        assert_eq!(red(history.current()), Some(3));
+        history.set_capacity(0);
+        assert_eq!(history.status().length, FRAME_HISTORY_CAPACITY_MIN);
+        history.set_capacity(usize::MAX);
+        history.push(frame(5));
+        assert_eq!(history.status().length, 2);
+    }
+
+    #[test]
+    fn drops_frames_over_the_memory_limit() {
+        let mut history = FrameHistory::with_bytes_max(10, 12);
+        for value in 0..5 {
+            history.push(frame(value));
+        }
+        assert_eq!(history.status().length, 3);
+        assert_eq!(red(history.step(-10)), Some(2));
+
+        // larger than the limit on its own
+        history.push(Arc::new(Frame::new(
+            2,
+            2,
+            FrameSamples::Rgba16(vec![0; 16]),
+        )));
+        assert_eq!(history.status().length, 1);
+        assert_eq!(history.bytes, 32);
+        history.push(frame(5));
+        assert_eq!(history.status().length, 1);
+        assert_eq!(history.bytes, 4);
+    }
+}
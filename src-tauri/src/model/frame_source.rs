@@ -0,0 +1,165 @@
+use crate::model::frame::Frame;
+use crate::model::test_pattern::TestPattern;
+use crate::model::yuv_video::{VideoError, YuvFrame, YuvVideo};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where the analyzed frames come from
+pub enum FrameSource {
+    /// screen capture, refreshed by the worker
+    Screen,
+    /// still image opened from a file
+    File { path: PathBuf, frame: Arc<Frame> },
+    /// image pasted from the clipboard
+    Clipboard { frame: Arc<Frame> },
+    /// generated test pattern
+    Pattern {
+        pattern: TestPattern,
+        frame: Arc<Frame>,
+    },
+    /// uncompressed video, advanced by playback or seeking
+    Video(VideoSource),
+}
+
+/// Description of the current source for the frontend
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FrameSourceInfo {
+    Screen,
+    File {
+        path: String,
+        width: u32,
+        height: u32,
+    },
+    Clipboard {
+        width: u32,
+        height: u32,
+    },
+    Pattern {
+        pattern: TestPattern,
+        width: u32,
+        height: u32,
+    },
+    Video {
+        path: String,
+        width: u32,
+        height: u32,
+        frame_count: usize,
+        position: usize,
+        frame_rate: f64,
+    },
+}
+
+/// A decoded video frame as the scopes need it
+pub struct VideoFrame {
+    pub position: usize,
+    pub ycbcr: Arc<YuvFrame>,
+}
+
+impl FrameSource {
+    /// `true` when every refresh may produce a new frame
+    pub fn is_live(&self) -> bool {
+        matches!(self, FrameSource::Screen)
+    }
+
+    /// The current frame of a still or video source, `None` for the screen
+    pub fn frame(&self) -> Option<Arc<Frame>> {
+        match self {
+            FrameSource::Screen => None,
+            FrameSource::File { frame, .. } => Some(Arc::clone(frame)),
+            FrameSource::Clipboard { frame } => Some(Arc::clone(frame)),
+            FrameSource::Pattern { frame, .. } => Some(Arc::clone(frame)),
+            FrameSource::Video(video) => Some(Arc::clone(&video.frame)),
+        }
+    }
+
+    /// Position and Y′CbCr planes when `frame` is the current frame of a video source
+    pub fn video_frame(&self, frame: &Arc<Frame>) -> Option<VideoFrame> {
+        match self {
+            FrameSource::Video(video) if Arc::ptr_eq(&video.frame, frame) => Some(VideoFrame {
+                position: video.position,
+                ycbcr: Arc::clone(&video.ycbcr),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn info(&self) -> FrameSourceInfo {
+        match self {
+            FrameSource::Screen => FrameSourceInfo::Screen,
+            FrameSource::File { path, frame } => FrameSourceInfo::File {
+                path: path.to_string_lossy().into_owned(),
+                width: frame.width(),
+                height: frame.height(),
+            },
+            FrameSource::Clipboard { frame } => FrameSourceInfo::Clipboard {
+                width: frame.width(),
+                height: frame.height(),
+            },
+            FrameSource::Pattern { pattern, frame } => FrameSourceInfo::Pattern {
+                pattern: *pattern,
+                width: frame.width(),
+                height: frame.height(),
+            },
+            FrameSource::Video(video) => {
+                let format = video.video.format();
+                FrameSourceInfo::Video {
+                    path: video.path.to_string_lossy().into_owned(),
+                    width: format.width,
+                    height: format.height,
+                    frame_count: video.video.frame_count(),
+                    position: video.position,
+                    frame_rate: format.frame_rate,
+                }
+            }
+        }
+    }
+}
+
+pub struct VideoSource {
+    path: PathBuf,
+    video: YuvVideo,
+    position: usize,
+    ycbcr: Arc<YuvFrame>,
+    /// `ycbcr` converted to R′G′B′
+    frame: Arc<Frame>,
+}
+
+impl VideoSource {
+    /// Starts at the first frame
+    pub fn new(path: PathBuf, mut video: YuvVideo) -> Result<Self, VideoError> {
+        let ycbcr = video.read_frame(0)?;
+        Ok(Self {
+            path,
+            frame: Arc::new(ycbcr.to_frame()),
+            ycbcr: Arc::new(ycbcr),
+            video,
+            position: 0,
+        })
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.video.frame_count()
+    }
+
+    /// Display time of one frame
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.video.format().frame_rate)
+    }
+
+    /// Decodes frame `position`, clamped to the last frame
+    pub fn seek(&mut self, position: usize) -> Result<(), VideoError> {
+        let position = position.min(self.frame_count() - 1);
+        let ycbcr = self.video.read_frame(position)?;
+        self.frame = Arc::new(ycbcr.to_frame());
+        self.ycbcr = Arc::new(ycbcr);
+        self.position = position;
+        Ok(())
+    }
+}
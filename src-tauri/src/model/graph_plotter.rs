@@ -1,9 +1,17 @@
-use colors_transform::{Color, Rgb};
+use crate::model::color_palette::PaletteColor;
+use crate::model::false_color;
+use crate::model::false_color::FalseColorSettings;
+use crate::model::frame::Frame;
+use crate::model::hdr;
+use crate::model::hdr::NitsConverter;
+use crate::model::legal_range::LegalRangeThresholds;
+use crate::model::luminance::LuminanceMeter;
+use crate::model::yuv_video::{YuvFrame, YuvMatrix};
 use image;
 use plotters::prelude::*;
 use plotters_backend;
 use plotters_bitmap::bitmap_pixel::RGBPixel;
-use screenshots::Image;
+use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 use std::sync::OnceLock;
 
@@ -14,8 +22,25 @@ const VECTOR_SCOPE_CENTER: (i32, i32) = (
     (VECTOR_SCOPE_WIDHT / 2) as i32,
     (VECTOR_SCOPE_HEIGHT / 2) as i32,
 );
+/// Cb / Cr of ±0.5 on the outermost circle
+pub const VECTOR_SCOPE_CHROMA_SCALE: f32 = 200.0;
 const WAVEFORM_HEIGHT: u32 = 255;
 const ANALYZE_SKIP_RATIO: usize = 64;
+pub const LOUPE_VIEW_SIZE: u32 = 256;
+pub const LOUPE_ZOOM_MIN: u32 = 2;
+pub const LOUPE_ZOOM_MAX: u32 = 32;
+const LOUPE_GRID_MIN_ZOOM: u32 = 6;
+const PALETTE_WIDTH: u32 = 300;
+const PALETTE_HEIGHT: u32 = 48;
+const FALSE_COLOR_MAX_WIDTH: u32 = 960;
+
+/// How a reference still is shown next to the current frame
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceMode {
+    Overlay,
+    Split,
+}
 
 static VECTOR_SCOPE_BUFFER_SIZE: OnceLock<usize> = OnceLock::new();
 #[cold]
@@ -41,6 +66,50 @@ fn init_waveform_aux_line_color() -> plotters_backend::BackendColor {
     }
 }
 
+static HDR_DECADE_LINE_COLOR: OnceLock<plotters_backend::BackendColor> = OnceLock::new();
+#[cold]
+fn init_hdr_decade_line_color() -> plotters_backend::BackendColor {
+    plotters_backend::BackendColor {
+        alpha: 1.0,
+        rgb: (80, 80, 80),
+    }
+}
+
+/// Colors of the 100 / 203 / 1000 nits lines
+static HDR_REFERENCE_LINE_COLORS: OnceLock<[plotters_backend::BackendColor; 3]> = OnceLock::new();
+#[cold]
+fn init_hdr_reference_line_colors() -> [plotters_backend::BackendColor; 3] {
+    [(255, 255, 255), (255, 220, 0), (255, 80, 80)]
+        .map(|rgb| plotters_backend::BackendColor { alpha: 1.0, rgb })
+}
+
+static LOUPE_GRID_LINE_COLOR: OnceLock<plotters_backend::BackendColor> = OnceLock::new();
+#[cold]
+fn init_loupe_grid_line_color() -> plotters_backend::BackendColor {
+    plotters_backend::BackendColor {
+        alpha: 1.0,
+        rgb: (60, 60, 60),
+    }
+}
+
+static LOUPE_CENTER_LINE_COLOR: OnceLock<plotters_backend::BackendColor> = OnceLock::new();
+#[cold]
+fn init_loupe_center_line_color() -> plotters_backend::BackendColor {
+    plotters_backend::BackendColor {
+        alpha: 1.0,
+        rgb: (255, 0, 0),
+    }
+}
+
+static REFERENCE_TRACE_COLOR: OnceLock<plotters_backend::BackendColor> = OnceLock::new();
+#[cold]
+fn init_reference_trace_color() -> plotters_backend::BackendColor {
+    plotters_backend::BackendColor {
+        alpha: 0.6,
+        rgb: (255, 160, 0),
+    }
+}
+
 static SKIN_TONE_LINE: OnceLock<(f64, f64)> = OnceLock::new();
 #[cold]
 fn init_skin_tone_line() -> (f64, f64) {
@@ -49,9 +118,25 @@ fn init_skin_tone_line() -> (f64, f64) {
     (line_x, line_y)
 }
 
+/// Normalized RGB to the color of a plotted point
 #[inline(always)]
-pub fn draw_vector_scope(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    let image_vec = image.rgba();
+fn to_backend_rgb(rgb: [f32; 3]) -> (u8, u8, u8) {
+    let [red, green, blue] = rgb.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    (red, green, blue)
+}
+
+/// Position of a normalized Y′CbCr color around `center`, Cb to the right and Cr upwards.
+/// RGB frames are plotted at their BT.709 Cb / Cr, so both share one graticule.
+#[inline(always)]
+fn vector_scope_point(center: (i32, i32), [_, cb, cr]: [f32; 3]) -> (i32, i32) {
+    (
+        center.0 + (cb * VECTOR_SCOPE_CHROMA_SCALE) as i32,
+        center.1 - (cr * VECTOR_SCOPE_CHROMA_SCALE) as i32,
+    )
+}
+
+#[inline(always)]
+pub fn draw_vector_scope(image: &Frame) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let mut graph = vec![
         BACKGROUND_COLOR;
         *VECTOR_SCOPE_BUFFER_SIZE.get_or_init(init_vector_scope_buffer_size)
@@ -62,84 +147,108 @@ pub fn draw_vector_scope(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::E
             (VECTOR_SCOPE_WIDHT, VECTOR_SCOPE_HEIGHT),
         )
         .unwrap();
-        let mut index: usize = 0;
-        while index < image_vec.len() {
-            let red = image_vec[index];
-            let green = image_vec[index + 1];
-            let blue = image_vec[index + 2];
-            let _alpha = image_vec[index + 3];
-
-            let rgb = Rgb::from(red.into(), green.into(), blue.into());
+        plot_vector_scope_pixels(&mut root, image, VECTOR_SCOPE_CENTER, None);
+        draw_vector_scope_graticule(&mut root, VECTOR_SCOPE_CENTER);
+        root.present()?;
+    }
 
-            let backend_color = plotters_backend::BackendColor {
-                alpha: 1.0,
-                rgb: (red, green, blue),
-            };
-            let color_degree: f64 = (rgb.get_hue() + 103.4) as f64;
-            let color_degree_as_radians: f64 = color_degree.to_radians();
-            let saturation: f64 = rgb.get_saturation() as f64;
-            let color_delta_x = saturation * f64::cos(color_degree_as_radians);
-            let color_delta_y = saturation * f64::sin(color_degree_as_radians);
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        VECTOR_SCOPE_WIDHT,
+        VECTOR_SCOPE_HEIGHT,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write vector scope buffer");
+    Ok(graph_as_image)
+}
 
-            // plot pixels
+/// Vector scope plotted from the Cb / Cr samples of a video frame, without converting to RGB
+#[inline(always)]
+pub fn draw_vector_scope_ycbcr(image: &YuvFrame) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut graph = vec![
+        BACKGROUND_COLOR;
+        *VECTOR_SCOPE_BUFFER_SIZE.get_or_init(init_vector_scope_buffer_size)
+    ];
+    {
+        let mut root: BitMapBackend<RGBPixel> = BitMapBackend::with_buffer_and_format(
+            &mut graph,
+            (VECTOR_SCOPE_WIDHT, VECTOR_SCOPE_HEIGHT),
+        )
+        .unwrap();
+        let matrix = image.format().matrix;
+        let mut sample: usize = 0;
+        while sample < image.chroma_sample_count() {
+            let ycbcr = image.chroma_ycbcr(sample);
             root.draw_pixel(
-                (
-                    (VECTOR_SCOPE_CENTER.0 + color_delta_x as i32),
-                    (VECTOR_SCOPE_CENTER.1 - color_delta_y as i32),
-                ),
-                backend_color,
+                vector_scope_point(VECTOR_SCOPE_CENTER, ycbcr),
+                plotters_backend::BackendColor {
+                    alpha: 1.0,
+                    rgb: to_backend_rgb(matrix.to_rgb(ycbcr)),
+                },
             )
             .expect("Error on plot pixel");
-
-            index = index + (4 * ANALYZE_SKIP_RATIO);
-        }
-
-        // draw circle frame
-        let mut saturation: u32 = 25;
-        while saturation <= 100 {
-            root.draw_circle(
-                VECTOR_SCOPE_CENTER,
-                saturation,
-                VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
-                false,
-            )
-            .expect("Error on draw vector scope circle");
-            saturation += 25;
+            sample += ANALYZE_SKIP_RATIO;
         }
+        draw_vector_scope_graticule(&mut root, VECTOR_SCOPE_CENTER);
+        root.present()?;
+    }
 
-        // draw center line
-        root.draw_line(
-            (0, VECTOR_SCOPE_CENTER.1),
-            (
-                VECTOR_SCOPE_WIDHT.try_into().unwrap(),
-                VECTOR_SCOPE_CENTER.1,
-            ),
-            VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
-        )
-        .expect("Error on draw center line");
-        root.draw_line(
-            (VECTOR_SCOPE_CENTER.0, 0),
-            (
-                VECTOR_SCOPE_CENTER.0,
-                VECTOR_SCOPE_HEIGHT.try_into().unwrap(),
-            ),
-            VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
-        )
-        .expect("Error on draw center line");
-
-        // draw skin tone line
-        root.draw_line(
-            (VECTOR_SCOPE_CENTER.0, VECTOR_SCOPE_CENTER.1),
-            (
-                (VECTOR_SCOPE_CENTER.0 as f64 - SKIN_TONE_LINE.get_or_init(init_skin_tone_line).0)
-                    as i32,
-                (VECTOR_SCOPE_CENTER.1 as f64 - SKIN_TONE_LINE.get_or_init(init_skin_tone_line).1)
-                    as i32,
-            ),
-            VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
-        )
-        .expect("Error on draw skin tone line");
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        VECTOR_SCOPE_WIDHT,
+        VECTOR_SCOPE_HEIGHT,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write vector scope buffer");
+    Ok(graph_as_image)
+}
 
+/// Vector scope of `image` compared against `reference`.
+/// Overlay draws the reference trace in a single contrasting color under the current trace,
+/// split draws the reference on the left and the current frame on the right.
+#[inline(always)]
+pub fn draw_vector_scope_with_reference(
+    image: &Frame,
+    reference: &Frame,
+    mode: ReferenceMode,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let graph_width = match mode {
+        ReferenceMode::Overlay => VECTOR_SCOPE_WIDHT,
+        ReferenceMode::Split => VECTOR_SCOPE_WIDHT * 2,
+    };
+    let mut graph = vec![BACKGROUND_COLOR; (graph_width * VECTOR_SCOPE_HEIGHT * 3) as usize];
+    {
+        let mut root: BitMapBackend<RGBPixel> =
+            BitMapBackend::with_buffer_and_format(&mut graph, (graph_width, VECTOR_SCOPE_HEIGHT))
+                .unwrap();
+        match mode {
+            ReferenceMode::Overlay => {
+                plot_vector_scope_pixels(
+                    &mut root,
+                    reference,
+                    VECTOR_SCOPE_CENTER,
+                    Some(*REFERENCE_TRACE_COLOR.get_or_init(init_reference_trace_color)),
+                );
+                plot_vector_scope_pixels(&mut root, image, VECTOR_SCOPE_CENTER, None);
+                draw_vector_scope_graticule(&mut root, VECTOR_SCOPE_CENTER);
+            }
+            ReferenceMode::Split => {
+                let current_center = (
+                    VECTOR_SCOPE_CENTER.0 + VECTOR_SCOPE_WIDHT as i32,
+                    VECTOR_SCOPE_CENTER.1,
+                );
+                plot_vector_scope_pixels(&mut root, reference, VECTOR_SCOPE_CENTER, None);
+                draw_vector_scope_graticule(&mut root, VECTOR_SCOPE_CENTER);
+                plot_vector_scope_pixels(&mut root, image, current_center, None);
+                draw_vector_scope_graticule(&mut root, current_center);
+            }
+        }
         root.present()?;
     }
 
@@ -147,7 +256,7 @@ pub fn draw_vector_scope(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::E
     image::write_buffer_with_format(
         &mut Cursor::new(&mut graph_as_image),
         &graph,
-        VECTOR_SCOPE_WIDHT,
+        graph_width,
         VECTOR_SCOPE_HEIGHT,
         image::ColorType::Rgb8,
         image::ImageFormat::Png,
@@ -156,9 +265,83 @@ pub fn draw_vector_scope(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::E
     Ok(graph_as_image)
 }
 
+/// Plots every `ANALYZE_SKIP_RATIO`th pixel of `image` around `center`.
+/// The pixel's own color is used unless `trace_color` is given.
 #[inline(always)]
-pub fn draw_waveform(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    let image_vec = image.rgba();
+fn plot_vector_scope_pixels(
+    root: &mut BitMapBackend<RGBPixel>,
+    image: &Frame,
+    center: (i32, i32),
+    trace_color: Option<plotters_backend::BackendColor>,
+) {
+    let mut pixel: usize = 0;
+    while pixel < image.pixel_count() {
+        if !image.is_analyzed(pixel) {
+            pixel += ANALYZE_SKIP_RATIO;
+            continue;
+        }
+        let pixel_rgb = image.rgb(pixel);
+        // chroma of the unclamped values, colors outside of sRGB land beyond the outer circle
+        let ycbcr = YuvMatrix::Bt709.to_ycbcr(pixel_rgb);
+
+        let backend_color = trace_color.unwrap_or(plotters_backend::BackendColor {
+            alpha: 1.0,
+            rgb: to_backend_rgb(pixel_rgb),
+        });
+
+        // plot pixels
+        root.draw_pixel(vector_scope_point(center, ycbcr), backend_color)
+            .expect("Error on plot pixel");
+
+        pixel += ANALYZE_SKIP_RATIO;
+    }
+}
+
+#[inline(always)]
+fn draw_vector_scope_graticule(root: &mut BitMapBackend<RGBPixel>, center: (i32, i32)) {
+    let left = center.0 - VECTOR_SCOPE_CENTER.0;
+
+    // draw circle frame
+    let mut saturation: u32 = 25;
+    while saturation <= 100 {
+        root.draw_circle(
+            center,
+            saturation,
+            VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
+            false,
+        )
+        .expect("Error on draw vector scope circle");
+        saturation += 25;
+    }
+
+    // draw center line
+    root.draw_line(
+        (left, center.1),
+        (left + VECTOR_SCOPE_WIDHT as i32, center.1),
+        VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
+    )
+    .expect("Error on draw center line");
+    root.draw_line(
+        (center.0, 0),
+        (center.0, VECTOR_SCOPE_HEIGHT.try_into().unwrap()),
+        VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
+    )
+    .expect("Error on draw center line");
+
+    // draw skin tone line
+    root.draw_line(
+        (center.0, center.1),
+        (
+            (center.0 as f64 - SKIN_TONE_LINE.get_or_init(init_skin_tone_line).0) as i32,
+            (center.1 as f64 - SKIN_TONE_LINE.get_or_init(init_skin_tone_line).1) as i32,
+        ),
+        VECTOR_SCOPE_AUX_LINE_COLOR.get_or_init(init_vector_scope_aux_line_color),
+    )
+    .expect("Error on draw skin tone line");
+}
+
+#[inline(always)]
+pub fn draw_waveform(image: &Frame) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let image_width = image.width();
     let mut graph = vec![BACKGROUND_COLOR; (image_width * WAVEFORM_HEIGHT * 3) as usize];
     {
@@ -166,13 +349,9 @@ pub fn draw_waveform(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error
             BitMapBackend::with_buffer_and_format(&mut graph, (image_width, WAVEFORM_HEIGHT))
                 .unwrap();
 
-        let mut index: usize = 0;
         let mut pixel: usize = 0;
-        while index < image_vec.len() {
-            let red = image_vec[pixel * 4];
-            let green = image_vec[pixel * 4 + 1];
-            let blue = image_vec[pixel * 4 + 2];
-            // let _alpha = image_vec[pixel * 4 + 3];
+        while pixel < image.pixel_count() {
+            let (red, green, blue) = to_backend_rgb(image.rgb(pixel));
 
             let backend_color_red = plotters_backend::BackendColor {
                 alpha: 1.0,
@@ -199,7 +378,6 @@ pub fn draw_waveform(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error
                 .expect("Error on plot pixel");
 
             pixel += 1;
-            index += 4;
         }
 
         // draw 128 line
@@ -235,57 +413,144 @@ pub fn draw_waveform(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error
 }
 
 #[inline(always)]
-pub fn draw_waveform_luminance(image: &Image) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    let image_vec = image.rgba();
+pub fn draw_waveform_luminance(
+    image: &Frame,
+    meter: &LuminanceMeter,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let image_width = image.width();
     let mut graph = vec![BACKGROUND_COLOR; (image_width * WAVEFORM_HEIGHT * 3) as usize];
     {
         let mut root: BitMapBackend<RGBPixel> =
             BitMapBackend::with_buffer_and_format(&mut graph, (image_width, WAVEFORM_HEIGHT))
                 .unwrap();
+        plot_waveform_luminance_pixels(&mut root, image, (0, image_width), None, meter);
+        draw_waveform_luminance_graticule(&mut root, (0, image_width));
+        root.present()?;
+    }
 
-        let mut index: usize = 0;
-        let mut pixel: usize = 0;
-        while index < image_vec.len() {
-            let red = image_vec[pixel * 4];
-            let green = image_vec[pixel * 4 + 1];
-            let blue = image_vec[pixel * 4 + 2];
-            // let _alpha = image_vec[pixel * 4 + 3];
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        image_width,
+        WAVEFORM_HEIGHT,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write waveform buffer");
+    Ok(graph_as_image)
+}
 
-            let plot_x = (pixel as u32 % image_width) as i32;
-            let luminance = (0.30 * red as f32) + (0.56 * green as f32) + (0.14 * blue as f32);
-            let backend_color = plotters_backend::BackendColor {
-                alpha: 1.0,
-                rgb: (red, green, blue),
-            };
+/// Luminance waveform of `image` compared against `reference`.
+/// The reference is horizontally scaled to the width of the current frame.
+#[inline(always)]
+pub fn draw_waveform_luminance_with_reference(
+    image: &Frame,
+    reference: &Frame,
+    mode: ReferenceMode,
+    meter: &LuminanceMeter,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image_width = image.width();
+    let mut graph = vec![BACKGROUND_COLOR; (image_width * WAVEFORM_HEIGHT * 3) as usize];
+    {
+        let mut root: BitMapBackend<RGBPixel> =
+            BitMapBackend::with_buffer_and_format(&mut graph, (image_width, WAVEFORM_HEIGHT))
+                .unwrap();
+        match mode {
+            ReferenceMode::Overlay => {
+                plot_waveform_luminance_pixels(
+                    &mut root,
+                    reference,
+                    (0, image_width),
+                    Some(*REFERENCE_TRACE_COLOR.get_or_init(init_reference_trace_color)),
+                    meter,
+                );
+                plot_waveform_luminance_pixels(&mut root, image, (0, image_width), None, meter);
+                draw_waveform_luminance_graticule(&mut root, (0, image_width));
+            }
+            ReferenceMode::Split => {
+                let half_width = image_width / 2;
+                plot_waveform_luminance_pixels(&mut root, reference, (0, half_width), None, meter);
+                draw_waveform_luminance_graticule(&mut root, (0, half_width));
+                plot_waveform_luminance_pixels(
+                    &mut root,
+                    image,
+                    (half_width, image_width - half_width),
+                    None,
+                    meter,
+                );
+                draw_waveform_luminance_graticule(
+                    &mut root,
+                    (half_width, image_width - half_width),
+                );
+            }
+        }
+        root.present()?;
+    }
 
-            root.draw_pixel((plot_x, luminance as i32), backend_color)
-                .expect("Error on plot pixel");
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        image_width,
+        WAVEFORM_HEIGHT,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write waveform buffer");
+    Ok(graph_as_image)
+}
 
-            pixel += 1;
-            index += 4;
-        }
+/// Luminance waveform on a logarithmic scale from 0.001 to 10,000 nits
+#[inline(always)]
+pub fn draw_waveform_hdr(
+    image: &Frame,
+    converter: &NitsConverter,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image_width = image.width();
+    let scale = (WAVEFORM_HEIGHT - 1) as f32;
+    let mut graph = vec![BACKGROUND_COLOR; (image_width * WAVEFORM_HEIGHT * 3) as usize];
+    {
+        let mut root: BitMapBackend<RGBPixel> =
+            BitMapBackend::with_buffer_and_format(&mut graph, (image_width, WAVEFORM_HEIGHT))
+                .unwrap();
 
-        // draw luminance aux lines
-        let mut luminance: i32 = 64;
-        while luminance < 255 {
+        // decades behind the trace, reference lines on top
+        for exponent in hdr::NITS_MIN.log10().round() as i32..=hdr::NITS_MAX.log10().round() as i32
+        {
+            let plot_y = (hdr::nits_to_scale(10f32.powi(exponent)) * scale).round() as i32;
             root.draw_line(
-                (0, luminance),
-                (image_width.try_into().unwrap(), luminance),
-                WAVEFORM_AUX_LINE_COLOR.get_or_init(init_waveform_aux_line_color),
+                (0, plot_y),
+                (image_width as i32, plot_y),
+                HDR_DECADE_LINE_COLOR.get_or_init(init_hdr_decade_line_color),
             )
-            .expect("Error on draw luminance auxiliary line");
-            luminance += 64;
+            .expect("Error on draw decade line");
         }
 
-        // draw half widht line
-        root.draw_line(
-            ((image_width / 2).try_into().unwrap(), 0),
-            ((image_width / 2).try_into().unwrap(), 255),
-            WAVEFORM_AUX_LINE_COLOR.get_or_init(init_waveform_aux_line_color),
-        )
-        .expect("Error on draw half width line");
+        for (pixel_index, rgb) in image.pixels().enumerate() {
+            if !image.is_analyzed(pixel_index) {
+                continue;
+            }
+            let nits = converter.luminance(rgb);
+            let plot_x = (pixel_index as u32 % image_width) as i32;
+            let plot_y = (hdr::nits_to_scale(nits) * scale) as i32;
+            root.draw_pixel(
+                (plot_x, plot_y),
+                plotters_backend::BackendColor {
+                    alpha: 1.0,
+                    rgb: to_backend_rgb(rgb),
+                },
+            )
+            .expect("Error on plot pixel");
+        }
 
+        let reference_line_colors =
+            HDR_REFERENCE_LINE_COLORS.get_or_init(init_hdr_reference_line_colors);
+        for (nits, color) in hdr::REFERENCE_NITS.iter().zip(reference_line_colors) {
+            let plot_y = (hdr::nits_to_scale(*nits) * scale).round() as i32;
+            root.draw_line((0, plot_y), (image_width as i32, plot_y), color)
+                .expect("Error on draw reference nits line");
+        }
         root.present()?;
     }
 
@@ -301,3 +566,338 @@ pub fn draw_waveform_luminance(image: &Image) -> Result<Vec<u8>, Box<dyn std::er
     .expect("Failed to write waveform buffer");
     Ok(graph_as_image)
 }
+
+/// Plots the luminance of every pixel of `image` into the horizontal range
+/// `(left, width)` of the graph, scaling the columns when the widths differ.
+#[inline(always)]
+fn plot_waveform_luminance_pixels(
+    root: &mut BitMapBackend<RGBPixel>,
+    image: &Frame,
+    (left, width): (u32, u32),
+    trace_color: Option<plotters_backend::BackendColor>,
+    meter: &LuminanceMeter,
+) {
+    let image_width = image.width();
+
+    let mut pixel: usize = 0;
+    while pixel < image.pixel_count() {
+        if !image.is_analyzed(pixel) {
+            pixel += 1;
+            continue;
+        }
+        let rgb = image.rgb(pixel);
+
+        let column = pixel as u32 % image_width;
+        let plot_x = if width == image_width {
+            (left + column) as i32
+        } else {
+            (left as u64 + column as u64 * width as u64 / image_width as u64) as i32
+        };
+        // binned from the full precision luminance
+        let luminance = meter.measure(rgb);
+        let backend_color = trace_color.unwrap_or(plotters_backend::BackendColor {
+            alpha: 1.0,
+            rgb: to_backend_rgb(rgb),
+        });
+
+        root.draw_pixel((plot_x, luminance as i32), backend_color)
+            .expect("Error on plot pixel");
+
+        pixel += 1;
+    }
+}
+
+#[inline(always)]
+fn draw_waveform_luminance_graticule(
+    root: &mut BitMapBackend<RGBPixel>,
+    (left, width): (u32, u32),
+) {
+    let left = left as i32;
+    let width = width as i32;
+
+    // draw luminance aux lines
+    let mut luminance: i32 = 64;
+    while luminance < 255 {
+        root.draw_line(
+            (left, luminance),
+            (left + width, luminance),
+            WAVEFORM_AUX_LINE_COLOR.get_or_init(init_waveform_aux_line_color),
+        )
+        .expect("Error on draw luminance auxiliary line");
+        luminance += 64;
+    }
+
+    // draw half widht line
+    root.draw_line(
+        (left + width / 2, 0),
+        (left + width / 2, 255),
+        WAVEFORM_AUX_LINE_COLOR.get_or_init(init_waveform_aux_line_color),
+    )
+    .expect("Error on draw half width line");
+}
+
+/// Number of source pixels per side the loupe needs for `zoom`.
+/// Always odd so that there is a single center pixel.
+pub fn loupe_capture_size(zoom: u32) -> u32 {
+    (LOUPE_VIEW_SIZE / zoom.clamp(LOUPE_ZOOM_MIN, LOUPE_ZOOM_MAX)) | 1
+}
+
+/// Returns the (r, g, b) value of the pixel at `point` of `image`, clamped to the image.
+pub fn get_pixel(image: &Frame, point: (u32, u32)) -> (u8, u8, u8) {
+    let (x, y) = clamp_to_image(image, point);
+    to_backend_rgb(image.rgb((y * image.width() + x) as usize))
+}
+
+fn clamp_to_image(image: &Frame, (x, y): (u32, u32)) -> (u32, u32) {
+    (
+        x.min(image.width().saturating_sub(1)),
+        y.min(image.height().saturating_sub(1)),
+    )
+}
+
+/// Magnifies `image` with a frame around the pixel at `cursor`
+#[inline(always)]
+pub fn draw_loupe(
+    image: &Frame,
+    zoom: u32,
+    cursor: (u32, u32),
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image_vec = image.to_rgba8();
+    let zoom = zoom.clamp(LOUPE_ZOOM_MIN, LOUPE_ZOOM_MAX);
+    let source_width = image.width();
+    let source_height = image.height();
+    let graph_width = source_width * zoom;
+    let graph_height = source_height * zoom;
+    let mut graph = vec![BACKGROUND_COLOR; (graph_width * graph_height * 3) as usize];
+
+    // nearest-neighbor magnification
+    for graph_y in 0..graph_height {
+        let source_row = ((graph_y / zoom) * source_width) as usize;
+        for graph_x in 0..graph_width {
+            let source_index = (source_row + (graph_x / zoom) as usize) * 4;
+            let graph_index = ((graph_y * graph_width + graph_x) * 3) as usize;
+            graph[graph_index..graph_index + 3]
+                .copy_from_slice(&image_vec[source_index..source_index + 3]);
+        }
+    }
+
+    {
+        let mut root: BitMapBackend<RGBPixel> =
+            BitMapBackend::with_buffer_and_format(&mut graph, (graph_width, graph_height)).unwrap();
+
+        // draw pixel grid
+        if zoom >= LOUPE_GRID_MIN_ZOOM {
+            for column in 1..source_width {
+                let x = (column * zoom) as i32;
+                root.draw_line(
+                    (x, 0),
+                    (x, graph_height as i32),
+                    LOUPE_GRID_LINE_COLOR.get_or_init(init_loupe_grid_line_color),
+                )
+                .expect("Error on draw loupe grid line");
+            }
+            for row in 1..source_height {
+                let y = (row * zoom) as i32;
+                root.draw_line(
+                    (0, y),
+                    (graph_width as i32, y),
+                    LOUPE_GRID_LINE_COLOR.get_or_init(init_loupe_grid_line_color),
+                )
+                .expect("Error on draw loupe grid line");
+            }
+        }
+
+        // draw cursor pixel frame
+        let (cursor_x, cursor_y) = clamp_to_image(image, cursor);
+        let cursor_top_left = ((cursor_x * zoom) as i32, (cursor_y * zoom) as i32);
+        root.draw_rect(
+            cursor_top_left,
+            (
+                cursor_top_left.0 + zoom as i32,
+                cursor_top_left.1 + zoom as i32,
+            ),
+            LOUPE_CENTER_LINE_COLOR.get_or_init(init_loupe_center_line_color),
+            false,
+        )
+        .expect("Error on draw loupe center frame");
+
+        root.present()?;
+    }
+
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        graph_width,
+        graph_height,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write loupe buffer");
+    Ok(graph_as_image)
+}
+
+#[inline(always)]
+pub fn draw_palette(palette: &[PaletteColor]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut graph = vec![BACKGROUND_COLOR; (PALETTE_WIDTH * PALETTE_HEIGHT * 3) as usize];
+    {
+        let mut root: BitMapBackend<RGBPixel> =
+            BitMapBackend::with_buffer_and_format(&mut graph, (PALETTE_WIDTH, PALETTE_HEIGHT))
+                .unwrap();
+
+        // swatch width is proportional to the coverage
+        let total_coverage: f64 = palette.iter().map(|color| color.coverage).sum();
+        let mut left: f64 = 0.0;
+        for color in palette {
+            let width = color.coverage / total_coverage * PALETTE_WIDTH as f64;
+            root.draw_rect(
+                (left.round() as i32, 0),
+                ((left + width).round() as i32, PALETTE_HEIGHT as i32),
+                &plotters_backend::BackendColor {
+                    alpha: 1.0,
+                    rgb: (color.red, color.green, color.blue),
+                },
+                true,
+            )
+            .expect("Error on draw palette swatch");
+            left += width;
+        }
+
+        root.present()?;
+    }
+
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        PALETTE_WIDTH,
+        PALETTE_HEIGHT,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )
+    .expect("Failed to write palette buffer");
+    Ok(graph_as_image)
+}
+
+/// False color / zebra / gamut warning rendering of the capture itself.
+/// Large captures are downscaled (nearest neighbor) to `FALSE_COLOR_MAX_WIDTH`.
+#[inline(always)]
+pub fn draw_false_color(
+    image: &Frame,
+    settings: &FalseColorSettings,
+    thresholds: &LegalRangeThresholds,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if image.pixel_count() == 0 {
+        return Err("Empty frame".into());
+    }
+    let step = image.width().div_ceil(FALSE_COLOR_MAX_WIDTH).max(1);
+    // rounded up so that a wide, thin capture keeps a row
+    let graph_width = image.width().div_ceil(step).max(1);
+    let graph_height = image.height().div_ceil(step).max(1);
+    let mut graph = vec![BACKGROUND_COLOR; (graph_width * graph_height * 3) as usize];
+
+    for graph_y in 0..graph_height {
+        let source_row = (graph_y * step * image.width()) as usize;
+        for graph_x in 0..graph_width {
+            let source_index = source_row + (graph_x * step) as usize;
+            // excluded pixels keep the background
+            if !image.is_analyzed(source_index) {
+                continue;
+            }
+            let (red, green, blue) = false_color::map_pixel(
+                image.rgb(source_index),
+                (graph_x, graph_y),
+                settings,
+                thresholds,
+            );
+            let graph_index = ((graph_y * graph_width + graph_x) * 3) as usize;
+            graph[graph_index] = red;
+            graph[graph_index + 1] = green;
+            graph[graph_index + 2] = blue;
+        }
+    }
+
+    let mut graph_as_image: Vec<u8> = Vec::new();
+    image::write_buffer_with_format(
+        &mut Cursor::new(&mut graph_as_image),
+        &graph,
+        graph_width,
+        graph_height,
+        image::ColorType::Rgb8,
+        image::ImageFormat::Png,
+    )?;
+    Ok(graph_as_image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+    use crate::model::yuv_video::{ChromaSubsampling, YuvFormat, YuvRange, YuvVideo};
+
+    fn gray_frame(width: u32, height: u32) -> Frame {
+        Frame::new(
+            width,
+            height,
+            FrameSamples::Rgba8(vec![128; (width * height * 4) as usize]),
+        )
+    }
+
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory(png).unwrap();
+        (image.width(), image.height())
+    }
+
+    /// A single BT.709 4:4:4 sample read back from a headerless file
+    fn yuv_sample(ycbcr: [u8; 3]) -> YuvFrame {
+        let path =
+            std::env::temp_dir().join(format!("vector-scope-test-{}.yuv", std::process::id()));
+        std::fs::write(&path, ycbcr).unwrap();
+        let format = YuvFormat {
+            width: 1,
+            height: 1,
+            subsampling: ChromaSubsampling::Yuv444,
+            bit_depth: 8,
+            matrix: YuvMatrix::Bt709,
+            range: YuvRange::Full,
+            frame_rate: 25.0,
+        };
+        let frame = YuvVideo::open_raw(&path, format)
+            .unwrap()
+            .read_frame(0)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        frame
+    }
+
+    #[test]
+    fn vector_scope_plots_rgb_and_ycbcr_at_the_same_point() {
+        let pixels = |png: Vec<u8>| image::load_from_memory(&png).unwrap().to_rgb8().into_raw();
+        let gray = pixels(draw_vector_scope(&gray_frame(1, 1)).unwrap());
+        // within the RGB gamut, so that the RGB frame is not clipped
+        for ycbcr in [[128, 100, 160], [100, 150, 110], [160, 110, 120]] {
+            let sample = yuv_sample(ycbcr);
+            let from_ycbcr = pixels(draw_vector_scope_ycbcr(&sample).unwrap());
+            let from_rgb = pixels(draw_vector_scope(&sample.to_frame()).unwrap());
+            assert!(from_ycbcr != gray, "{ycbcr:?} is not plotted");
+            assert!(from_ycbcr == from_rgb, "{ycbcr:?} is plotted apart");
+        }
+    }
+
+    #[test]
+    fn false_color_keeps_thin_frames() {
+        let settings = FalseColorSettings::default();
+        let thresholds = LegalRangeThresholds::default();
+        for ((width, height), expected) in [
+            ((1921, 1), (641, 1)),
+            ((1921, 3), (641, 1)),
+            ((1921, 4), (641, 2)),
+            ((1, 1), (1, 1)),
+            ((1920, 1080), (960, 540)),
+        ] {
+            let png = draw_false_color(&gray_frame(width, height), &settings, &thresholds).unwrap();
+            assert_eq!(png_size(&png), expected, "{width}x{height}");
+        }
+        assert!(draw_false_color(&gray_frame(0, 0), &settings, &thresholds).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::model::frame::Frame;
+use crate::model::transfer_function::{DecodeTable, TransferFunction};
+use serde::{Deserialize, Serialize};
+
+/// SDR white when the input is not HDR
+const SDR_WHITE_NITS: f32 = 100.0;
+const PQ_PEAK_NITS: f32 = 10000.0;
+/// nominal peak of an HLG display (BT.2100)
+const HLG_PEAK_NITS: f32 = 1000.0;
+/// system gamma of HLG at the nominal peak (BT.2100)
+const HLG_SYSTEM_GAMMA: f32 = 1.2;
+/// BT.2100 (BT.2020) coefficients
+const LUMINANCE_WEIGHTS: (f32, f32, f32) = (0.2627, 0.6780, 0.0593);
+
+/// Range of the logarithmic nits scale
+pub const NITS_MIN: f32 = 0.001;
+pub const NITS_MAX: f32 = 10000.0;
+/// SDR white, HDR reference white (BT.2408) and a common mastering peak
+pub const REFERENCE_NITS: [f32; 3] = [100.0, 203.0, 1000.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HdrMode {
+    /// SDR waveform
+    Off,
+    Pq,
+    Hlg,
+}
+
+/// Light levels in nits of a single frame and accumulated since the last reset
+/// (CTA-861.3: the light level of a pixel is its brightest component).
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct HdrStatistics {
+    pub frame_max_light_level: f32,
+    pub frame_average_light_level: f32,
+    pub max_cll: f32,
+    pub max_fall: f32,
+}
+
+/// Converts encoded pixels to display light in nits
+pub struct NitsConverter {
+    mode: HdrMode,
+    /// SDR / PQ: display light in nits, HLG: scene light (0.0 - 1.0)
+    linear_table: DecodeTable,
+}
+
+impl NitsConverter {
+    pub fn new(mode: HdrMode) -> Self {
+        let linear_table = match mode {
+            HdrMode::Off => TransferFunction::Srgb.decode_table().scale(SDR_WHITE_NITS),
+            HdrMode::Pq => TransferFunction::Pq.decode_table().scale(PQ_PEAK_NITS),
+            HdrMode::Hlg => TransferFunction::Hlg.decode_table(),
+        };
+        Self { mode, linear_table }
+    }
+
+    /// Display light of each component of normalized RGB in nits
+    #[inline(always)]
+    pub fn components(&self, rgb: [f32; 3]) -> (f32, f32, f32) {
+        let red = self.linear_table.get(rgb[0]);
+        let green = self.linear_table.get(rgb[1]);
+        let blue = self.linear_table.get(rgb[2]);
+        match self.mode {
+            HdrMode::Hlg => {
+                // OOTF: the scene luminance decides the gain of every component
+                let gain =
+                    HLG_PEAK_NITS * bt2100_luminance(red, green, blue).powf(HLG_SYSTEM_GAMMA - 1.0);
+                (red * gain, green * gain, blue * gain)
+            }
+            _ => (red, green, blue),
+        }
+    }
+
+    /// Display luminance of normalized RGB in nits
+    #[inline(always)]
+    pub fn luminance(&self, rgb: [f32; 3]) -> f32 {
+        let (red, green, blue) = self.components(rgb);
+        bt2100_luminance(red, green, blue)
+    }
+}
+
+#[inline(always)]
+fn bt2100_luminance(red: f32, green: f32, blue: f32) -> f32 {
+    LUMINANCE_WEIGHTS.0 * red + LUMINANCE_WEIGHTS.1 * green + LUMINANCE_WEIGHTS.2 * blue
+}
+
+/// Position (0.0 - 1.0) of `nits` on the logarithmic scale
+#[inline(always)]
+pub fn nits_to_scale(nits: f32) -> f32 {
+    let nits = nits.clamp(NITS_MIN, NITS_MAX);
+    (nits.log10() - NITS_MIN.log10()) / (NITS_MAX.log10() - NITS_MIN.log10())
+}
+
+/// Accumulates MaxCLL / MaxFALL over frames
+#[derive(Default)]
+pub struct LightLevelMeter {
+    max_cll: f32,
+    max_fall: f32,
+}
+
+impl LightLevelMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn measure(&mut self, image: &Frame, converter: &NitsConverter) -> HdrStatistics {
+        let mut frame_max: f32 = 0.0;
+        let mut total: f64 = 0.0;
+        let mut pixel_count: usize = 0;
+        for rgb in image.analyzed_pixels() {
+            let (red, green, blue) = converter.components(rgb);
+            let light_level = red.max(green).max(blue);
+            frame_max = frame_max.max(light_level);
+            total += light_level as f64;
+            pixel_count += 1;
+        }
+        let frame_average = match pixel_count {
+            0 => 0.0,
+            _ => (total / pixel_count as f64) as f32,
+        };
+        self.max_cll = self.max_cll.max(frame_max);
+        self.max_fall = self.max_fall.max(frame_average);
+        HdrStatistics {
+            frame_max_light_level: frame_max,
+            frame_average_light_level: frame_average,
+            max_cll: self.max_cll,
+            max_fall: self.max_fall,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} != {expected}"
+        );
+    }
+
+    fn gray(value: f32) -> [f32; 3] {
+        [value; 3]
+    }
+
+    #[test]
+    fn converts_reference_whites_to_nits() {
+        let sdr = NitsConverter::new(HdrMode::Off);
+        assert_close(sdr.luminance(gray(1.0)), 100.0, 0.01);
+        assert_eq!(sdr.luminance(gray(0.0)), 0.0);
+
+        let pq = NitsConverter::new(HdrMode::Pq);
+        assert_close(pq.luminance(gray(0.5081)), 100.0, 0.2);
+        assert_close(
+            pq.luminance(gray(TransferFunction::Pq.encode(0.1))),
+            1000.0,
+            1.0,
+        );
+        assert_close(pq.luminance(gray(1.0)), 10000.0, 1.0);
+
+        let hlg = NitsConverter::new(HdrMode::Hlg);
+        assert_close(hlg.luminance(gray(0.75)), 203.0, 1.0);
+        assert_close(hlg.luminance(gray(1.0)), 1000.0, 0.5);
+        // the gain of the OOTF follows the luminance, so a primary keeps its hue
+        let (red, green, blue) = hlg.components([1.0, 0.0, 0.0]);
+        assert!(red > 0.0 && red < 1000.0, "{red}");
+        assert_eq!((green, blue), (0.0, 0.0));
+    }
+
+    #[test]
+    fn places_reference_nits_on_the_log_scale() {
+        assert_eq!(nits_to_scale(NITS_MIN), 0.0);
+        assert_eq!(nits_to_scale(NITS_MAX), 1.0);
+        // clamped to the scale
+        assert_eq!(nits_to_scale(0.0), 0.0);
+        assert_eq!(nits_to_scale(20000.0), 1.0);
+        // seven decades from 0.001 to 10,000 nits
+        let [sdr_white, hdr_white, peak] = REFERENCE_NITS.map(nits_to_scale);
+        assert_close(sdr_white, 5.0 / 7.0, 1e-6);
+        assert_close(hdr_white, (203f32.log10() + 3.0) / 7.0, 1e-6);
+        assert_close(peak, 6.0 / 7.0, 1e-6);
+    }
+
+    #[test]
+    fn accumulates_max_cll_and_max_fall() {
+        let converter = NitsConverter::new(HdrMode::Pq);
+        let nits_1000 = TransferFunction::Pq.encode(0.1);
+        // the light level of a pixel is its brightest component
+        let bright = Frame::new(
+            2,
+            1,
+            FrameSamples::Rgba32F(vec![0.5081, 0.0, 0.0, 1.0, 0.0, nits_1000, 0.0, 1.0]),
+        );
+        let dark = Frame::new(1, 1, FrameSamples::Rgba32F(vec![0.5081, 0.5081, 0.0, 1.0]));
+
+        let mut meter = LightLevelMeter::new();
+        let statistics = meter.measure(&bright, &converter);
+        assert_close(statistics.frame_max_light_level, 1000.0, 1.0);
+        assert_close(statistics.frame_average_light_level, 550.0, 1.0);
+        assert_close(statistics.max_cll, 1000.0, 1.0);
+        assert_close(statistics.max_fall, 550.0, 1.0);
+
+        let statistics = meter.measure(&dark, &converter);
+        assert_close(statistics.frame_max_light_level, 100.0, 0.2);
+        assert_close(statistics.frame_average_light_level, 100.0, 0.2);
+        assert_close(statistics.max_cll, 1000.0, 1.0);
+        assert_close(statistics.max_fall, 550.0, 1.0);
+
+        meter.reset();
+        let statistics = meter.measure(&dark, &converter);
+        assert_close(statistics.max_cll, 100.0, 0.2);
+        assert_close(statistics.max_fall, 100.0, 0.2);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::model::frame::{Frame, FrameSamples};
+use image::DynamicImage;
+use std::path::Path;
+
+pub const SUPPORTED_EXTENSIONS: &[&str] =
+    &["png", "jpg", "jpeg", "tif", "tiff", "exr", "webp", "bmp"];
+
+/// Loads an image file as a frame, keeping 16-bit and float samples.
+pub fn load_image(path: &Path) -> Result<Frame, image::ImageError> {
+    let image = image::open(path)?;
+    let (width, height) = (image.width(), image.height());
+    let samples = match image {
+        DynamicImage::ImageLuma16(_)
+        | DynamicImage::ImageLumaA16(_)
+        | DynamicImage::ImageRgb16(_)
+        | DynamicImage::ImageRgba16(_) => FrameSamples::Rgba16(image.to_rgba16().into_raw()),
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+            FrameSamples::Rgba32F(image.to_rgba32f().into_raw())
+        }
+        _ => FrameSamples::Rgba8(image.to_rgba8().into_raw()),
+    };
+    Ok(Frame::new(width, height, samples))
+}
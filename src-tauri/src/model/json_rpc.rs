@@ -0,0 +1,267 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+const JSON_RPC_VERSION: &str = "2.0";
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// implementation defined server error
+const SERVER_ERROR: i32 = -32000;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {method}"),
+        }
+    }
+
+    pub fn invalid_params(message: impl std::fmt::Display) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: format!("Invalid params: {message}"),
+        }
+    }
+
+    /// The method was called but failed
+    pub fn server_error(message: impl std::fmt::Display) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// notifications have no id and get no response, `"id": null` is still a request
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+}
+
+/// `Some` for any value, `null` included, so that only a missing field is `None`
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+impl Response {
+    fn new(id: Value, outcome: Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: JSON_RPC_VERSION,
+            result,
+            error,
+            id,
+        }
+    }
+}
+
+/// Named params of a method, `null` or missing params are read as `{}`
+pub fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Null => Value::Object(Default::default()),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+/// Handles one JSON-RPC 2.0 message (a request, a notification or a batch)
+/// and returns the response to send back, if any.
+pub fn handle_message(
+    message: &str,
+    handler: &dyn Fn(&str, Value) -> Result<Value, RpcError>,
+) -> Option<String> {
+    let response = match serde_json::from_str::<Value>(message) {
+        Err(err) => Some(
+            serde_json::to_value(Response::new(
+                Value::Null,
+                Err(RpcError {
+                    code: PARSE_ERROR,
+                    message: format!("Parse error: {err}"),
+                }),
+            ))
+            .unwrap(),
+        ),
+        Ok(Value::Array(batch)) if !batch.is_empty() => {
+            let responses: Vec<Value> = batch
+                .into_iter()
+                .filter_map(|request| handle_request(request, handler))
+                .collect();
+            match responses.is_empty() {
+                true => None,
+                false => Some(Value::Array(responses)),
+            }
+        }
+        Ok(request) => handle_request(request, handler),
+    };
+    response.map(|response| response.to_string())
+}
+
+fn handle_request(
+    request: Value,
+    handler: &dyn Fn(&str, Value) -> Result<Value, RpcError>,
+) -> Option<Value> {
+    let response = match serde_json::from_value::<Request>(request) {
+        Ok(request) if request.jsonrpc == JSON_RPC_VERSION => {
+            let outcome = handler(&request.method, request.params);
+            Response::new(request.id?, outcome)
+        }
+        _ => Response::new(
+            Value::Null,
+            Err(RpcError {
+                code: INVALID_REQUEST,
+                message: "Invalid Request".to_string(),
+            }),
+        ),
+    };
+    Some(serde_json::to_value(response).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn handler(method: &str, params: Value) -> Result<Value, RpcError> {
+        #[derive(Deserialize)]
+        struct Operands {
+            a: i64,
+            b: i64,
+        }
+        match method {
+            "add" => {
+                let operands: Operands = super::params(params)?;
+                Ok(json!(operands.a + operands.b))
+            }
+            "fail" => Err(RpcError::server_error("failed")),
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    fn call(message: &str) -> Option<Value> {
+        handle_message(message, &handler).map(|response| serde_json::from_str(&response).unwrap())
+    }
+
+    #[test]
+    fn answers_requests() {
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","method":"add","params":{"a":1,"b":2},"id":7}"#),
+            Some(json!({"jsonrpc": "2.0", "result": 3, "id": 7}))
+        );
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","method":"fail","id":"x"}"#),
+            Some(
+                json!({"jsonrpc": "2.0", "error": {"code": SERVER_ERROR, "message": "failed"}, "id": "x"})
+            )
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let response = call(r#"{"jsonrpc":"2.0","method""#).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[test]
+    fn reports_invalid_requests() {
+        for message in [
+            r#"{"jsonrpc":"1.0","method":"add","id":1}"#,
+            r#"{"jsonrpc":"2.0","id":1}"#,
+            r#"{"jsonrpc":"2.0","method":1,"id":1}"#,
+            "42",
+            "[]",
+        ] {
+            let response = call(message).unwrap();
+            assert_eq!(response["error"]["code"], INVALID_REQUEST, "{message}");
+            assert_eq!(response["id"], Value::Null, "{message}");
+        }
+    }
+
+    #[test]
+    fn reports_unknown_methods() {
+        let response = call(r#"{"jsonrpc":"2.0","method":"nope","id":1}"#).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 1);
+    }
+
+    #[test]
+    fn reports_invalid_params() {
+        for params in [r#"{"a":1}"#, r#"{"a":"1","b":2}"#, "[1]", "null"] {
+            let message = format!(r#"{{"jsonrpc":"2.0","method":"add","params":{params},"id":1}}"#);
+            let response = call(&message).unwrap();
+            assert_eq!(response["error"]["code"], INVALID_PARAMS, "{params}");
+        }
+    }
+
+    #[test]
+    fn does_not_answer_notifications() {
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","method":"add","params":{"a":1,"b":2}}"#),
+            None
+        );
+        // errors of notifications are not reported either
+        assert_eq!(call(r#"{"jsonrpc":"2.0","method":"nope"}"#), None);
+    }
+
+    #[test]
+    fn answers_null_id() {
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","method":"add","params":{"a":1,"b":2},"id":null}"#),
+            Some(json!({"jsonrpc": "2.0", "result": 3, "id": null}))
+        );
+    }
+
+    #[test]
+    fn answers_batches() {
+        let response = call(
+            r#"[
+                {"jsonrpc":"2.0","method":"add","params":{"a":1,"b":2},"id":1},
+                {"jsonrpc":"2.0","method":"add","params":{"a":3,"b":4}},
+                {"jsonrpc":"2.0","method":"nope","id":2},
+                {"foo":"bar"}
+            ]"#,
+        )
+        .unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(
+            responses[0],
+            json!({"jsonrpc": "2.0", "result": 3, "id": 1})
+        );
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+
+        // nothing to send back for a batch of notifications
+        assert_eq!(
+            call(r#"[{"jsonrpc":"2.0","method":"add","params":{"a":1,"b":2}}]"#),
+            None
+        );
+    }
+}
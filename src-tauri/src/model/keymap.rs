@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Actions that can be bound to a global shortcut
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    /// analyzes one frame and switches to manual refresh
+    Refresh,
+    ToggleManualMode,
+    ToggleHold,
+    /// steps the waveform through luma, linear luminance, PQ and HLG
+    CycleScopeMode,
+    Export,
+    /// sets the capture area to the window under the cursor
+    SnapCaptureArea,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 6] = [
+        KeyAction::Refresh,
+        KeyAction::ToggleManualMode,
+        KeyAction::ToggleHold,
+        KeyAction::CycleScopeMode,
+        KeyAction::Export,
+        KeyAction::SnapCaptureArea,
+    ];
+
+    fn default_accelerator(self) -> Option<&'static str> {
+        match self {
+            KeyAction::Refresh => Some("CommandOrControl+Shift+R"),
+            KeyAction::ToggleManualMode => Some("CommandOrControl+Shift+M"),
+            KeyAction::ToggleHold => Some("CommandOrControl+Shift+H"),
+            KeyAction::CycleScopeMode => Some("CommandOrControl+Shift+W"),
+            KeyAction::Export => Some("CommandOrControl+Shift+E"),
+            KeyAction::SnapCaptureArea => Some("CommandOrControl+Shift+A"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KeymapError {
+    InvalidAccelerator(String),
+    /// the accelerator is already bound to `action`
+    Conflict {
+        accelerator: String,
+        action: KeyAction,
+    },
+}
+
+impl std::fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeymapError::InvalidAccelerator(accelerator) => {
+                write!(f, "Invalid shortcut: {accelerator}")
+            }
+            KeymapError::Conflict {
+                accelerator,
+                action,
+            } => write!(f, "{accelerator} is already used by {action:?}"),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// Global shortcut of every action, `None` when unbound.
+/// Actions missing in saved settings get their default binding.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<KeyAction, Option<String>>")]
+pub struct Keymap {
+    #[serde(flatten)]
+    bindings: BTreeMap<KeyAction, Option<String>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .iter()
+                .map(|&action| (action, action.default_accelerator().map(String::from)))
+                .collect(),
+        }
+    }
+}
+
+impl From<BTreeMap<KeyAction, Option<String>>> for Keymap {
+    fn from(mut bindings: BTreeMap<KeyAction, Option<String>>) -> Self {
+        for (action, accelerator) in Keymap::default().bindings {
+            bindings.entry(action).or_insert(accelerator);
+        }
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn get(&self, action: KeyAction) -> Option<&str> {
+        self.bindings
+            .get(&action)
+            .and_then(|accelerator| accelerator.as_deref())
+    }
+
+    /// Bound actions
+    pub fn bindings(&self) -> impl Iterator<Item = (KeyAction, &str)> {
+        self.bindings
+            .iter()
+            .filter_map(|(&action, accelerator)| Some((action, accelerator.as_deref()?)))
+    }
+
+    /// Binds `action` to `accelerator` (or unbinds it with `None`).
+    /// The keymap is left unchanged when the accelerator is invalid or bound to another action.
+    pub fn rebind(
+        &mut self,
+        action: KeyAction,
+        accelerator: Option<&str>,
+    ) -> Result<(), KeymapError> {
+        let accelerator = match accelerator {
+            Some(accelerator) => {
+                let normalized = normalize(accelerator)?;
+                if let Some(other) = self.action_of(&normalized, action) {
+                    return Err(KeymapError::Conflict {
+                        accelerator: accelerator.trim().to_string(),
+                        action: other,
+                    });
+                }
+                Some(accelerator.trim().to_string())
+            }
+            None => None,
+        };
+        self.bindings.insert(action, accelerator);
+        Ok(())
+    }
+
+    /// Checks every accelerator and that no two actions share one
+    pub fn validate(&self) -> Result<(), KeymapError> {
+        for (action, accelerator) in self.bindings() {
+            if let Some(other) = self.action_of(&normalize(accelerator)?, action) {
+                return Err(KeymapError::Conflict {
+                    accelerator: accelerator.to_string(),
+                    action: other,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Action other than `except` bound to the normalized accelerator
+    fn action_of(&self, normalized: &str, except: KeyAction) -> Option<KeyAction> {
+        self.bindings()
+            .find(|&(action, accelerator)| {
+                action != except && normalize(accelerator).as_deref() == Ok(normalized)
+            })
+            .map(|(action, _)| action)
+    }
+}
+
+/// Comparable form of an accelerator: case and aliases ignored, modifiers in a fixed order.
+/// `CommandOrControl` is resolved for the platform so that it matches `Control` or `Super`.
+fn normalize(accelerator: &str) -> Result<String, KeymapError> {
+    let invalid = || KeymapError::InvalidAccelerator(accelerator.to_string());
+    let mut modifiers = [false; 4];
+    let mut key = None;
+    for token in accelerator.split('+').map(str::trim) {
+        let modifier = match token.to_lowercase().as_str() {
+            "" => return Err(invalid()),
+            "commandorcontrol" | "commandorctrl" | "cmdorctrl" | "cmdorcontrol" => {
+                match cfg!(target_os = "macos") {
+                    true => 3,
+                    false => 0,
+                }
+            }
+            "control" | "ctrl" => 0,
+            "alt" | "option" => 1,
+            "shift" => 2,
+            "super" | "command" | "cmd" | "meta" => 3,
+            _ if key.is_none() => {
+                key = Some(token.to_uppercase());
+                continue;
+            }
+            // only one non-modifier key
+            _ => return Err(invalid()),
+        };
+        modifiers[modifier] = true;
+    }
+    let key = key.ok_or_else(invalid)?;
+    let mut normalized: Vec<&str> = ["Control", "Alt", "Shift", "Super"]
+        .iter()
+        .zip(modifiers)
+        .filter_map(|(&name, pressed)| pressed.then_some(name))
+        .collect();
+    normalized.push(&key);
+    Ok(normalized.join("+"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `CommandOrControl` as `normalize` resolves it
+    const COMMAND_OR_CONTROL: &str = match cfg!(target_os = "macos") {
+        true => "Super",
+        false => "Control",
+    };
+
+    #[test]
+    fn orders_modifiers() {
+        assert_eq!(
+            normalize("Shift+Alt+Ctrl+K").unwrap(),
+            "Control+Alt+Shift+K"
+        );
+        assert_eq!(normalize("Super + Shift + F1").unwrap(), "Shift+Super+F1");
+        assert_eq!(normalize("Shift+Control+A"), normalize("Control+Shift+A"));
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        let expected = format!("{COMMAND_OR_CONTROL}+Shift+R");
+        for accelerator in [
+            "CommandOrControl+Shift+R",
+            "CommandOrCtrl+Shift+R",
+            "CmdOrCtrl+Shift+R",
+            "CmdOrControl+Shift+R",
+        ] {
+            assert_eq!(normalize(accelerator).unwrap(), expected, "{accelerator}");
+        }
+        assert_eq!(normalize("Ctrl+Option+X"), normalize("Control+Alt+X"));
+        assert_eq!(normalize("Cmd+X"), normalize("Super+X"));
+        assert_eq!(normalize("Meta+X"), normalize("Command+X"));
+    }
+
+    #[test]
+    fn folds_case() {
+        assert_eq!(normalize("ctrl+shift+r"), normalize("CONTROL+SHIFT+R"));
+        assert_eq!(normalize("cmdorctrl+a"), normalize("CommandOrControl+A"));
+    }
+
+    #[test]
+    fn rejects_invalid_accelerators() {
+        for accelerator in ["", "Ctrl+", "+A", "Ctrl++A", "Shift", "Ctrl+A+B"] {
+            assert_eq!(
+                normalize(accelerator),
+                Err(KeymapError::InvalidAccelerator(accelerator.to_string())),
+                "{accelerator}"
+            );
+        }
+    }
+
+    #[test]
+    fn default_keymap_is_valid() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.validate(), Ok(()));
+        assert_eq!(keymap.bindings().count(), KeyAction::ALL.len());
+        assert_eq!(
+            keymap.get(KeyAction::Refresh),
+            Some("CommandOrControl+Shift+R")
+        );
+    }
+
+    #[test]
+    fn refuses_duplicate_bindings() {
+        let mut keymap = Keymap::default();
+        let accelerator = format!("shift+{COMMAND_OR_CONTROL}+r");
+        assert_eq!(
+            keymap.rebind(KeyAction::Export, Some(&accelerator)),
+            Err(KeymapError::Conflict {
+                accelerator: accelerator.clone(),
+                action: KeyAction::Refresh,
+            })
+        );
+        assert_eq!(keymap, Keymap::default());
+        // rebinding an action to its own shortcut is no conflict
+        assert_eq!(
+            keymap.rebind(KeyAction::Refresh, Some(&accelerator)),
+            Ok(())
+        );
+        assert_eq!(keymap.get(KeyAction::Refresh), Some(accelerator.as_str()));
+        assert_eq!(
+            keymap.rebind(KeyAction::Export, Some("Ctrl+")),
+            Err(KeymapError::InvalidAccelerator("Ctrl+".to_string()))
+        );
+    }
+
+    #[test]
+    fn unbinds_actions() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.rebind(KeyAction::Refresh, None), Ok(()));
+        assert_eq!(keymap.get(KeyAction::Refresh), None);
+        assert!(keymap
+            .bindings()
+            .all(|(action, _)| action != KeyAction::Refresh));
+        // the freed shortcut can be taken by another action
+        assert_eq!(
+            keymap.rebind(KeyAction::Export, Some(" CommandOrControl+Shift+R ")),
+            Ok(())
+        );
+        assert_eq!(
+            keymap.get(KeyAction::Export),
+            Some("CommandOrControl+Shift+R")
+        );
+    }
+
+    #[test]
+    fn fills_in_missing_actions_from_settings() {
+        let keymap: Keymap =
+            serde_json::from_str(r#"{"refresh": null, "export": "Alt+E"}"#).unwrap();
+        assert_eq!(keymap.get(KeyAction::Refresh), None);
+        assert_eq!(keymap.get(KeyAction::Export), Some("Alt+E"));
+        assert_eq!(
+            keymap.get(KeyAction::ToggleHold),
+            Keymap::default().get(KeyAction::ToggleHold)
+        );
+
+        let keymap: Keymap =
+            serde_json::from_str(r#"{"export": "alt+e", "toggle_hold": "Alt+E"}"#).unwrap();
+        assert!(matches!(
+            keymap.validate(),
+            Err(KeymapError::Conflict { .. })
+        ));
+    }
+}
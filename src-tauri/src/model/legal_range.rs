@@ -0,0 +1,190 @@
+use crate::model::color_statistics;
+use crate::model::frame::Frame;
+use crate::model::graph_plotter::VECTOR_SCOPE_CHROMA_SCALE;
+use crate::model::yuv_video::{YuvMatrix, YuvRange};
+use serde::{Deserialize, Serialize};
+
+/// Video range luma (16 - 235) and chroma (16 - 240) excursions on the 0 - 255 scale
+const VIDEO_LUMA_EXCURSION: f32 = 219.0;
+const VIDEO_CHROMA_EXCURSION: f32 = 224.0;
+const VIDEO_BLACK: f32 = 16.0;
+
+/// Limits in video levels on the same scales as the scopes:
+/// luminance on the 0 - 255 waveform scale,
+/// BT.709 Cb / Cr distance on the vector scope scale (0.5 on the outer circle at 100).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LegalRangeThresholds {
+    pub luminance_min: f32,
+    pub luminance_max: f32,
+    pub saturation_max: f32,
+    /// share of illegal pixels (0.0 - 100.0) tolerated before raising the alarm
+    pub tolerance_percent: f32,
+    /// levels of the analyzed RGB, full range (screen captures) is mapped to video range first
+    pub signal_range: YuvRange,
+}
+
+impl Default for LegalRangeThresholds {
+    fn default() -> Self {
+        Self {
+            luminance_min: 16.0,
+            luminance_max: 235.0,
+            saturation_max: 75.0,
+            tolerance_percent: 1.0,
+            signal_range: YuvRange::Full,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PixelLegality {
+    Legal,
+    BelowLuminance,
+    AboveLuminance,
+    OverSaturation,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct LegalRangeResult {
+    pub below_luminance_percent: f32,
+    pub above_luminance_percent: f32,
+    pub over_saturation_percent: f32,
+    pub is_exceeded: bool,
+}
+
+/// Luminance of the normalized RGB is checked first, a pixel is reported once.
+/// Chroma is measured with BT.709, as screen content is HD.
+#[inline(always)]
+pub fn classify_pixel(rgb: [f32; 3], thresholds: &LegalRangeThresholds) -> PixelLegality {
+    let [_, cb, cr] = YuvMatrix::Bt709.to_ycbcr(rgb);
+    let mut luminance = color_statistics::luminance(rgb);
+    let mut chroma = cb.hypot(cr) * VECTOR_SCOPE_CHROMA_SCALE;
+    if thresholds.signal_range == YuvRange::Full {
+        luminance = VIDEO_BLACK + luminance * VIDEO_LUMA_EXCURSION / 255.0;
+        chroma *= VIDEO_CHROMA_EXCURSION / 255.0;
+    }
+    if luminance < thresholds.luminance_min {
+        PixelLegality::BelowLuminance
+    } else if luminance > thresholds.luminance_max {
+        PixelLegality::AboveLuminance
+    } else if chroma > thresholds.saturation_max {
+        PixelLegality::OverSaturation
+    } else {
+        PixelLegality::Legal
+    }
+}
+
+pub fn check(image: &Frame, thresholds: &LegalRangeThresholds) -> LegalRangeResult {
+    let mut below_luminance: usize = 0;
+    let mut above_luminance: usize = 0;
+    let mut over_saturation: usize = 0;
+    let mut pixel_count: usize = 0;
+    for rgb in image.analyzed_pixels() {
+        match classify_pixel(rgb, thresholds) {
+            PixelLegality::Legal => {}
+            PixelLegality::BelowLuminance => below_luminance += 1,
+            PixelLegality::AboveLuminance => above_luminance += 1,
+            PixelLegality::OverSaturation => over_saturation += 1,
+        }
+        pixel_count += 1;
+    }
+    if pixel_count == 0 {
+        return LegalRangeResult::default();
+    }
+
+    let to_percent = |count: usize| count as f32 * 100.0 / pixel_count as f32;
+    let below_luminance_percent = to_percent(below_luminance);
+    let above_luminance_percent = to_percent(above_luminance);
+    let over_saturation_percent = to_percent(over_saturation);
+    LegalRangeResult {
+        below_luminance_percent,
+        above_luminance_percent,
+        over_saturation_percent,
+        is_exceeded: below_luminance_percent > thresholds.tolerance_percent
+            || above_luminance_percent > thresholds.tolerance_percent
+            || over_saturation_percent > thresholds.tolerance_percent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+
+    const FULL: LegalRangeThresholds = LegalRangeThresholds {
+        luminance_min: 16.0,
+        luminance_max: 235.0,
+        saturation_max: 75.0,
+        tolerance_percent: 1.0,
+        signal_range: YuvRange::Full,
+    };
+    const LIMITED: LegalRangeThresholds = LegalRangeThresholds {
+        signal_range: YuvRange::Limited,
+        ..FULL
+    };
+
+    #[test]
+    fn full_range_black_and_white_are_legal() {
+        assert_eq!(classify_pixel([0.0; 3], &FULL), PixelLegality::Legal);
+        assert_eq!(classify_pixel([1.0; 3], &FULL), PixelLegality::Legal);
+        assert_eq!(classify_pixel([0.5; 3], &FULL), PixelLegality::Legal);
+    }
+
+    #[test]
+    fn limited_range_checks_levels_as_they_are() {
+        assert_eq!(
+            classify_pixel([0.0; 3], &LIMITED),
+            PixelLegality::BelowLuminance
+        );
+        assert_eq!(
+            classify_pixel([1.0; 3], &LIMITED),
+            PixelLegality::AboveLuminance
+        );
+        assert_eq!(
+            classify_pixel([16.0 / 255.0; 3], &LIMITED),
+            PixelLegality::Legal
+        );
+        assert_eq!(
+            classify_pixel([235.0 / 255.0; 3], &LIMITED),
+            PixelLegality::Legal
+        );
+    }
+
+    #[test]
+    fn measures_chroma_on_the_vector_scope_scale() {
+        // fully saturated primaries are beyond the limit, half saturated ones within it
+        for primary in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] {
+            assert_eq!(
+                classify_pixel(primary, &FULL),
+                PixelLegality::OverSaturation,
+                "{primary:?}"
+            );
+            let half = primary.map(|value| value * 0.5 + 0.25);
+            assert_eq!(
+                classify_pixel(half, &FULL),
+                PixelLegality::Legal,
+                "{half:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn counts_illegal_pixels() {
+        let samples = [[0, 0, 0], [255, 0, 0], [255, 255, 255], [128, 128, 128]]
+            .iter()
+            .flat_map(|[red, green, blue]| [*red, *green, *blue, 255])
+            .collect();
+        let frame = Frame::new(2, 2, FrameSamples::Rgba8(samples));
+
+        let result = check(&frame, &FULL);
+        assert_eq!(result.over_saturation_percent, 25.0);
+        assert_eq!(result.below_luminance_percent, 0.0);
+        assert!(result.is_exceeded);
+
+        let result = check(&frame, &LIMITED);
+        assert_eq!(result.below_luminance_percent, 25.0);
+        assert_eq!(result.above_luminance_percent, 25.0);
+        assert_eq!(result.over_saturation_percent, 25.0);
+    }
+}
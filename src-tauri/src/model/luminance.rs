@@ -0,0 +1,61 @@
+use crate::model::color_statistics;
+use crate::model::transfer_function::{DecodeTable, TransferFunction};
+use serde::{Deserialize, Serialize};
+
+/// BT.709 coefficients, matching the sRGB working space
+const LINEAR_WEIGHTS: (f32, f32, f32) = (0.2126, 0.7152, 0.0722);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LuminanceMode {
+    /// Y′, weighted sum of the encoded values
+    Luma,
+    /// Y, weighted sum of the values linearized with the transfer function
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LuminanceSettings {
+    pub mode: LuminanceMode,
+    pub transfer_function: TransferFunction,
+}
+
+impl Default for LuminanceSettings {
+    fn default() -> Self {
+        Self {
+            mode: LuminanceMode::Luma,
+            transfer_function: TransferFunction::Srgb,
+        }
+    }
+}
+
+/// Luminance of a pixel on the 0 - 255 waveform scale
+pub struct LuminanceMeter {
+    /// `None` for luma
+    linear_table: Option<DecodeTable>,
+}
+
+impl LuminanceMeter {
+    pub fn new(settings: &LuminanceSettings) -> Self {
+        Self {
+            linear_table: match settings.mode {
+                LuminanceMode::Luma => None,
+                LuminanceMode::Linear => Some(settings.transfer_function.decode_table()),
+            },
+        }
+    }
+
+    /// Takes normalized RGB
+    #[inline(always)]
+    pub fn measure(&self, rgb: [f32; 3]) -> f32 {
+        match &self.linear_table {
+            None => color_statistics::luminance(rgb),
+            Some(table) => {
+                (LINEAR_WEIGHTS.0 * table.get(rgb[0])
+                    + LINEAR_WEIGHTS.1 * table.get(rgb[1])
+                    + LINEAR_WEIGHTS.2 * table.get(rgb[2]))
+                    * 255.0
+            }
+        }
+    }
+}
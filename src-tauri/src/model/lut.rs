@@ -0,0 +1,539 @@
+use crate::model::frame::Frame;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const LUT_SIZE_MIN: usize = 2;
+const LUT_1D_SIZE_MAX: usize = 65536;
+const LUT_3D_SIZE_MAX: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LutInterpolation {
+    Trilinear,
+    Tetrahedral,
+}
+
+#[derive(Debug)]
+pub enum LutError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    Invalid(String),
+}
+
+impl std::fmt::Display for LutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LutError::Io(err) => write!(f, "{err}"),
+            LutError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            LutError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for LutError {}
+
+impl From<std::io::Error> for LutError {
+    fn from(err: std::io::Error) -> Self {
+        LutError::Io(err)
+    }
+}
+
+/// Summary of a loaded LUT for the frontend
+#[derive(Clone, Debug, Serialize)]
+pub struct LutInfo {
+    pub title: String,
+    pub size_1d: Option<usize>,
+    pub size_3d: Option<usize>,
+}
+
+/// Adobe / Resolve `.cube` LUT.
+/// When a file has both a 1D and a 3D table, the 1D table is applied first (shaper)
+/// and its output is looked up in the domain of the 3D table.
+#[derive(Clone, Debug)]
+pub struct Lut {
+    title: String,
+    /// input domain of the first table
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// domain of the 3D table, the same as the input domain without a shaper
+    domain_3d_min: [f32; 3],
+    domain_3d_max: [f32; 3],
+    table_1d: Option<Vec<[f32; 3]>>,
+    size_3d: usize,
+    /// red changes fastest, then green, then blue
+    table_3d: Option<Vec<[f32; 3]>>,
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> LutError {
+    LutError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+fn parse_floats<const N: usize>(line_number: usize, values: &[&str]) -> Result<[f32; N], LutError> {
+    if values.len() != N {
+        return Err(parse_error(
+            line_number,
+            format!("expected {N} values but found {}", values.len()),
+        ));
+    }
+    let mut parsed = [0f32; N];
+    for (index, value) in values.iter().enumerate() {
+        parsed[index] = value
+            .parse::<f32>()
+            .map_err(|_| parse_error(line_number, format!("invalid number `{value}`")))?;
+    }
+    Ok(parsed)
+}
+
+fn parse_size(line_number: usize, values: &[&str], max: usize) -> Result<usize, LutError> {
+    let size = match values {
+        [size] => size
+            .parse::<usize>()
+            .map_err(|_| parse_error(line_number, format!("invalid size `{size}`")))?,
+        _ => return Err(parse_error(line_number, "expected a single size")),
+    };
+    if !(LUT_SIZE_MIN..=max).contains(&size) {
+        return Err(parse_error(
+            line_number,
+            format!("size {size} out of range"),
+        ));
+    }
+    Ok(size)
+}
+
+impl Lut {
+    pub fn load(path: &Path) -> Result<Self, LutError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, LutError> {
+        let mut title = String::new();
+        let mut domain_min = [0f32; 3];
+        let mut domain_max = [1f32; 3];
+        let mut range_1d: Option<[f32; 2]> = None;
+        let mut range_3d: Option<[f32; 2]> = None;
+        let mut size_1d: Option<usize> = None;
+        let mut size_3d: Option<usize> = None;
+        let mut entries: Vec<[f32; 3]> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let keyword = tokens.next().unwrap_or_default();
+            let values: Vec<&str> = tokens.collect();
+            match keyword {
+                "TITLE" => {
+                    title = line["TITLE".len()..].trim().trim_matches('"').to_string();
+                }
+                "LUT_1D_SIZE" => size_1d = Some(parse_size(line_number, &values, LUT_1D_SIZE_MAX)?),
+                "LUT_3D_SIZE" => size_3d = Some(parse_size(line_number, &values, LUT_3D_SIZE_MAX)?),
+                "DOMAIN_MIN" => domain_min = parse_floats::<3>(line_number, &values)?,
+                "DOMAIN_MAX" => domain_max = parse_floats::<3>(line_number, &values)?,
+                // Resolve style input range of each table, same for every channel
+                "LUT_1D_INPUT_RANGE" => range_1d = Some(parse_floats::<2>(line_number, &values)?),
+                "LUT_3D_INPUT_RANGE" => range_3d = Some(parse_floats::<2>(line_number, &values)?),
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    let mut all_values = vec![keyword];
+                    all_values.extend(values);
+                    entries.push(parse_floats::<3>(line_number, &all_values)?);
+                }
+                // unknown keywords are allowed by the specification
+                _ => {}
+            }
+        }
+
+        if size_1d.is_none() && size_3d.is_none() {
+            return Err(LutError::Invalid(
+                "LUT_1D_SIZE or LUT_3D_SIZE is required".to_string(),
+            ));
+        }
+        let (domain_3d_min, domain_3d_max) = match range_3d {
+            Some([min, max]) => ([min; 3], [max; 3]),
+            None => (domain_min, domain_max),
+        };
+        let (domain_min, domain_max) = match (size_1d, range_1d) {
+            (Some(_), Some([min, max])) => ([min; 3], [max; 3]),
+            (Some(_), None) => (domain_min, domain_max),
+            (None, _) => (domain_3d_min, domain_3d_max),
+        };
+        if (0..3).any(|channel| {
+            domain_max[channel] <= domain_min[channel]
+                || domain_3d_max[channel] <= domain_3d_min[channel]
+        }) {
+            return Err(LutError::Invalid(
+                "DOMAIN_MAX must be greater than DOMAIN_MIN".to_string(),
+            ));
+        }
+        let expected = size_1d.unwrap_or(0) + size_3d.map_or(0, |size| size * size * size);
+        if entries.len() != expected {
+            return Err(LutError::Invalid(format!(
+                "expected {expected} entries but found {}",
+                entries.len()
+            )));
+        }
+
+        let table_3d = size_3d.map(|_| entries.split_off(size_1d.unwrap_or(0)));
+        let table_1d = size_1d.map(|_| entries);
+        Ok(Self {
+            title,
+            domain_min,
+            domain_max,
+            domain_3d_min,
+            domain_3d_max,
+            table_1d,
+            size_3d: size_3d.unwrap_or(0),
+            table_3d,
+        })
+    }
+
+    pub fn info(&self) -> LutInfo {
+        LutInfo {
+            title: self.title.clone(),
+            size_1d: self.table_1d.as_ref().map(|table| table.len()),
+            size_3d: self.table_3d.as_ref().map(|_| self.size_3d),
+        }
+    }
+
+    /// Applies the LUT to a color in the input domain.
+    pub fn apply(&self, rgb: [f32; 3], interpolation: LutInterpolation) -> [f32; 3] {
+        let mut color = normalize(rgb, self.domain_min, self.domain_max);
+
+        if let Some(table) = &self.table_1d {
+            color = apply_1d(table, color);
+            if self.table_3d.is_some() {
+                // the shaper output is a value in the domain of the 3D table
+                color = normalize(color, self.domain_3d_min, self.domain_3d_max);
+            }
+        }
+        if let Some(table) = &self.table_3d {
+            color = match interpolation {
+                LutInterpolation::Trilinear => apply_3d_trilinear(table, self.size_3d, color),
+                LutInterpolation::Tetrahedral => apply_3d_tetrahedral(table, self.size_3d, color),
+            };
+        }
+        color
+    }
+
+    /// Applies the LUT to every pixel of a frame, whose samples are values in the
+    /// input domain. Alpha is kept.
+    pub fn apply_to_frame(&self, frame: &Frame, interpolation: LutInterpolation) -> Frame {
+        frame.map_rgb(|rgb| self.apply(rgb, interpolation))
+    }
+}
+
+/// Position of `rgb` in 0.0 - 1.0 of the domain, clamped to the table
+fn normalize(rgb: [f32; 3], min: [f32; 3], max: [f32; 3]) -> [f32; 3] {
+    let mut color = [0f32; 3];
+    for channel in 0..3 {
+        color[channel] =
+            ((rgb[channel] - min[channel]) / (max[channel] - min[channel])).clamp(0.0, 1.0);
+    }
+    color
+}
+
+fn apply_1d(table: &[[f32; 3]], color: [f32; 3]) -> [f32; 3] {
+    let last = (table.len() - 1) as f32;
+    let mut output = [0f32; 3];
+    for channel in 0..3 {
+        let position = color[channel] * last;
+        let lower = (position.floor() as usize).min(table.len() - 2);
+        let fraction = position - lower as f32;
+        output[channel] =
+            table[lower][channel] + (table[lower + 1][channel] - table[lower][channel]) * fraction;
+    }
+    output
+}
+
+#[inline(always)]
+fn lattice(table: &[[f32; 3]], size: usize, red: usize, green: usize, blue: usize) -> [f32; 3] {
+    table[red + green * size + blue * size * size]
+}
+
+#[inline(always)]
+fn lerp(a: [f32; 3], b: [f32; 3], fraction: f32) -> [f32; 3] {
+    [
+        a[0] + (b[0] - a[0]) * fraction,
+        a[1] + (b[1] - a[1]) * fraction,
+        a[2] + (b[2] - a[2]) * fraction,
+    ]
+}
+
+/// Lower lattice index and fraction of each channel
+#[inline(always)]
+fn locate(size: usize, color: [f32; 3]) -> ([usize; 3], [f32; 3]) {
+    let last = (size - 1) as f32;
+    let mut index = [0usize; 3];
+    let mut fraction = [0f32; 3];
+    for channel in 0..3 {
+        let position = color[channel] * last;
+        index[channel] = (position.floor() as usize).min(size - 2);
+        fraction[channel] = position - index[channel] as f32;
+    }
+    (index, fraction)
+}
+
+fn apply_3d_trilinear(table: &[[f32; 3]], size: usize, color: [f32; 3]) -> [f32; 3] {
+    let ([r, g, b], [fr, fg, fb]) = locate(size, color);
+    let c000 = lattice(table, size, r, g, b);
+    let c100 = lattice(table, size, r + 1, g, b);
+    let c010 = lattice(table, size, r, g + 1, b);
+    let c110 = lattice(table, size, r + 1, g + 1, b);
+    let c001 = lattice(table, size, r, g, b + 1);
+    let c101 = lattice(table, size, r + 1, g, b + 1);
+    let c011 = lattice(table, size, r, g + 1, b + 1);
+    let c111 = lattice(table, size, r + 1, g + 1, b + 1);
+
+    let c00 = lerp(c000, c100, fr);
+    let c10 = lerp(c010, c110, fr);
+    let c01 = lerp(c001, c101, fr);
+    let c11 = lerp(c011, c111, fr);
+    let c0 = lerp(c00, c10, fg);
+    let c1 = lerp(c01, c11, fg);
+    lerp(c0, c1, fb)
+}
+
+fn apply_3d_tetrahedral(table: &[[f32; 3]], size: usize, color: [f32; 3]) -> [f32; 3] {
+    let ([r, g, b], [fr, fg, fb]) = locate(size, color);
+    let c000 = lattice(table, size, r, g, b);
+    let c111 = lattice(table, size, r + 1, g + 1, b + 1);
+
+    // pick the tetrahedron containing the point and walk its edges
+    let (first, second, weights) = if fr > fg {
+        if fg > fb {
+            (
+                lattice(table, size, r + 1, g, b),
+                lattice(table, size, r + 1, g + 1, b),
+                [fr, fg, fb],
+            )
+        } else if fr > fb {
+            (
+                lattice(table, size, r + 1, g, b),
+                lattice(table, size, r + 1, g, b + 1),
+                [fr, fb, fg],
+            )
+        } else {
+            (
+                lattice(table, size, r, g, b + 1),
+                lattice(table, size, r + 1, g, b + 1),
+                [fb, fr, fg],
+            )
+        }
+    } else if fb > fg {
+        (
+            lattice(table, size, r, g, b + 1),
+            lattice(table, size, r, g + 1, b + 1),
+            [fb, fg, fr],
+        )
+    } else if fb > fr {
+        (
+            lattice(table, size, r, g + 1, b),
+            lattice(table, size, r, g + 1, b + 1),
+            [fg, fb, fr],
+        )
+    } else {
+        (
+            lattice(table, size, r, g + 1, b),
+            lattice(table, size, r + 1, g + 1, b),
+            [fg, fr, fb],
+        )
+    };
+
+    let mut output = [0f32; 3];
+    for channel in 0..3 {
+        output[channel] = c000[channel]
+            + (first[channel] - c000[channel]) * weights[0]
+            + (second[channel] - first[channel]) * weights[1]
+            + (c111[channel] - second[channel]) * weights[2];
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::frame::FrameSamples;
+
+    const TOLERANCE: f32 = 1e-5;
+
+    fn assert_color_eq(actual: [f32; 3], expected: [f32; 3]) {
+        for channel in 0..3 {
+            assert!(
+                (actual[channel] - expected[channel]).abs() < TOLERANCE,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    /// 3D table whose entries are `transform` of the lattice points in 0.0 - `scale`
+    fn cube_3d(size: usize, scale: f32, transform: impl Fn([f32; 3]) -> [f32; 3]) -> String {
+        let last = (size - 1) as f32;
+        let mut text = format!("LUT_3D_SIZE {size}\n");
+        for blue in 0..size {
+            for green in 0..size {
+                for red in 0..size {
+                    let lattice = [red, green, blue].map(|index| index as f32 / last * scale);
+                    let [r, g, b] = transform(lattice);
+                    text += &format!("{r} {g} {b}\n");
+                }
+            }
+        }
+        text
+    }
+
+    fn parse_error_line(text: &str) -> usize {
+        match Lut::parse(text) {
+            Err(LutError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_1d() {
+        let lut = Lut::parse("TITLE \"half\"\nLUT_1D_SIZE 2\n0 0 0\n0.5 0.5 0.5\n").unwrap();
+        assert_eq!(lut.info().title, "half");
+        assert_eq!(lut.info().size_1d, Some(2));
+        assert_eq!(lut.info().size_3d, None);
+        assert_color_eq(
+            lut.apply([1.0, 0.5, 0.0], LutInterpolation::Tetrahedral),
+            [0.5, 0.25, 0.0],
+        );
+    }
+
+    #[test]
+    fn parses_3d_with_comments() {
+        let text = "# generated\n\n".to_string() + &cube_3d(3, 1.0, |rgb| rgb);
+        let lut = Lut::parse(&text).unwrap();
+        assert_eq!(lut.info().size_1d, None);
+        assert_eq!(lut.info().size_3d, Some(3));
+        // red changes fastest
+        assert_eq!(lut.table_3d.as_ref().unwrap()[1], [0.5, 0.0, 0.0]);
+        assert_eq!(lut.table_3d.as_ref().unwrap()[3], [0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn maps_domain_min_max() {
+        let text = "DOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n".to_string() + &cube_3d(2, 2.0, |rgb| rgb);
+        let lut = Lut::parse(&text).unwrap();
+        assert_color_eq(
+            lut.apply([1.0, 0.5, 2.0], LutInterpolation::Trilinear),
+            [1.0, 0.5, 2.0],
+        );
+        // outside of the domain is clamped
+        assert_color_eq(
+            lut.apply([3.0, -1.0, 1.0], LutInterpolation::Trilinear),
+            [2.0, 0.0, 1.0],
+        );
+    }
+
+    #[test]
+    fn looks_up_frame_samples_in_the_domain() {
+        let text = "DOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n".to_string() + &cube_3d(2, 2.0, |rgb| rgb);
+        let lut = Lut::parse(&text).unwrap();
+        let frame = Frame::new(
+            2,
+            1,
+            FrameSamples::Rgba32F(vec![0.5, 0.5, 0.5, 1.0, 1.0, 0.25, 1.5, 0.5]),
+        );
+        let output = lut.apply_to_frame(&frame, LutInterpolation::Tetrahedral);
+        assert_color_eq(output.rgb(0), [0.5, 0.5, 0.5]);
+        assert_color_eq(output.rgb(1), [1.0, 0.25, 1.5]);
+    }
+
+    #[test]
+    fn rejects_bad_sizes() {
+        assert_eq!(parse_error_line("LUT_3D_SIZE 1\n"), 1);
+        assert_eq!(parse_error_line("LUT_3D_SIZE 257\n"), 1);
+        assert_eq!(parse_error_line("# size\nLUT_1D_SIZE abc\n"), 2);
+        assert_eq!(parse_error_line("LUT_3D_SIZE 2 2\n"), 1);
+        assert!(matches!(
+            Lut::parse("TITLE \"no size\"\n"),
+            Err(LutError::Invalid(_))
+        ));
+        // entry count does not match the size
+        assert!(matches!(
+            Lut::parse("LUT_3D_SIZE 2\n0 0 0\n1 1 1\n"),
+            Err(LutError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert_eq!(parse_error_line("LUT_1D_SIZE 2\n0 0 0\n1 x 1\n"), 3);
+        assert_eq!(parse_error_line("LUT_1D_SIZE 2\n0 0\n1 1 1\n"), 2);
+        assert_eq!(parse_error_line("DOMAIN_MIN 0 0\n"), 1);
+        assert!(matches!(
+            Lut::parse("DOMAIN_MIN 1 1 1\nDOMAIN_MAX 1 1 1\nLUT_1D_SIZE 2\n0 0 0\n1 1 1\n"),
+            Err(LutError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn identity_keeps_colors() {
+        for size in [2, 17] {
+            let lut = Lut::parse(&cube_3d(size, 1.0, |rgb| rgb)).unwrap();
+            for color in [
+                [0.0, 0.0, 0.0],
+                [1.0, 1.0, 1.0],
+                [0.3, 0.7, 0.1],
+                [0.9, 0.2, 0.55],
+            ] {
+                for interpolation in [LutInterpolation::Trilinear, LutInterpolation::Tetrahedral] {
+                    assert_color_eq(lut.apply(color, interpolation), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interpolates_linear_tables_exactly() {
+        // both interpolations reproduce a function that is linear in every channel
+        let transform = |[r, g, b]: [f32; 3]| [1.0 - r, b, 0.5 * r + 0.25 * g];
+        let lut = Lut::parse(&cube_3d(5, 1.0, transform)).unwrap();
+        for color in [[0.3, 0.7, 0.1], [0.9, 0.2, 0.55], [0.05, 0.95, 0.5]] {
+            for interpolation in [LutInterpolation::Trilinear, LutInterpolation::Tetrahedral] {
+                assert_color_eq(lut.apply(color, interpolation), transform(color));
+            }
+        }
+    }
+
+    #[test]
+    fn interpolations_differ_on_a_corner_table() {
+        // only the white corner is lit: trilinear gives the product of the channels,
+        // tetrahedral the smallest channel
+        let lut = Lut::parse(&cube_3d(2, 1.0, |rgb| match rgb {
+            [1.0, 1.0, 1.0] => [1.0; 3],
+            _ => [0.0; 3],
+        }))
+        .unwrap();
+        let color = [0.5, 0.8, 0.25];
+        assert_color_eq(
+            lut.apply(color, LutInterpolation::Trilinear),
+            [0.1, 0.1, 0.1],
+        );
+        assert_color_eq(
+            lut.apply(color, LutInterpolation::Tetrahedral),
+            [0.25, 0.25, 0.25],
+        );
+    }
+
+    #[test]
+    fn maps_shaper_output_into_3d_domain() {
+        // the shaper doubles the input, the 3D table is the identity over 0.0 - 2.0
+        let text = "LUT_1D_SIZE 2\nLUT_3D_INPUT_RANGE 0 2\n0 0 0\n2 2 2\n".to_string()
+            + &cube_3d(2, 2.0, |rgb| rgb).replace("LUT_3D_SIZE 2\n", "")
+            + "LUT_3D_SIZE 2\n";
+        let lut = Lut::parse(&text).unwrap();
+        assert_eq!(lut.info().size_1d, Some(2));
+        assert_eq!(lut.info().size_3d, Some(2));
+        assert_color_eq(
+            lut.apply([0.25, 0.5, 1.0], LutInterpolation::Tetrahedral),
+            [0.5, 1.0, 2.0],
+        );
+    }
+}
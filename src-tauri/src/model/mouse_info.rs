@@ -1,12 +1,18 @@
+#[cfg(target_os = "macos")]
+use crate::model::display_layout::{CoordinateSpace, DisplayLayout};
 use mouse_position::mouse_position::Mouse;
 
+/// Cursor position in physical pixels of the desktop
 #[allow(dead_code)]
 pub fn get_mouse_position() -> (i32, i32) {
     let position = Mouse::get_mouse_position();
     match position {
         Mouse::Position { x, y } => {
-            #[cfg(debug_assertions)]
-            println!("x: {}, y: {}", x, y);
+            // macOS reports points
+            #[cfg(target_os = "macos")]
+            let (x, y) = DisplayLayout::current()
+                .to_physical((x as f64, y as f64), CoordinateSpace::Logical)
+                .map_or((x, y), |(x, y)| (x.round() as i32, y.round() as i32));
             return (x, y);
         }
         Mouse::Error => {
@@ -0,0 +1,41 @@
+use image::RgbImage;
+use std::io::Cursor;
+
+/// Accumulates rendered scope images so that traces persist over time.
+pub struct PeakHold {
+    accumulated: Option<RgbImage>,
+}
+
+impl PeakHold {
+    pub fn new() -> Self {
+        Self { accumulated: None }
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulated = None;
+    }
+
+    /// Merges the PNG `graph` into the accumulated image (per channel maximum)
+    /// and returns the merged image as PNG.
+    /// The accumulation restarts when the graph size changes.
+    pub fn accumulate(&mut self, graph: &[u8]) -> Result<Vec<u8>, image::ImageError> {
+        let graph = image::load_from_memory_with_format(graph, image::ImageFormat::Png)?.to_rgb8();
+        let accumulated = match self.accumulated.take() {
+            Some(mut accumulated) if accumulated.dimensions() == graph.dimensions() => {
+                for (accumulated_value, value) in accumulated.iter_mut().zip(graph.iter()) {
+                    *accumulated_value = (*accumulated_value).max(*value);
+                }
+                accumulated
+            }
+            _ => graph,
+        };
+
+        let mut graph_as_image: Vec<u8> = Vec::new();
+        accumulated.write_to(
+            &mut Cursor::new(&mut graph_as_image),
+            image::ImageOutputFormat::Png,
+        )?;
+        self.accumulated = Some(accumulated);
+        Ok(graph_as_image)
+    }
+}
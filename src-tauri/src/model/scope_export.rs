@@ -0,0 +1,109 @@
+use crate::model::color_statistics::ColorStatistics;
+use crate::model::frame::{Frame, FrameSamples};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const EXPORT_FOLDER_PREFIX: &str = "VectorScope_";
+const EXPORT_FOLDER_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
+const FILE_NAME_CAPTURE: &str = "capture";
+const FILE_NAME_STATISTICS: &str = "statistics.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Png,
+    Tiff,
+    Jpeg,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            ExportFormat::Tiff => "tif",
+            ExportFormat::Jpeg => "jpg",
+        }
+    }
+
+    fn image_format(&self) -> image::ImageFormat {
+        match self {
+            ExportFormat::Png => image::ImageFormat::Png,
+            ExportFormat::Tiff => image::ImageFormat::Tiff,
+            ExportFormat::Jpeg => image::ImageFormat::Jpeg,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StatisticsReport<'a> {
+    timestamp: String,
+    statistics: &'a ColorStatistics,
+}
+
+/// Writes the capture, the scope renders (`(name, PNG)`) and the statistics
+/// into a new timestamped folder under `directory` and returns the folder.
+pub fn export(
+    directory: &Path,
+    capture: &Frame,
+    renders: &[(&str, Vec<u8>)],
+    statistics: &ColorStatistics,
+    formats: &[ExportFormat],
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let now = chrono::Local::now();
+    let folder = directory.join(
+        EXPORT_FOLDER_PREFIX.to_string() + &now.format(EXPORT_FOLDER_TIME_FORMAT).to_string(),
+    );
+    std::fs::create_dir_all(&folder)?;
+
+    // 16-bit and float frames are written with 16 bits (8 bits for JPEG)
+    let capture_image = match capture.samples() {
+        FrameSamples::Rgba8(samples) => DynamicImage::ImageRgba8(
+            image::RgbaImage::from_raw(capture.width(), capture.height(), samples.clone())
+                .ok_or("capture buffer size mismatch")?,
+        ),
+        _ => DynamicImage::ImageRgba16(
+            image::ImageBuffer::from_raw(
+                capture.width(),
+                capture.height(),
+                capture.to_rgba16().into_owned(),
+            )
+            .ok_or("capture buffer size mismatch")?,
+        ),
+    };
+    save_all_formats(&capture_image, &folder, FILE_NAME_CAPTURE, formats)?;
+
+    for (name, render) in renders {
+        let render_image = image::load_from_memory_with_format(render, image::ImageFormat::Png)?;
+        save_all_formats(&render_image, &folder, name, formats)?;
+    }
+
+    let report = StatisticsReport {
+        timestamp: now.to_rfc3339(),
+        statistics,
+    };
+    std::fs::write(
+        folder.join(FILE_NAME_STATISTICS),
+        serde_json::to_string_pretty(&report)?,
+    )?;
+
+    Ok(folder)
+}
+
+fn save_all_formats(
+    image: &DynamicImage,
+    folder: &Path,
+    name: &str,
+    formats: &[ExportFormat],
+) -> Result<(), image::ImageError> {
+    for format in formats {
+        let path = folder.join(name).with_extension(format.extension());
+        match format {
+            // JPEG has no alpha channel
+            ExportFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+                .save_with_format(path, format.image_format())?,
+            _ => image.save_with_format(path, format.image_format())?,
+        }
+    }
+    Ok(())
+}
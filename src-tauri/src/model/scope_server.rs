@@ -0,0 +1,440 @@
+use crate::model::color_statistics::ColorStatistics;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+pub const DEFAULT_PORT: u16 = 8270;
+const WEBSOCKET_PATH: &str = "/ws";
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+/// a client that does not take an update within this time is dropped
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// updates waiting for a client, it is dropped when it falls further behind
+const CLIENT_QUEUE_LENGTH: usize = 4;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_LINE_LENGTH_MAX: usize = 1024;
+/// any routable address, only used to find the LAN interface (nothing is sent)
+const LAN_PROBE_ADDRESS: (Ipv4Addr, u16) = (Ipv4Addr::new(192, 168, 0, 1), 80);
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Vector Scope</title>
+<style>
+body { margin: 0; background: #101010; color: #c0c0c0; font-family: sans-serif; }
+main { display: flex; flex-wrap: wrap; gap: 8px; padding: 8px; align-items: flex-start; }
+img { max-width: 100%; image-rendering: pixelated; }
+#waveform { transform: scaleY(-1); }
+pre { margin: 8px; font-size: 12px; }
+</style>
+</head>
+<body>
+<main>
+<img id="vector_scope" alt="">
+<img id="waveform" alt="">
+</main>
+<pre id="statistics"></pre>
+<script>
+function connect() {
+  let socket = new WebSocket(`ws://${location.host}/ws`);
+  socket.onmessage = (event) => {
+    let update = JSON.parse(event.data);
+    for (let name of ["vector_scope", "waveform"]) {
+      let image = document.getElementById(name);
+      image.hidden = !update[name];
+      if (update[name]) image.src = `/${name}.png?v=${update.version}`;
+    }
+    document.getElementById("statistics").textContent =
+      JSON.stringify(update.statistics, null, 2);
+  };
+  socket.onclose = () => setTimeout(connect, 1000);
+}
+connect();
+</script>
+</body>
+</html>
+"#;
+
+/// Interfaces the server listens on
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerBinding {
+    Localhost,
+    /// every interface, reachable from other devices
+    Lan,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScopeServerSettings {
+    pub binding: ServerBinding,
+    pub port: u16,
+}
+
+impl Default for ScopeServerSettings {
+    fn default() -> Self {
+        Self {
+            binding: ServerBinding::Localhost,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+/// Renderings of one analyzed frame. Empty images are not served.
+#[derive(Clone, Default)]
+pub struct ScopeSnapshot {
+    pub vector_scope: Vec<u8>,
+    pub waveform: Vec<u8>,
+    pub statistics: ColorStatistics,
+}
+
+/// Pushed to WebSocket clients after every frame; the images are fetched with `version`
+#[derive(Serialize)]
+struct UpdateMessage<'a> {
+    version: u64,
+    vector_scope: bool,
+    waveform: bool,
+    statistics: &'a ColorStatistics,
+}
+
+struct ServerState {
+    keep_alive: AtomicBool,
+    snapshot: RwLock<(u64, ScopeSnapshot)>,
+    /// update queues of the WebSocket clients, each written by its connection thread
+    clients: Mutex<Vec<SyncSender<String>>>,
+}
+
+impl ServerState {
+    fn update_message(&self) -> String {
+        let snapshot = self.snapshot.read().unwrap();
+        let (version, snapshot) = &*snapshot;
+        serde_json::to_string(&UpdateMessage {
+            version: *version,
+            vector_scope: !snapshot.vector_scope.is_empty(),
+            waveform: !snapshot.waveform.is_empty(),
+            statistics: &snapshot.statistics,
+        })
+        .expect("Failed to serialize scope update")
+    }
+}
+
+/// Embedded HTTP server for the latest scope images and statistics.
+/// `GET /` is a viewer page, `/vector_scope.png`, `/waveform.png` and `/statistics.json`
+/// return the latest frame, and `/ws` notifies WebSocket clients of every new frame.
+pub struct ScopeServer {
+    address: SocketAddr,
+    binding: ServerBinding,
+    state: Arc<ServerState>,
+    accept_thread: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl ScopeServer {
+    pub fn start(settings: &ScopeServerSettings) -> std::io::Result<Self> {
+        let ip = match settings.binding {
+            ServerBinding::Localhost => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            ServerBinding::Lan => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        };
+        let listener = TcpListener::bind((ip, settings.port))?;
+        // polled so that `stop` is noticed
+        listener.set_nonblocking(true)?;
+        let state = Arc::new(ServerState {
+            keep_alive: AtomicBool::new(true),
+            snapshot: RwLock::new((0, ScopeSnapshot::default())),
+            clients: Mutex::new(Vec::new()),
+        });
+        Ok(Self {
+            address: listener.local_addr()?,
+            binding: settings.binding,
+            state: Arc::clone(&state),
+            accept_thread: Mutex::new(Some(thread::spawn(move || accept_loop(listener, state)))),
+        })
+    }
+
+    /// Address to open on another device
+    pub fn url(&self) -> String {
+        let ip = match self.binding {
+            ServerBinding::Localhost => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            ServerBinding::Lan => lan_address().unwrap_or(self.address.ip()),
+        };
+        format!("http://{}", SocketAddr::new(ip, self.address.port()))
+    }
+
+    /// Replaces the served frame and queues a notification for every WebSocket client.
+    /// Never waits for a client, the ones that fell behind or disconnected are dropped.
+    pub fn publish(&self, snapshot: ScopeSnapshot) {
+        {
+            let mut current = self.state.snapshot.write().unwrap();
+            *current = (current.0 + 1, snapshot);
+        }
+        let message = self.state.update_message();
+        self.state
+            .clients
+            .lock()
+            .unwrap()
+            .retain(|client| client.try_send(message.clone()).is_ok());
+    }
+
+    /// Closes every client and waits until the port is released
+    pub fn stop(&self) {
+        self.state.keep_alive.store(false, Ordering::Relaxed);
+        // the connection threads close their socket once the queue is gone
+        self.state.clients.lock().unwrap().clear();
+        if let Some(accept_thread) = self.accept_thread.lock().unwrap().take() {
+            let _ = accept_thread.join();
+        }
+    }
+}
+
+impl Drop for ScopeServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn accept_loop(listener: TcpListener, state: Arc<ServerState>) {
+    while state.keep_alive.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let state = Arc::clone(&state);
+                thread::spawn(move || {
+                    if let Err(_err) = handle_connection(stream, &state) {
+                        #[cfg(debug_assertions)]
+                        println!("Scope server: {_err}");
+                    }
+                });
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_INTERVAL);
+            }
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                println!("Scope server: {_err}");
+                thread::sleep(ACCEPT_INTERVAL);
+            }
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    state: &ServerState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // some platforms hand out sockets in the listener's non-blocking mode
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+
+    match peek_request_path(&stream)?.as_deref() {
+        Some(WEBSOCKET_PATH) => {
+            let client = tungstenite::accept(stream)?;
+            let (sender, updates) = mpsc::sync_channel(CLIENT_QUEUE_LENGTH);
+            {
+                // locked before the first message so that no update is missed in between
+                let mut clients = state.clients.lock().unwrap();
+                sender.try_send(state.update_message())?;
+                clients.push(sender);
+            }
+            write_updates(client, updates)
+        }
+        path => serve_http(stream, path, state),
+    }
+}
+
+/// Sends queued updates until the client is dropped from `ServerState::clients`
+fn write_updates(
+    mut client: WebSocket<TcpStream>,
+    updates: Receiver<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for message in updates {
+        client.send(Message::Text(message))?;
+    }
+    client.close(None)?;
+    client.flush()?;
+    Ok(())
+}
+
+/// Path of the request line without consuming it, so that the WebSocket handshake
+/// still sees the whole request. `None` when the request line is malformed.
+fn peek_request_path(stream: &TcpStream) -> std::io::Result<Option<String>> {
+    let mut buffer = [0u8; REQUEST_LINE_LENGTH_MAX];
+    let mut length = 0;
+    // the request line may arrive in several packets
+    for _ in 0..10 {
+        length = stream.peek(&mut buffer)?;
+        if length == 0 || buffer[..length].contains(&b'\n') {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let request = String::from_utf8_lossy(&buffer[..length]);
+    let request_line = match request.split_once('\n') {
+        Some((request_line, _)) => request_line,
+        None => return Ok(None),
+    };
+    let mut tokens = request_line.split_whitespace();
+    Ok(match (tokens.next(), tokens.next()) {
+        (Some("GET"), Some(target)) => Some(
+            target
+                .split_once('?')
+                .map_or(target, |(path, _)| path)
+                .to_string(),
+        ),
+        _ => None,
+    })
+}
+
+fn serve_http(
+    mut stream: TcpStream,
+    path: Option<&str>,
+    state: &ServerState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // the headers are not needed, but are read so that the client sees the response
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 && line != "\r\n" && line != "\n" {
+        line.clear();
+    }
+
+    // copied out so that a slow client does not block `publish`
+    let (status, content_type, body): (&str, &str, Vec<u8>) = {
+        let snapshot = state.snapshot.read().unwrap();
+        let (_, snapshot) = &*snapshot;
+        match path {
+            Some("/") => ("200 OK", "text/html; charset=utf-8", INDEX_HTML.into()),
+            Some("/vector_scope.png") if !snapshot.vector_scope.is_empty() => {
+                ("200 OK", "image/png", snapshot.vector_scope.clone())
+            }
+            Some("/waveform.png") if !snapshot.waveform.is_empty() => {
+                ("200 OK", "image/png", snapshot.waveform.clone())
+            }
+            Some("/statistics.json") => (
+                "200 OK",
+                "application/json",
+                serde_json::to_vec(&snapshot.statistics)?,
+            ),
+            Some(_) => ("404 Not Found", "text/plain", "Not Found".into()),
+            None => ("400 Bad Request", "text/plain", "Bad Request".into()),
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Address of the interface that reaches the local network
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect(LAN_PROBE_ADDRESS).ok()?;
+    socket.local_addr().ok().map(|address| address.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn start() -> ScopeServer {
+        ScopeServer::start(&ScopeServerSettings {
+            binding: ServerBinding::Localhost,
+            port: 0,
+        })
+        .unwrap()
+    }
+
+    fn get(server: &ScopeServer, path: &str) -> (String, Vec<u8>) {
+        let mut stream = TcpStream::connect(server.address).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .unwrap();
+        let body = response.split_off(header_end + 4);
+        (String::from_utf8(response).unwrap(), body)
+    }
+
+    fn read_update(client: &mut WebSocket<impl Read + Write>) -> serde_json::Value {
+        match client.read().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            message => panic!("unexpected message {message:?}"),
+        }
+    }
+
+    #[test]
+    fn serves_published_snapshot() {
+        let server = start();
+        let (head, _) = get(&server, "/vector_scope.png");
+        assert!(head.starts_with("HTTP/1.1 404 Not Found"), "{head}");
+
+        server.publish(ScopeSnapshot {
+            vector_scope: vec![1, 2, 3],
+            ..Default::default()
+        });
+        let (head, body) = get(&server, "/vector_scope.png?v=1");
+        assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+        assert!(head.contains("Content-Type: image/png"), "{head}");
+        assert_eq!(body, [1, 2, 3]);
+        let (head, _) = get(&server, "/waveform.png");
+        assert!(head.starts_with("HTTP/1.1 404 Not Found"), "{head}");
+        let (head, body) = get(&server, "/");
+        assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+        assert_eq!(body, INDEX_HTML.as_bytes());
+    }
+
+    #[test]
+    fn notifies_websocket_clients() {
+        let server = start();
+        let (mut client, _) =
+            tungstenite::connect(format!("ws://{}{WEBSOCKET_PATH}", server.address)).unwrap();
+        // the current snapshot on connect, the client is registered after it
+        let update = read_update(&mut client);
+        assert_eq!(update["version"], 0);
+        assert_eq!(update["vector_scope"], false);
+
+        server.publish(ScopeSnapshot {
+            vector_scope: vec![1],
+            ..Default::default()
+        });
+        let update = read_update(&mut client);
+        assert_eq!(update["version"], 1);
+        assert_eq!(update["vector_scope"], true);
+        assert_eq!(update["waveform"], false);
+    }
+
+    #[test]
+    fn drops_clients_that_fall_behind() {
+        let server = start();
+        let (sender, updates) = mpsc::sync_channel(CLIENT_QUEUE_LENGTH);
+        server.state.clients.lock().unwrap().push(sender);
+        for _ in 0..CLIENT_QUEUE_LENGTH {
+            server.publish(ScopeSnapshot::default());
+        }
+        assert_eq!(server.state.clients.lock().unwrap().len(), 1);
+        server.publish(ScopeSnapshot::default());
+        assert!(server.state.clients.lock().unwrap().is_empty());
+        assert_eq!(updates.try_iter().count(), CLIENT_QUEUE_LENGTH);
+    }
+
+    #[test]
+    fn drops_disconnected_clients() {
+        let server = start();
+        let (sender, updates) = mpsc::sync_channel(CLIENT_QUEUE_LENGTH);
+        server.state.clients.lock().unwrap().push(sender);
+        drop(updates);
+        server.publish(ScopeSnapshot::default());
+        assert!(server.state.clients.lock().unwrap().is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::model::capture_area::CaptureArea;
+use crate::model::display_layout::{CoordinateSpace, Display, DisplayLayout};
 use display_info::DisplayInfo;
 use screenshots::{Image, Screen};
 
@@ -10,25 +12,161 @@ pub fn capture_entire_sreen() -> Image {
     return image;
 }
 
+/// Top left of the display `capture_entire_sreen` captures, in physical pixels
+pub fn entire_screen_origin() -> (i32, i32) {
+    let display_id = DisplayInfo::from_point(0, 0).unwrap().id;
+    DisplayLayout::current()
+        .display(display_id)
+        .map_or((0, 0), |display| (display.x, display.y))
+}
+
+/// `None` when the display of the area has been disconnected or the capture failed
 #[inline(always)]
-pub fn capture_area(area_top_left: (i32, i32), area_bottom_right: (i32, i32)) -> Image {
-    let display_info = DisplayInfo::from_point(area_top_left.0, area_top_left.1).unwrap();
-    let screen = Screen::new(&display_info);
+pub fn capture_area(area: &CaptureArea) -> Option<Image> {
+    let displays = DisplayInfo::all().ok()?;
+    let display_info = displays
+        .iter()
+        .find(|display| display.id == area.display_id)?;
+    let display = *DisplayLayout::from(displays.as_slice()).display(area.display_id)?;
 
     // for multi screen support
-    // screen.capture_area() requires the axis from target screen's top left
-    // so I need to re-calculate capture area position
-    let display_top_left: (i32, i32) = (display_info.x, display_info.y);
+    // the area is captured relative to the target screen's top left
+    capture_physical_area(
+        display_info,
+        area.top_left.0 - display.x,
+        area.top_left.1 - display.y,
+        area.width(),
+        area.height(),
+    )
+}
 
-    let width: u32 = (area_bottom_right.0 - area_top_left.0).try_into().unwrap();
-    let height: u32 = (area_bottom_right.1 - area_top_left.1).try_into().unwrap();
-    let image = screen
-        .capture_area(
-            area_top_left.0 - display_top_left.0,
-            area_top_left.1 - display_top_left.1,
-            width,
-            height,
+/// `center` in physical pixels, as `mouse_info` reports the cursor.
+/// Returns the capture and the position of `center` in it.
+/// Fails when no display can be listed or captured, e.g. while the session is locked.
+#[inline(always)]
+pub fn capture_around(
+    center: (i32, i32),
+    size: u32,
+) -> Result<(Image, (u32, u32)), Box<dyn std::error::Error>> {
+    let displays = DisplayInfo::all()?;
+    let layout = DisplayLayout::from(displays.as_slice());
+    let display = *layout
+        .display_at(
+            (center.0 as f64, center.1 as f64),
+            CoordinateSpace::Physical,
         )
-        .unwrap();
-    return image;
+        .or_else(|| layout.displays().first())
+        .ok_or("No display found")?;
+    let display_info = displays
+        .iter()
+        .find(|display_info| display_info.id == display.id)
+        .ok_or("No display found")?;
+
+    let ((x, y), size, cursor) = square_around(center, size, &display);
+    let image = capture_physical_area(display_info, x, y, size, size)
+        .ok_or("Failed to capture the display")?;
+    Ok((image, cursor))
+}
+
+/// Top left relative to the display and side of a square of `size` around `center`,
+/// and the position of `center` in the square.
+/// The square is kept inside the display, so `center` is off the middle at the edges.
+fn square_around(
+    center: (i32, i32),
+    size: u32,
+    display: &Display,
+) -> ((i32, i32), u32, (u32, u32)) {
+    let size = size.min(display.width).min(display.height);
+    let half = (size / 2) as i32;
+    let max_x = (display.width - size) as i32;
+    let max_y = (display.height - size) as i32;
+    let x = (center.0 - display.x - half).clamp(0, max_x);
+    let y = (center.1 - display.y - half).clamp(0, max_y);
+    // a cursor off every display is shown at the nearest pixel
+    let last = size as i32 - 1;
+    let cursor = (
+        (center.0 - display.x - x).clamp(0, last) as u32,
+        (center.1 - display.y - y).clamp(0, last) as u32,
+    );
+    ((x, y), size, cursor)
+}
+
+/// Captures physical pixels relative to the top left of the display.
+/// `screenshots` takes whole display units and multiplies them by the scale factor,
+/// so the units around the area are captured and the pixels are cut out of them.
+fn capture_physical_area(
+    display_info: &DisplayInfo,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Option<Image> {
+    let screen = Screen::new(display_info);
+    let scale_factor = match display_info.scale_factor > 0.0 {
+        true => display_info.scale_factor,
+        false => 1.0,
+    };
+    let left = (x as f32 / scale_factor).floor() as i32;
+    let top = (y as f32 / scale_factor).floor() as i32;
+    let right = ((x + width as i32) as f32 / scale_factor).ceil() as i32;
+    let bottom = ((y + height as i32) as f32 / scale_factor).ceil() as i32;
+    let image = screen
+        .capture_area(left, top, (right - left) as u32, (bottom - top) as u32)
+        .ok()?;
+
+    // first pixel of the capture, rounded the same way as `screenshots`
+    let origin_x = (left as f32 * scale_factor) as i32;
+    let origin_y = (top as f32 * scale_factor) as i32;
+    crop(
+        &image,
+        (x - origin_x).max(0) as u32,
+        (y - origin_y).max(0) as u32,
+        width,
+        height,
+    )
+}
+
+/// Part of `image`, cut to its size; `None` when nothing is left
+fn crop(image: &Image, x: u32, y: u32, width: u32, height: u32) -> Option<Image> {
+    let width = width.min(image.width().saturating_sub(x));
+    let height = height.min(image.height().saturating_sub(y));
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let stride = image.width() as usize * 4;
+    let rgba = image
+        .rgba()
+        .chunks_exact(stride)
+        .skip(y as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[x as usize * 4..(x + width) as usize * 4])
+        .copied()
+        .collect();
+    Some(Image::new(width, height, rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_cursor_in_the_square_at_display_edges() {
+        let display = Display::new(2, 1920, 0, 1920, 1080, 1.0);
+        // inside the display the cursor is in the middle
+        assert_eq!(
+            square_around((2880, 540), 64, &display),
+            ((928, 508), 64, (32, 32))
+        );
+        // at the top left corner the square is moved onto the display, not the cursor
+        assert_eq!(square_around((1925, 3), 64, &display), ((0, 0), 64, (5, 3)));
+        assert_eq!(
+            square_around((3839, 1079), 64, &display),
+            ((1856, 1016), 64, (63, 63))
+        );
+        // off the display the nearest pixel is reported
+        assert_eq!(
+            square_around((1900, -10), 64, &display),
+            ((0, 0), 64, (0, 0))
+        );
+    }
 }
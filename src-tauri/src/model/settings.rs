@@ -0,0 +1,36 @@
+use crate::model::keymap::Keymap;
+use crate::model::tray_icon::TrayIconMode;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// User settings kept between launches, in the app config directory
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub keymap: Keymap,
+    pub tray_icon_mode: TrayIconMode,
+}
+
+impl Settings {
+    /// Defaults when the file is missing or can not be read
+    pub fn load(directory: &Path) -> Self {
+        let mut settings = std::fs::read(directory.join(SETTINGS_FILE_NAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Settings>(&bytes).ok())
+            .unwrap_or_default();
+        if let Err(_err) = settings.keymap.validate() {
+            #[cfg(debug_assertions)]
+            println!("Settings: {_err}, using the default shortcuts");
+            settings.keymap = Keymap::default();
+        }
+        settings
+    }
+
+    pub fn save(&self, directory: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(directory)?;
+        let json = serde_json::to_vec_pretty(self)?;
+        std::fs::write(directory.join(SETTINGS_FILE_NAME), json)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::model::color_statistics::ColorStatistics;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const CSV_HEADER: &str = "timestamp,elapsed_seconds,width,height,\
+luminance_min,luminance_max,luminance_mean,saturation_mean,saturation_max,\
+red_mean,green_mean,blue_mean,frame";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    Csv,
+    Jsonl,
+}
+
+impl RecordingFormat {
+    /// Guesses the format from the file extension, CSV unless it is `.jsonl` / `.json`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("jsonl") | Some("json") => RecordingFormat::Jsonl,
+            _ => RecordingFormat::Csv,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StatisticsRecord<'a> {
+    timestamp: String,
+    elapsed_seconds: f64,
+    /// position in the video, `None` for other sources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frame: Option<usize>,
+    #[serde(flatten)]
+    statistics: &'a ColorStatistics,
+}
+
+/// Appends per-frame statistics with timestamps to a CSV or JSONL file.
+pub struct StatisticsRecorder {
+    writer: BufWriter<File>,
+    format: RecordingFormat,
+    started_at: Instant,
+    frame_count: usize,
+}
+
+impl StatisticsRecorder {
+    pub fn new(path: &Path, format: RecordingFormat) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format == RecordingFormat::Csv {
+            writeln!(writer, "{CSV_HEADER}")?;
+        }
+        writer.flush()?;
+        Ok(Self {
+            writer,
+            format,
+            started_at: Instant::now(),
+            frame_count: 0,
+        })
+    }
+
+    /// `frame` is the position of the analyzed frame when the source is a video
+    pub fn append(
+        &mut self,
+        statistics: &ColorStatistics,
+        frame: Option<usize>,
+    ) -> std::io::Result<()> {
+        let record = StatisticsRecord {
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            elapsed_seconds: self.started_at.elapsed().as_secs_f64(),
+            frame,
+            statistics,
+        };
+        match self.format {
+            RecordingFormat::Csv => writeln!(
+                self.writer,
+                "{},{:.3},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{}",
+                record.timestamp,
+                record.elapsed_seconds,
+                statistics.width,
+                statistics.height,
+                statistics.luminance_min,
+                statistics.luminance_max,
+                statistics.luminance_mean,
+                statistics.saturation_mean,
+                statistics.saturation_max,
+                statistics.red_mean,
+                statistics.green_mean,
+                statistics.blue_mean,
+                frame.map(|frame| frame.to_string()).unwrap_or_default(),
+            )?,
+            RecordingFormat::Jsonl => {
+                serde_json::to_writer(&mut self.writer, &record)?;
+                writeln!(self.writer)?;
+            }
+        }
+        // flush every frame so a crash does not lose the recording
+        self.writer.flush()?;
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+}
@@ -0,0 +1,86 @@
+use crate::model::frame::{Frame, FrameSamples};
+use serde::{Deserialize, Serialize};
+
+const PATTERN_WIDTH: u32 = 1920;
+const PATTERN_HEIGHT: u32 = 1080;
+/// EBU 100/0/75/0 bars: white at 100%, the colors at 75%
+const COLOR_BARS: [[f32; 3]; 8] = [
+    [1.0, 1.0, 1.0],
+    [0.75, 0.75, 0.0],
+    [0.0, 0.75, 0.75],
+    [0.0, 0.75, 0.0],
+    [0.75, 0.0, 0.75],
+    [0.75, 0.0, 0.0],
+    [0.0, 0.0, 0.75],
+    [0.0, 0.0, 0.0],
+];
+
+/// Generated frame with known values, to check the scopes without a capture
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestPattern {
+    /// eight vertical bars, white to black
+    ColorBars,
+    /// black on the left to white on the right
+    GrayRamp,
+}
+
+impl TestPattern {
+    /// 1920x1080 frame at 16-bit, so the ramp has no steps
+    pub fn render(self) -> Frame {
+        let mut samples: Vec<u16> =
+            Vec::with_capacity((PATTERN_WIDTH * PATTERN_HEIGHT * 4) as usize);
+        for _ in 0..PATTERN_HEIGHT {
+            for x in 0..PATTERN_WIDTH {
+                let rgb = match self {
+                    TestPattern::ColorBars => {
+                        COLOR_BARS[(x * COLOR_BARS.len() as u32 / PATTERN_WIDTH) as usize]
+                    }
+                    TestPattern::GrayRamp => [x as f32 / (PATTERN_WIDTH - 1) as f32; 3],
+                };
+                samples.extend(rgb.map(|value| (value * u16::MAX as f32).round() as u16));
+                samples.push(u16::MAX);
+            }
+        }
+        Frame::new(PATTERN_WIDTH, PATTERN_HEIGHT, FrameSamples::Rgba16(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_color_bars() {
+        let frame = TestPattern::ColorBars.render();
+        assert_eq!(
+            (frame.width(), frame.height()),
+            (PATTERN_WIDTH, PATTERN_HEIGHT)
+        );
+        let bar_width = PATTERN_WIDTH / COLOR_BARS.len() as u32;
+        for (index, expected) in COLOR_BARS.iter().enumerate() {
+            let x = index as u32 * bar_width + bar_width / 2;
+            // the same on every row
+            for y in [0, PATTERN_HEIGHT - 1] {
+                let rgb = frame.rgb((y * PATTERN_WIDTH + x) as usize);
+                for channel in 0..3 {
+                    assert!(
+                        (rgb[channel] - expected[channel]).abs() < 1e-4,
+                        "bar {index}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn renders_gray_ramp() {
+        let frame = TestPattern::GrayRamp.render();
+        assert_eq!(frame.rgb(0), [0.0; 3]);
+        assert_eq!(frame.rgb((PATTERN_WIDTH - 1) as usize), [1.0; 3]);
+        let row: Vec<f32> = (0..PATTERN_WIDTH as usize)
+            .map(|x| frame.rgb(x)[0])
+            .collect();
+        assert!(row.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
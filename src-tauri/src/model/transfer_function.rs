@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+/// one entry per 16-bit code value
+const DECODE_TABLE_SIZE: usize = 65536;
+/// BT.1886 with a black level of zero
+const GAMMA_BT1886: f32 = 2.4;
+
+// SMPTE ST 2084 (PQ)
+const PQ_M1: f32 = 2610.0 / 16384.0;
+const PQ_M2: f32 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f32 = 3424.0 / 4096.0;
+const PQ_C2: f32 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+// ARIB STD-B67 / BT.2100 (HLG)
+const HLG_A: f32 = 0.178_832_77;
+const HLG_B: f32 = 1.0 - 4.0 * HLG_A;
+const HLG_C: f32 = 0.559_910_7;
+
+/// Relation between encoded values and linear light.
+/// Linear values are relative (0.0 - 1.0): to the display white for sRGB / BT.1886 / gamma,
+/// to 10,000 nits for PQ, and scene light for HLG.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferFunction {
+    Srgb,
+    Bt1886,
+    Pq,
+    Hlg,
+    /// pure power function
+    Gamma(f32),
+}
+
+impl TransferFunction {
+    /// encoded (0.0 - 1.0) to linear light (0.0 - 1.0)
+    pub fn decode(self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+        match self {
+            TransferFunction::Srgb => match value <= 0.04045 {
+                true => value / 12.92,
+                false => ((value + 0.055) / 1.055).powf(2.4),
+            },
+            TransferFunction::Bt1886 => value.powf(GAMMA_BT1886),
+            TransferFunction::Pq => {
+                let power = value.powf(1.0 / PQ_M2);
+                ((power - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * power)).powf(1.0 / PQ_M1)
+            }
+            TransferFunction::Hlg => match value <= 0.5 {
+                true => value * value / 3.0,
+                false => (((value - HLG_C) / HLG_A).exp() + HLG_B) / 12.0,
+            },
+            TransferFunction::Gamma(gamma) => value.powf(gamma),
+        }
+    }
+
+    /// linear light (0.0 - 1.0) to encoded (0.0 - 1.0)
+    pub fn encode(self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+        match self {
+            TransferFunction::Srgb => match value <= 0.0031308 {
+                true => value * 12.92,
+                false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+            },
+            TransferFunction::Bt1886 => value.powf(1.0 / GAMMA_BT1886),
+            TransferFunction::Pq => {
+                let power = value.powf(PQ_M1);
+                ((PQ_C1 + PQ_C2 * power) / (1.0 + PQ_C3 * power)).powf(PQ_M2)
+            }
+            TransferFunction::Hlg => match value <= 1.0 / 12.0 {
+                true => (3.0 * value).sqrt(),
+                false => HLG_A * (12.0 * value - HLG_B).ln() + HLG_C,
+            },
+            TransferFunction::Gamma(gamma) => value.powf(1.0 / gamma),
+        }
+    }
+
+    /// `decode` sampled at every 16-bit code value
+    pub fn decode_table(self) -> DecodeTable {
+        let last = (DECODE_TABLE_SIZE - 1) as f32;
+        DecodeTable(
+            (0..DECODE_TABLE_SIZE)
+                .map(|index| self.decode(index as f32 / last))
+                .collect(),
+        )
+    }
+}
+
+/// Lookup table for decoding every pixel of a frame
+pub struct DecodeTable(Box<[f32]>);
+
+impl DecodeTable {
+    /// Multiplies every entry, e.g. to get nits
+    pub fn scale(mut self, factor: f32) -> Self {
+        for linear in self.0.iter_mut() {
+            *linear *= factor;
+        }
+        self
+    }
+
+    /// Linear light of a normalized encoded value (clamped to 0.0 - 1.0)
+    #[inline(always)]
+    pub fn get(&self, value: f32) -> f32 {
+        self.0[(value.clamp(0.0, 1.0) * (DECODE_TABLE_SIZE - 1) as f32).round() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNCTIONS: [TransferFunction; 5] = [
+        TransferFunction::Srgb,
+        TransferFunction::Bt1886,
+        TransferFunction::Pq,
+        TransferFunction::Hlg,
+        TransferFunction::Gamma(2.2),
+    ];
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for function in FUNCTIONS {
+            for step in 0..=100 {
+                let encoded = step as f32 / 100.0;
+                assert_close(function.encode(function.decode(encoded)), encoded, 1e-4);
+            }
+            assert_eq!(function.decode(0.0), 0.0, "{function:?}");
+            assert_close(function.decode(1.0), 1.0, 1e-5);
+        }
+    }
+
+    #[test]
+    fn decodes_known_values() {
+        // SDR reference white in PQ
+        assert_close(TransferFunction::Pq.decode(0.5081) * 10000.0, 100.0, 0.1);
+        // HDR reference white (BT.2408) is 75% HLG, gray at the 1000 nits system gamma of 1.2
+        assert_close(
+            1000.0 * TransferFunction::Hlg.decode(0.75).powf(1.2),
+            203.0,
+            1.0,
+        );
+        assert_close(TransferFunction::Hlg.decode(0.5), 1.0 / 12.0, 1e-6);
+        assert_close(TransferFunction::Bt1886.decode(0.5), 0.5f32.powf(2.4), 1e-6);
+    }
+
+    #[test]
+    fn srgb_joins_the_linear_segment() {
+        let threshold = 0.04045;
+        let linear = TransferFunction::Srgb.decode(threshold);
+        assert_close(linear, threshold / 12.92, 1e-7);
+        // both segments meet at the threshold
+        assert_close(((threshold + 0.055) / 1.055).powf(2.4), linear, 1e-6);
+        assert_close(TransferFunction::Srgb.encode(0.0031308), threshold, 1e-5);
+        assert_close(
+            TransferFunction::Srgb.decode(0.5),
+            ((0.5 + 0.055) / 1.055f32).powf(2.4),
+            1e-7,
+        );
+    }
+
+    #[test]
+    fn table_matches_decode() {
+        let table = TransferFunction::Pq.decode_table().scale(10000.0);
+        // at code values, where the table is exact
+        for code in (0..DECODE_TABLE_SIZE).step_by(4369) {
+            let encoded = code as f32 / (DECODE_TABLE_SIZE - 1) as f32;
+            let expected = TransferFunction::Pq.decode(encoded) * 10000.0;
+            assert_close(table.get(encoded), expected, expected * 1e-5 + 1e-6);
+        }
+        // clamped to the table
+        assert_eq!(table.get(-1.0), 0.0);
+        assert_close(table.get(2.0), 10000.0, 0.1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::model::color_statistics;
+use crate::model::frame::Frame;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+const TRAY_ICON_PNG: &[u8] = include_bytes!("../../icons/icon.png");
+pub const TRAY_ICON_SIZE: u32 = 64;
+const ALARM_BADGE_COLOR: Rgba<u8> = Rgba([230, 30, 30, 255]);
+const HISTOGRAM_BAR_COLOR: Rgba<u8> = Rgba([220, 220, 220, 255]);
+const HISTOGRAM_BACKGROUND_COLOR: Rgba<u8> = Rgba([40, 40, 40, 255]);
+/// pixels read per frame at most, a small icon does not need more
+const SAMPLE_COUNT_MAX: usize = 65536;
+
+/// What the tray icon shows while frames are analyzed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconMode {
+    /// the app icon, with the alarm badge
+    #[default]
+    Default,
+    AverageColor,
+    /// luma histogram, one bar per 4 code values
+    Histogram,
+}
+
+pub struct TrayIconImage {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<RgbaImage> for TrayIconImage {
+    fn from(image: RgbaImage) -> Self {
+        Self {
+            width: image.width(),
+            height: image.height(),
+            rgba: image.into_raw(),
+        }
+    }
+}
+
+fn base_icon() -> RgbaImage {
+    image::load_from_memory_with_format(TRAY_ICON_PNG, image::ImageFormat::Png)
+        .expect("Failed to decode tray icon")
+        .resize_exact(TRAY_ICON_SIZE, TRAY_ICON_SIZE, FilterType::Triangle)
+        .to_rgba8()
+}
+
+/// Same as the icon in `tauri.conf.json`
+pub fn default_icon() -> TrayIconImage {
+    base_icon().into()
+}
+
+/// Default icon with a red badge on the bottom right
+pub fn alarm_icon() -> TrayIconImage {
+    let mut icon = base_icon();
+    draw_badge(&mut icon, ALARM_BADGE_COLOR);
+    icon.into()
+}
+
+fn draw_badge(icon: &mut RgbaImage, color: Rgba<u8>) {
+    let radius = (icon.width() / 4) as i64;
+    let center = (
+        icon.width() as i64 - radius - 1,
+        icon.height() as i64 - radius - 1,
+    );
+    for (x, y, pixel) in icon.enumerate_pixels_mut() {
+        let dx = x as i64 - center.0;
+        let dy = y as i64 - center.1;
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = color;
+        }
+    }
+}
+
+/// Icon for an analyzed frame, `None` in the default mode
+pub fn render(mode: TrayIconMode, frame: &Frame, is_alarm: bool) -> Option<TrayIconImage> {
+    let mut icon = match mode {
+        TrayIconMode::Default => return None,
+        TrayIconMode::AverageColor => average_color_icon(frame),
+        TrayIconMode::Histogram => histogram_icon(frame),
+    };
+    if is_alarm {
+        draw_badge(&mut icon, ALARM_BADGE_COLOR);
+    }
+    Some(icon.into())
+}
+
+/// Every n-th pixel so that at most `SAMPLE_COUNT_MAX` are read
+fn sampled_pixels(frame: &Frame) -> impl Iterator<Item = [f32; 3]> + '_ {
+    let step = frame.pixel_count().div_ceil(SAMPLE_COUNT_MAX).max(1);
+    frame.analyzed_pixels().step_by(step)
+}
+
+fn average_color_icon(frame: &Frame) -> RgbaImage {
+    let mut sum = [0.0f64; 3];
+    let mut count = 0usize;
+    for rgb in sampled_pixels(frame) {
+        for (total, value) in sum.iter_mut().zip(rgb) {
+            *total += value.clamp(0.0, 1.0) as f64;
+        }
+        count += 1;
+    }
+    let [red, green, blue] = sum.map(|total| (total / count.max(1) as f64 * 255.0).round() as u8);
+    // a circle, like the badge, so that the icon is not a plain square in the tray
+    let mut icon = RgbaImage::new(TRAY_ICON_SIZE, TRAY_ICON_SIZE);
+    let radius = (TRAY_ICON_SIZE / 2) as i64;
+    for (x, y, pixel) in icon.enumerate_pixels_mut() {
+        let dx = x as i64 - radius;
+        let dy = y as i64 - radius;
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = Rgba([red, green, blue, 255]);
+        }
+    }
+    icon
+}
+
+fn histogram_icon(frame: &Frame) -> RgbaImage {
+    let mut bins = [0u32; TRAY_ICON_SIZE as usize];
+    for rgb in sampled_pixels(frame) {
+        let luminance = color_statistics::luminance(rgb).clamp(0.0, 255.0);
+        let bin = (luminance as usize * bins.len() / 256).min(bins.len() - 1);
+        bins[bin] += 1;
+    }
+    // square root so that small populations are still visible next to a large flat area
+    let scale = (*bins.iter().max().unwrap_or(&0) as f32).sqrt().max(1.0);
+    let mut icon =
+        RgbaImage::from_pixel(TRAY_ICON_SIZE, TRAY_ICON_SIZE, HISTOGRAM_BACKGROUND_COLOR);
+    for (x, count) in bins.iter().enumerate() {
+        let height = ((*count as f32).sqrt() / scale * TRAY_ICON_SIZE as f32).round() as u32;
+        for y in TRAY_ICON_SIZE - height..TRAY_ICON_SIZE {
+            icon.put_pixel(x as u32, y, HISTOGRAM_BAR_COLOR);
+        }
+    }
+    icon
+}
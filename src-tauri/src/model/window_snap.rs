@@ -0,0 +1,429 @@
+use serde::Serialize;
+
+/// Rectangle of a window's client area in physical pixels of the desktop
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowBounds {
+    /// Shrinks every side by `inset` pixels, `None` when nothing is left
+    pub fn inset(self, inset: u32) -> Option<Self> {
+        let both_sides = inset.checked_mul(2)?;
+        let width = self
+            .width
+            .checked_sub(both_sides)
+            .filter(|width| *width > 0)?;
+        let height = self
+            .height
+            .checked_sub(both_sides)
+            .filter(|height| *height > 0)?;
+        // fits, as it is less than half of the width
+        let inset = inset as i32;
+        Some(Self {
+            x: self.x.checked_add(inset)?,
+            y: self.y.checked_add(inset)?,
+            width,
+            height,
+        })
+    }
+
+    /// Top left and bottom right (exclusive) as the capture area takes them
+    pub fn corners(self) -> ((i32, i32), (i32, i32)) {
+        (
+            (self.x, self.y),
+            (self.x + self.width as i32, self.y + self.height as i32),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum WindowQueryError {
+    /// no window system backend on this platform
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    Unsupported,
+    Connection(String),
+    Request(String),
+}
+
+impl std::fmt::Display for WindowQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowQueryError::Unsupported => {
+                write!(
+                    f,
+                    "Finding the window under the cursor is not supported on this platform"
+                )
+            }
+            WindowQueryError::Connection(message) => {
+                write!(f, "Failed to connect to the window system: {message}")
+            }
+            WindowQueryError::Request(message) => write!(f, "Window query failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for WindowQueryError {}
+
+/// The queries needed to find a client window, so that the search can run on a fake tree
+pub trait WindowTree {
+    type Window: Copy + PartialEq;
+
+    fn root(&self) -> Self::Window;
+    /// Topmost child of `parent` that contains the screen position
+    fn child_at(
+        &self,
+        parent: Self::Window,
+        position: (i32, i32),
+    ) -> Result<Option<Self::Window>, WindowQueryError>;
+    /// Children in stacking order, bottom first
+    fn children(&self, window: Self::Window) -> Result<Vec<Self::Window>, WindowQueryError>;
+    /// `true` for the window of an application, as opposed to a window manager frame
+    fn is_client(&self, window: Self::Window) -> Result<bool, WindowQueryError>;
+    fn bounds(&self, window: Self::Window) -> Result<WindowBounds, WindowQueryError>;
+}
+
+/// Client area of the top-level window at `position`, `None` over the desktop.
+/// Window manager frames are skipped so that title bars and borders are not included.
+pub fn client_bounds_at<T: WindowTree>(
+    tree: &T,
+    position: (i32, i32),
+) -> Result<Option<WindowBounds>, WindowQueryError> {
+    let top_level = match tree.child_at(tree.root(), position)? {
+        Some(top_level) => top_level,
+        None => return Ok(None),
+    };
+    let client = find_client(tree, top_level)?.unwrap_or(top_level);
+    tree.bounds(client).map(Some)
+}
+
+/// Breadth first, the client is usually a direct child of the frame
+fn find_client<T: WindowTree>(
+    tree: &T,
+    top_level: T::Window,
+) -> Result<Option<T::Window>, WindowQueryError> {
+    let mut queue = std::collections::VecDeque::from([top_level]);
+    while let Some(window) = queue.pop_front() {
+        if tree.is_client(window)? {
+            return Ok(Some(window));
+        }
+        queue.extend(tree.children(window)?.into_iter().rev());
+    }
+    Ok(None)
+}
+
+/// Client area of the window at `position`, both in physical pixels
+pub fn window_bounds_at(position: (i32, i32)) -> Result<Option<WindowBounds>, WindowQueryError> {
+    #[cfg(target_os = "linux")]
+    {
+        let tree = x11::X11WindowTree::connect()?;
+        client_bounds_at(&tree, position)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = position;
+        Err(WindowQueryError::Unsupported)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::{WindowBounds, WindowQueryError, WindowTree};
+    use xcb::{x, Xid};
+
+    impl From<xcb::Error> for WindowQueryError {
+        fn from(err: xcb::Error) -> Self {
+            WindowQueryError::Request(err.to_string())
+        }
+    }
+
+    pub struct X11WindowTree {
+        connection: xcb::Connection,
+        root: x::Window,
+        /// set on client windows by the window manager (ICCCM)
+        wm_state: x::Atom,
+    }
+
+    impl X11WindowTree {
+        pub fn connect() -> Result<Self, WindowQueryError> {
+            let (connection, screen_index) = xcb::Connection::connect(None)
+                .map_err(|err| WindowQueryError::Connection(err.to_string()))?;
+            let root = connection
+                .get_setup()
+                .roots()
+                .nth(screen_index as usize)
+                .ok_or_else(|| WindowQueryError::Connection("no screen".to_string()))?
+                .root();
+            let cookie = connection.send_request(&x::InternAtom {
+                only_if_exists: false,
+                name: b"WM_STATE",
+            });
+            let wm_state = connection.wait_for_reply(cookie)?.atom();
+            Ok(Self {
+                connection,
+                root,
+                wm_state,
+            })
+        }
+    }
+
+    impl WindowTree for X11WindowTree {
+        type Window = x::Window;
+
+        fn root(&self) -> x::Window {
+            self.root
+        }
+
+        fn child_at(
+            &self,
+            parent: x::Window,
+            position: (i32, i32),
+        ) -> Result<Option<x::Window>, WindowQueryError> {
+            let cookie = self.connection.send_request(&x::TranslateCoordinates {
+                src_window: self.root,
+                dst_window: parent,
+                src_x: position.0 as i16,
+                src_y: position.1 as i16,
+            });
+            let child = self.connection.wait_for_reply(cookie)?.child();
+            Ok((!child.is_none()).then_some(child))
+        }
+
+        fn children(&self, window: x::Window) -> Result<Vec<x::Window>, WindowQueryError> {
+            let cookie = self.connection.send_request(&x::QueryTree { window });
+            Ok(self.connection.wait_for_reply(cookie)?.children().to_vec())
+        }
+
+        fn is_client(&self, window: x::Window) -> Result<bool, WindowQueryError> {
+            let cookie = self.connection.send_request(&x::GetProperty {
+                delete: false,
+                window,
+                property: self.wm_state,
+                r#type: x::ATOM_ANY,
+                long_offset: 0,
+                long_length: 0,
+            });
+            Ok(self.connection.wait_for_reply(cookie)?.r#type() != x::ATOM_NONE)
+        }
+
+        fn bounds(&self, window: x::Window) -> Result<WindowBounds, WindowQueryError> {
+            let geometry_cookie = self.connection.send_request(&x::GetGeometry {
+                drawable: x::Drawable::Window(window),
+            });
+            // the geometry is relative to the parent, the frame when reparented
+            let origin_cookie = self.connection.send_request(&x::TranslateCoordinates {
+                src_window: window,
+                dst_window: self.root,
+                src_x: 0,
+                src_y: 0,
+            });
+            let geometry = self.connection.wait_for_reply(geometry_cookie)?;
+            let origin = self.connection.wait_for_reply(origin_cookie)?;
+            Ok(WindowBounds {
+                x: origin.dst_x() as i32,
+                y: origin.dst_y() as i32,
+                width: geometry.width() as u32,
+                height: geometry.height() as u32,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const ROOT: u32 = 0;
+
+    struct FakeWindow {
+        bounds: WindowBounds,
+        is_client: bool,
+        /// bottom first
+        children: Vec<u32>,
+    }
+
+    /// Windows by id, with the root at 0
+    struct FakeTree {
+        windows: HashMap<u32, FakeWindow>,
+    }
+
+    impl FakeTree {
+        fn new() -> Self {
+            let mut tree = Self {
+                windows: HashMap::new(),
+            };
+            tree.windows.insert(
+                ROOT,
+                FakeWindow {
+                    bounds: bounds(0, 0, 1920, 1080),
+                    is_client: false,
+                    children: Vec::new(),
+                },
+            );
+            tree
+        }
+
+        fn add(
+            &mut self,
+            id: u32,
+            parent: u32,
+            bounds: WindowBounds,
+            is_client: bool,
+        ) -> &mut Self {
+            self.windows.insert(
+                id,
+                FakeWindow {
+                    bounds,
+                    is_client,
+                    children: Vec::new(),
+                },
+            );
+            self.windows.get_mut(&parent).unwrap().children.push(id);
+            self
+        }
+    }
+
+    impl WindowTree for FakeTree {
+        type Window = u32;
+
+        fn root(&self) -> u32 {
+            ROOT
+        }
+
+        fn child_at(
+            &self,
+            parent: u32,
+            position: (i32, i32),
+        ) -> Result<Option<u32>, WindowQueryError> {
+            Ok(self.windows[&parent]
+                .children
+                .iter()
+                .rev()
+                .find(|child| {
+                    let ((left, top), (right, bottom)) = self.windows[child].bounds.corners();
+                    (left..right).contains(&position.0) && (top..bottom).contains(&position.1)
+                })
+                .copied())
+        }
+
+        fn children(&self, window: u32) -> Result<Vec<u32>, WindowQueryError> {
+            Ok(self.windows[&window].children.clone())
+        }
+
+        fn is_client(&self, window: u32) -> Result<bool, WindowQueryError> {
+            Ok(self.windows[&window].is_client)
+        }
+
+        fn bounds(&self, window: u32) -> Result<WindowBounds, WindowQueryError> {
+            Ok(self.windows[&window].bounds)
+        }
+    }
+
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn skips_the_frame_of_a_reparented_window() {
+        let mut tree = FakeTree::new();
+        tree.add(1, ROOT, bounds(100, 100, 808, 634), false)
+            .add(2, 1, bounds(104, 130, 800, 600), true)
+            // title bar, above the client in stacking order
+            .add(3, 1, bounds(100, 100, 808, 30), false);
+        assert_eq!(
+            client_bounds_at(&tree, (110, 110)).unwrap(),
+            Some(bounds(104, 130, 800, 600))
+        );
+    }
+
+    #[test]
+    fn takes_a_client_without_a_frame() {
+        let mut tree = FakeTree::new();
+        tree.add(1, ROOT, bounds(0, 0, 400, 300), true)
+            // override-redirect popup above it, not managed by the window manager
+            .add(2, ROOT, bounds(200, 200, 400, 300), false);
+        assert_eq!(
+            client_bounds_at(&tree, (50, 50)).unwrap(),
+            Some(bounds(0, 0, 400, 300))
+        );
+        assert_eq!(
+            client_bounds_at(&tree, (250, 250)).unwrap(),
+            Some(bounds(200, 200, 400, 300))
+        );
+    }
+
+    #[test]
+    fn finds_nothing_over_the_desktop() {
+        let mut tree = FakeTree::new();
+        assert_eq!(client_bounds_at(&tree, (10, 10)).unwrap(), None);
+        tree.add(1, ROOT, bounds(100, 100, 200, 200), false).add(
+            2,
+            1,
+            bounds(100, 100, 200, 200),
+            true,
+        );
+        assert_eq!(client_bounds_at(&tree, (10, 10)).unwrap(), None);
+    }
+
+    #[test]
+    fn finds_the_client_in_nested_frames() {
+        let mut tree = FakeTree::new();
+        tree.add(1, ROOT, bounds(0, 0, 1000, 800), false)
+            .add(2, 1, bounds(0, 0, 1000, 30), false)
+            .add(3, 1, bounds(5, 30, 990, 765), false)
+            .add(4, 3, bounds(10, 40, 980, 750), true)
+            // deeper than the nearest client
+            .add(5, 2, bounds(0, 0, 30, 30), false)
+            .add(6, 5, bounds(0, 0, 30, 30), true);
+        assert_eq!(find_client(&tree, 1).unwrap(), Some(4));
+        assert_eq!(
+            client_bounds_at(&tree, (500, 10)).unwrap(),
+            Some(bounds(10, 40, 980, 750))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_top_level_window() {
+        let mut tree = FakeTree::new();
+        tree.add(1, ROOT, bounds(0, 0, 500, 500), false)
+            .add(2, 1, bounds(0, 0, 500, 20), false);
+        assert_eq!(find_client(&tree, 1).unwrap(), None);
+        assert_eq!(
+            client_bounds_at(&tree, (10, 10)).unwrap(),
+            Some(bounds(0, 0, 500, 500))
+        );
+    }
+
+    #[test]
+    fn insets_every_side() {
+        assert_eq!(
+            bounds(10, -20, 100, 50).inset(5),
+            Some(bounds(15, -15, 90, 40))
+        );
+        assert_eq!(
+            bounds(10, 20, 100, 50).inset(0),
+            Some(bounds(10, 20, 100, 50))
+        );
+        assert_eq!(bounds(0, 0, 100, 50).inset(24), Some(bounds(24, 24, 52, 2)));
+    }
+
+    #[test]
+    fn inset_leaves_nothing() {
+        assert_eq!(bounds(0, 0, 100, 50).inset(25), None);
+        assert_eq!(bounds(0, 0, 100, 50).inset(26), None);
+        assert_eq!(bounds(0, 0, 100, 50).inset(u32::MAX), None);
+        assert_eq!(
+            bounds(0, 0, u32::MAX, u32::MAX).inset(u32::MAX / 2 + 1),
+            None
+        );
+    }
+}
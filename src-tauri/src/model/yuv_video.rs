@@ -0,0 +1,620 @@
+use crate::model::frame::{Frame, FrameSamples};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["y4m"];
+
+const Y4M_SIGNATURE: &str = "YUV4MPEG2";
+const Y4M_FRAME_MARKER: &str = "FRAME";
+/// guards against reading a whole raw file as a Y4M header line
+const Y4M_LINE_LENGTH_MAX: u64 = 1024;
+/// 16K, so that sample indices fit in `u32` and frame sizes in `usize`
+const FRAME_SIDE_MAX: u32 = 16384;
+const BIT_DEPTH_MIN: u8 = 8;
+const BIT_DEPTH_MAX: u8 = 16;
+/// smaller videos are assumed to be SD (BT.601) when the matrix is not known
+const HD_HEIGHT_MIN: u32 = 720;
+const U16_MAX: f32 = u16::MAX as f32;
+
+#[derive(Debug)]
+pub enum VideoError {
+    Io(std::io::Error),
+    InvalidHeader(String),
+    Unsupported(String),
+    /// the file ends before the first frame
+    Empty,
+}
+
+impl std::fmt::Display for VideoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoError::Io(err) => write!(f, "{err}"),
+            VideoError::InvalidHeader(message) => write!(f, "invalid header: {message}"),
+            VideoError::Unsupported(message) => write!(f, "unsupported: {message}"),
+            VideoError::Empty => write!(f, "no complete frame in the file"),
+        }
+    }
+}
+
+impl std::error::Error for VideoError {}
+
+impl From<std::io::Error> for VideoError {
+    fn from(err: std::io::Error) -> Self {
+        VideoError::Io(err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChromaSubsampling {
+    #[serde(rename = "420")]
+    Yuv420,
+    #[serde(rename = "422")]
+    Yuv422,
+    #[serde(rename = "444")]
+    Yuv444,
+}
+
+impl ChromaSubsampling {
+    /// horizontal and vertical ratio between luma and chroma samples
+    fn divisors(self) -> (u32, u32) {
+        match self {
+            ChromaSubsampling::Yuv420 => (2, 2),
+            ChromaSubsampling::Yuv422 => (2, 1),
+            ChromaSubsampling::Yuv444 => (1, 1),
+        }
+    }
+}
+
+/// Y′CbCr to R′G′B′ coefficients
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum YuvMatrix {
+    Bt601,
+    Bt709,
+    Bt2020,
+}
+
+impl YuvMatrix {
+    /// BT.601 for SD, BT.709 otherwise
+    pub fn for_height(height: u32) -> Self {
+        match height < HD_HEIGHT_MIN {
+            true => YuvMatrix::Bt601,
+            false => YuvMatrix::Bt709,
+        }
+    }
+
+    /// (Kr, Kb)
+    fn coefficients(self) -> (f32, f32) {
+        match self {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+            YuvMatrix::Bt2020 => (0.2627, 0.0593),
+        }
+    }
+
+    /// Normalized Y′ (0.0 - 1.0), Cb and Cr (-0.5 - 0.5) to normalized R′G′B′
+    #[inline(always)]
+    pub fn to_rgb(self, ycbcr: [f32; 3]) -> [f32; 3] {
+        let (kr, kb) = self.coefficients();
+        let [luma, cb, cr] = ycbcr;
+        let red = luma + 2.0 * (1.0 - kr) * cr;
+        let blue = luma + 2.0 * (1.0 - kb) * cb;
+        let green = (luma - kr * red - kb * blue) / (1.0 - kr - kb);
+        [red, green, blue]
+    }
+
+    /// Normalized R′G′B′ to Y′ (0.0 - 1.0), Cb and Cr (-0.5 - 0.5)
+    #[inline(always)]
+    pub fn to_ycbcr(self, [red, green, blue]: [f32; 3]) -> [f32; 3] {
+        let (kr, kb) = self.coefficients();
+        let luma = kr * red + (1.0 - kr - kb) * green + kb * blue;
+        [
+            luma,
+            (blue - luma) / (2.0 * (1.0 - kb)),
+            (red - luma) / (2.0 * (1.0 - kr)),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum YuvRange {
+    /// 16 - 235 (luma) / 16 - 240 (chroma) at 8-bit
+    Limited,
+    Full,
+}
+
+/// Layout of planar Y′CbCr frames.
+/// Samples above 8-bit are stored as 16-bit little endian.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct YuvFormat {
+    pub width: u32,
+    pub height: u32,
+    pub subsampling: ChromaSubsampling,
+    pub bit_depth: u8,
+    pub matrix: YuvMatrix,
+    pub range: YuvRange,
+    pub frame_rate: f64,
+}
+
+impl YuvFormat {
+    fn validate(&self) -> Result<(), VideoError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(VideoError::InvalidHeader(format!(
+                "frame size {}x{}",
+                self.width, self.height
+            )));
+        }
+        if self.width > FRAME_SIDE_MAX || self.height > FRAME_SIDE_MAX {
+            return Err(VideoError::Unsupported(format!(
+                "frame size {}x{}",
+                self.width, self.height
+            )));
+        }
+        if !(BIT_DEPTH_MIN..=BIT_DEPTH_MAX).contains(&self.bit_depth) {
+            return Err(VideoError::Unsupported(format!(
+                "{}-bit samples",
+                self.bit_depth
+            )));
+        }
+        if !self.frame_rate.is_finite() || self.frame_rate <= 0.0 {
+            return Err(VideoError::InvalidHeader(format!(
+                "frame rate {}",
+                self.frame_rate
+            )));
+        }
+        self.frame_size()?;
+        Ok(())
+    }
+
+    fn chroma_size(&self) -> (u32, u32) {
+        let (horizontal, vertical) = self.subsampling.divisors();
+        (
+            self.width.div_ceil(horizontal),
+            self.height.div_ceil(vertical),
+        )
+    }
+
+    fn bytes_per_sample(&self) -> usize {
+        match self.bit_depth {
+            8 => 1,
+            _ => 2,
+        }
+    }
+
+    /// Bytes of the samples of one frame
+    pub fn frame_size(&self) -> Result<usize, VideoError> {
+        let (chroma_width, chroma_height) = self.chroma_size();
+        (self.width as usize)
+            .checked_mul(self.height as usize)
+            .and_then(|luma_count| {
+                (chroma_width as usize)
+                    .checked_mul(chroma_height as usize)?
+                    .checked_mul(2)?
+                    .checked_add(luma_count)
+            })
+            .and_then(|sample_count| sample_count.checked_mul(self.bytes_per_sample()))
+            .ok_or_else(|| {
+                VideoError::Unsupported(format!("frame size {}x{}", self.width, self.height))
+            })
+    }
+
+    /// Normalizes a luma code value to 0.0 - 1.0
+    #[inline(always)]
+    fn normalize_luma(&self, value: u16) -> f32 {
+        let scale = (1u32 << (self.bit_depth - 8)) as f32;
+        match self.range {
+            YuvRange::Limited => (value as f32 - 16.0 * scale) / (219.0 * scale),
+            YuvRange::Full => value as f32 / ((1u32 << self.bit_depth) - 1) as f32,
+        }
+    }
+
+    /// Normalizes a chroma code value to -0.5 - 0.5
+    #[inline(always)]
+    fn normalize_chroma(&self, value: u16) -> f32 {
+        let scale = (1u32 << (self.bit_depth - 8)) as f32;
+        match self.range {
+            YuvRange::Limited => (value as f32 - 128.0 * scale) / (224.0 * scale),
+            YuvRange::Full => {
+                (value as f32 - 128.0 * scale) / ((1u32 << self.bit_depth) - 1) as f32
+            }
+        }
+    }
+}
+
+/// One decoded frame with its planes at the native bit depth
+pub struct YuvFrame {
+    format: YuvFormat,
+    luma: Vec<u16>,
+    cb: Vec<u16>,
+    cr: Vec<u16>,
+}
+
+impl YuvFrame {
+    /// `data` is `format.frame_size()` bytes of a validated format
+    fn from_bytes(format: YuvFormat, data: &[u8]) -> Self {
+        let samples: Vec<u16> = match format.bytes_per_sample() {
+            1 => data.iter().map(|sample| *sample as u16).collect(),
+            _ => data
+                .chunks_exact(2)
+                .map(|sample| u16::from_le_bytes([sample[0], sample[1]]))
+                .collect(),
+        };
+        let luma_count = format.width as usize * format.height as usize;
+        let (chroma_width, chroma_height) = format.chroma_size();
+        let chroma_count = chroma_width as usize * chroma_height as usize;
+        Self {
+            format,
+            luma: samples[..luma_count].to_vec(),
+            cb: samples[luma_count..luma_count + chroma_count].to_vec(),
+            cr: samples[luma_count + chroma_count..luma_count + 2 * chroma_count].to_vec(),
+        }
+    }
+
+    pub fn format(&self) -> &YuvFormat {
+        &self.format
+    }
+
+    pub fn chroma_sample_count(&self) -> usize {
+        self.cb.len()
+    }
+
+    /// Normalized Y′CbCr at chroma sample `index`, with the luma sample at its top left
+    #[inline(always)]
+    pub fn chroma_ycbcr(&self, index: usize) -> [f32; 3] {
+        let (chroma_width, _) = self.format.chroma_size();
+        let (horizontal, vertical) = self.format.subsampling.divisors();
+        let x = (index as u32 % chroma_width) * horizontal;
+        let y = (index as u32 / chroma_width) * vertical;
+        [
+            self.format
+                .normalize_luma(self.luma[(y * self.format.width + x) as usize]),
+            self.format.normalize_chroma(self.cb[index]),
+            self.format.normalize_chroma(self.cr[index]),
+        ]
+    }
+
+    /// R′G′B′ frame with the chroma upsampled by repetition
+    pub fn to_frame(&self) -> Frame {
+        let (chroma_width, _) = self.format.chroma_size();
+        let (horizontal, vertical) = self.format.subsampling.divisors();
+        let mut samples: Vec<u16> = Vec::with_capacity(self.luma.len() * 4);
+        for y in 0..self.format.height {
+            for x in 0..self.format.width {
+                let chroma_index = ((y / vertical) * chroma_width + x / horizontal) as usize;
+                let rgb = self.format.matrix.to_rgb([
+                    self.format
+                        .normalize_luma(self.luma[(y * self.format.width + x) as usize]),
+                    self.format.normalize_chroma(self.cb[chroma_index]),
+                    self.format.normalize_chroma(self.cr[chroma_index]),
+                ]);
+                samples.extend(rgb.map(|value| (value.clamp(0.0, 1.0) * U16_MAX).round() as u16));
+                samples.push(u16::MAX);
+            }
+        }
+        Frame::new(
+            self.format.width,
+            self.format.height,
+            FrameSamples::Rgba16(samples),
+        )
+    }
+}
+
+/// Y4M or headerless planar Y′CbCr file, read one frame at a time
+pub struct YuvVideo {
+    reader: BufReader<File>,
+    format: YuvFormat,
+    /// position of the samples of every complete frame
+    frame_offsets: Vec<u64>,
+}
+
+impl YuvVideo {
+    /// Opens a YUV4MPEG2 file. Range is limited unless `XCOLORRANGE=FULL`,
+    /// the matrix is guessed from the height.
+    pub fn open_y4m(path: &Path) -> Result<Self, VideoError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let header = match read_line(&mut reader) {
+            Ok(Some(header)) => header,
+            Ok(None) | Err(VideoError::InvalidHeader(_)) => String::new(),
+            Err(err) => return Err(err),
+        };
+        let format = parse_y4m_header(&header)?;
+        format.validate()?;
+
+        let file_length = reader.get_ref().metadata()?.len();
+        let frame_size = format.frame_size()? as u64;
+        let mut frame_offsets: Vec<u64> = Vec::new();
+        while let Some(line) = read_line(&mut reader)? {
+            if !line.starts_with(Y4M_FRAME_MARKER) {
+                return Err(VideoError::InvalidHeader(format!(
+                    "expected {Y4M_FRAME_MARKER} but found `{line}`"
+                )));
+            }
+            let offset = reader.stream_position()?;
+            if offset + frame_size > file_length {
+                break;
+            }
+            frame_offsets.push(offset);
+            reader.seek(SeekFrom::Start(offset + frame_size))?;
+        }
+        Self::new(reader, format, frame_offsets)
+    }
+
+    /// Opens a headerless file of consecutive planar frames (Y, then Cb, then Cr).
+    /// A trailing partial frame is ignored.
+    pub fn open_raw(path: &Path, format: YuvFormat) -> Result<Self, VideoError> {
+        format.validate()?;
+        let reader = BufReader::new(File::open(path)?);
+        let frame_size = format.frame_size()? as u64;
+        let frame_count = reader.get_ref().metadata()?.len() / frame_size;
+        let frame_offsets = (0..frame_count).map(|index| index * frame_size).collect();
+        Self::new(reader, format, frame_offsets)
+    }
+
+    fn new(
+        reader: BufReader<File>,
+        format: YuvFormat,
+        frame_offsets: Vec<u64>,
+    ) -> Result<Self, VideoError> {
+        if frame_offsets.is_empty() {
+            return Err(VideoError::Empty);
+        }
+        Ok(Self {
+            reader,
+            format,
+            frame_offsets,
+        })
+    }
+
+    pub fn format(&self) -> &YuvFormat {
+        &self.format
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_offsets.len()
+    }
+
+    /// Reads frame `index`, clamped to the last frame
+    pub fn read_frame(&mut self, index: usize) -> Result<YuvFrame, VideoError> {
+        let offset = self.frame_offsets[index.min(self.frame_offsets.len() - 1)];
+        let mut data = vec![0u8; self.format.frame_size()?];
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.reader.read_exact(&mut data)?;
+        Ok(YuvFrame::from_bytes(self.format, &data))
+    }
+}
+
+/// Reads up to the next `\n`, `None` when the file ends before it
+fn read_line(reader: &mut BufReader<File>) -> Result<Option<String>, VideoError> {
+    let mut line: Vec<u8> = Vec::new();
+    let length = reader
+        .by_ref()
+        .take(Y4M_LINE_LENGTH_MAX)
+        .read_until(b'\n', &mut line)?;
+    match line.pop() {
+        Some(b'\n') => Ok(Some(String::from_utf8_lossy(&line).into_owned())),
+        _ if (length as u64) < Y4M_LINE_LENGTH_MAX => Ok(None),
+        _ => Err(VideoError::InvalidHeader("line is too long".to_string())),
+    }
+}
+
+fn parse_y4m_header(header: &str) -> Result<YuvFormat, VideoError> {
+    let mut tokens = header.split(' ');
+    if tokens.next() != Some(Y4M_SIGNATURE) {
+        return Err(VideoError::InvalidHeader(format!(
+            "not a {Y4M_SIGNATURE} file"
+        )));
+    }
+    let mut width: Option<u32> = None;
+    let mut height: Option<u32> = None;
+    let mut frame_rate: Option<f64> = None;
+    let mut subsampling = ChromaSubsampling::Yuv420;
+    let mut bit_depth: u8 = 8;
+    let mut range = YuvRange::Limited;
+    for token in tokens.filter(|token| !token.is_empty()) {
+        // tags are one letter, but the token may start with any character
+        let tag_length = token.chars().next().map_or(0, char::len_utf8);
+        let (tag, value) = token.split_at(tag_length);
+        match tag {
+            "W" => width = Some(parse_number(token, value)?),
+            "H" => height = Some(parse_number(token, value)?),
+            "F" => {
+                let (numerator, denominator) = value
+                    .split_once(':')
+                    .ok_or_else(|| VideoError::InvalidHeader(format!("`{token}`")))?;
+                frame_rate = Some(
+                    parse_number::<f64>(token, numerator)?
+                        / parse_number::<f64>(token, denominator)?,
+                );
+            }
+            "C" => (subsampling, bit_depth) = parse_y4m_color_space(value)?,
+            "X" => {
+                if let Some(color_range) = value.strip_prefix("COLORRANGE=") {
+                    range = match color_range {
+                        "FULL" => YuvRange::Full,
+                        _ => YuvRange::Limited,
+                    };
+                }
+            }
+            // interlacing, pixel aspect ratio
+            _ => {}
+        }
+    }
+    let width = width.ok_or_else(|| VideoError::InvalidHeader("missing width".to_string()))?;
+    let height = height.ok_or_else(|| VideoError::InvalidHeader("missing height".to_string()))?;
+    Ok(YuvFormat {
+        width,
+        height,
+        subsampling,
+        bit_depth,
+        matrix: YuvMatrix::for_height(height),
+        range,
+        frame_rate: frame_rate
+            .ok_or_else(|| VideoError::InvalidHeader("missing frame rate".to_string()))?,
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(token: &str, value: &str) -> Result<T, VideoError> {
+    value
+        .parse::<T>()
+        .map_err(|_| VideoError::InvalidHeader(format!("`{token}`")))
+}
+
+/// `420jpeg`, `422`, `444p10` etc.
+fn parse_y4m_color_space(value: &str) -> Result<(ChromaSubsampling, u8), VideoError> {
+    let unsupported = || VideoError::Unsupported(format!("color space `{value}`"));
+    if value.len() < 3 || !value.is_char_boundary(3) {
+        return Err(unsupported());
+    }
+    let (subsampling, suffix) = value.split_at(3);
+    let subsampling = match subsampling {
+        "420" => ChromaSubsampling::Yuv420,
+        "422" => ChromaSubsampling::Yuv422,
+        "444" => ChromaSubsampling::Yuv444,
+        _ => return Err(unsupported()),
+    };
+    let bit_depth = match suffix {
+        // chroma siting variants of 8-bit 4:2:0
+        "" | "jpeg" | "paldv" | "mpeg2" => 8,
+        _ => suffix
+            .strip_prefix('p')
+            .and_then(|depth| depth.parse::<u8>().ok())
+            .ok_or_else(unsupported)?,
+    };
+    Ok((subsampling, bit_depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(header: &str) -> Result<YuvFormat, VideoError> {
+        let format = parse_y4m_header(header)?;
+        format.validate()?;
+        Ok(format)
+    }
+
+    fn invalid_header(header_line: &str) -> String {
+        match header(header_line) {
+            Err(VideoError::InvalidHeader(message)) => message,
+            Err(err) => panic!("{header_line}: {err}"),
+            Ok(format) => panic!("{header_line}: parsed {format:?}"),
+        }
+    }
+
+    fn unsupported(header_line: &str) -> String {
+        match header(header_line) {
+            Err(VideoError::Unsupported(message)) => message,
+            Err(err) => panic!("{header_line}: {err}"),
+            Ok(format) => panic!("{header_line}: parsed {format:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_y4m_header() {
+        let format =
+            header("YUV4MPEG2 W640 H480 F30000:1001 Ip A1:1 C420jpeg XYSCSS=420JPEG").unwrap();
+        assert_eq!(
+            format,
+            YuvFormat {
+                width: 640,
+                height: 480,
+                subsampling: ChromaSubsampling::Yuv420,
+                bit_depth: 8,
+                matrix: YuvMatrix::Bt601,
+                range: YuvRange::Limited,
+                frame_rate: 30000.0 / 1001.0,
+            }
+        );
+
+        let format = header("YUV4MPEG2 W1920 H1080 F25:1 C422p10 XCOLORRANGE=FULL").unwrap();
+        assert_eq!(format.subsampling, ChromaSubsampling::Yuv422);
+        assert_eq!(format.bit_depth, 10);
+        assert_eq!(format.matrix, YuvMatrix::Bt709);
+        assert_eq!(format.range, YuvRange::Full);
+
+        // 8-bit 4:2:0 without a color space, extra spaces are ignored
+        let format = header("YUV4MPEG2  W2 H2 F1:1 ").unwrap();
+        assert_eq!(format.subsampling, ChromaSubsampling::Yuv420);
+        assert_eq!(format.bit_depth, 8);
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        assert!(invalid_header("").contains(Y4M_SIGNATURE));
+        assert!(invalid_header("YUV4MPEG W2 H2 F1:1").contains(Y4M_SIGNATURE));
+        assert_eq!(invalid_header("YUV4MPEG2 H2 F1:1"), "missing width");
+        assert_eq!(invalid_header("YUV4MPEG2 W2 F1:1"), "missing height");
+        assert_eq!(invalid_header("YUV4MPEG2 W2 H2"), "missing frame rate");
+        assert_eq!(invalid_header("YUV4MPEG2 Wabc H2 F1:1"), "`Wabc`");
+        assert_eq!(invalid_header("YUV4MPEG2 W-2 H2 F1:1"), "`W-2`");
+        assert_eq!(invalid_header("YUV4MPEG2 W2 H2 F30"), "`F30`");
+        assert_eq!(invalid_header("YUV4MPEG2 W2 H2 F30:0"), "frame rate inf");
+        assert_eq!(invalid_header("YUV4MPEG2 W0 H2 F1:1"), "frame size 0x2");
+        // not a tag, but must not panic
+        assert_eq!(header("YUV4MPEG2 W2 H2 F1:1 éX").unwrap().width, 2);
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        assert_eq!(
+            unsupported("YUV4MPEG2 W2 H2 F1:1 C411"),
+            "color space `411`"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W2 H2 F1:1 Cmono"),
+            "color space `mono`"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W2 H2 F1:1 C420p"),
+            "color space `420p`"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W2 H2 F1:1 C44é"),
+            "color space `44é`"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W2 H2 F1:1 C444p20"),
+            "20-bit samples"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W16385 H2 F1:1"),
+            "frame size 16385x2"
+        );
+        assert_eq!(
+            unsupported("YUV4MPEG2 W4294967295 H4294967295 F1:1"),
+            "frame size 4294967295x4294967295"
+        );
+    }
+
+    #[test]
+    fn computes_frame_size() {
+        let format = header("YUV4MPEG2 W5 H3 F1:1").unwrap();
+        // 5x3 luma, 3x2 chroma
+        assert_eq!(format.frame_size().unwrap(), 15 + 2 * 6);
+        let format = header("YUV4MPEG2 W5 H3 F1:1 C422p12").unwrap();
+        assert_eq!(format.frame_size().unwrap(), (15 + 2 * 9) * 2);
+        let format = header("YUV4MPEG2 W16384 H16384 F1:1 C444p16").unwrap();
+        assert_eq!(format.frame_size().unwrap(), 16384 * 16384 * 3 * 2);
+    }
+
+    #[test]
+    fn splits_planes() {
+        let format = header("YUV4MPEG2 W2 H2 F1:1 C420p10 XCOLORRANGE=FULL").unwrap();
+        let data: Vec<u8> = [0u16, 1, 2, 1023, 512, 0]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        assert_eq!(data.len(), format.frame_size().unwrap());
+        let frame = YuvFrame::from_bytes(format, &data);
+        assert_eq!(frame.luma, [0, 1, 2, 1023]);
+        assert_eq!(frame.cb, [512]);
+        assert_eq!(frame.cr, [0]);
+        assert_eq!(frame.chroma_sample_count(), 1);
+        let [luma, cb, cr] = frame.chroma_ycbcr(0);
+        assert_eq!(luma, 0.0);
+        assert!((cb - 0.0).abs() < 1e-3 && (cr + 0.5).abs() < 1e-3);
+    }
+}
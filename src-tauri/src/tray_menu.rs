@@ -0,0 +1,116 @@
+use crate::main_view_model::WaveformMode;
+use crate::model::color_space::InputColorSpace;
+use crate::model::test_pattern::TestPattern;
+use crate::model::tray_icon::TrayIconMode;
+
+pub const TRAY_QUIT: &str = "QUIT";
+pub const TRAY_VECTOR_SCOPE: &str = "VECTOR_SCOPE";
+pub const TRAY_WAVEFORM: &str = "WAVEFORM";
+pub const TRAY_CAPTURE_AREA_SETTING: &str = "CAPTURE_AREA_SETTING";
+pub const TRAY_CAPTURE_FULL_SCREEN: &str = "CAPTURE_FULL_SCREEN";
+pub const TRAY_EXCLUDE_OWN_WINDOWS: &str = "EXCLUDE_OWN_WINDOWS";
+pub const TRAY_AUTO_REFRESH: &str = "AUTO_REFRESH";
+pub const TRAY_HOLD: &str = "HOLD";
+pub const TRAY_LOUPE: &str = "LOUPE";
+pub const TRAY_PALETTE: &str = "PALETTE";
+pub const TRAY_FALSE_COLOR: &str = "FALSE_COLOR";
+pub const TRAY_EXPORT: &str = "EXPORT";
+pub const TRAY_EXPORT_DIRECTORY: &str = "EXPORT_DIRECTORY";
+pub const TRAY_RECORDING: &str = "RECORDING";
+pub const TRAY_LEGAL_RANGE: &str = "LEGAL_RANGE";
+pub const TRAY_SCOPE_SERVER: &str = "SCOPE_SERVER";
+pub const TRAY_CONTROL_SOCKET: &str = "CONTROL_SOCKET";
+pub const TRAY_SOURCE_SCREEN: &str = "SOURCE_SCREEN";
+pub const TRAY_SOURCE_OPEN_IMAGE: &str = "SOURCE_OPEN_IMAGE";
+pub const TRAY_SOURCE_OPEN_VIDEO: &str = "SOURCE_OPEN_VIDEO";
+pub const TRAY_SOURCE_CLIPBOARD: &str = "SOURCE_CLIPBOARD";
+pub const TRAY_SOURCE_PATTERN_COLOR_BARS: &str = "SOURCE_PATTERN_COLOR_BARS";
+pub const TRAY_SOURCE_PATTERN_GRAY_RAMP: &str = "SOURCE_PATTERN_GRAY_RAMP";
+pub const TRAY_REFERENCE_FREEZE: &str = "REFERENCE_FREEZE";
+pub const TRAY_REFERENCE_LOAD: &str = "REFERENCE_LOAD";
+pub const TRAY_REFERENCE_OVERLAY: &str = "REFERENCE_OVERLAY";
+pub const TRAY_REFERENCE_SPLIT: &str = "REFERENCE_SPLIT";
+pub const TRAY_REFERENCE_CLEAR: &str = "REFERENCE_CLEAR";
+pub const TRAY_COLOR_SPACE_SRGB: &str = "COLOR_SPACE_SRGB";
+pub const TRAY_COLOR_SPACE_DISPLAY_P3: &str = "COLOR_SPACE_DISPLAY_P3";
+pub const TRAY_COLOR_SPACE_ADOBE_RGB: &str = "COLOR_SPACE_ADOBE_RGB";
+pub const TRAY_COLOR_SPACE_REC709: &str = "COLOR_SPACE_REC709";
+pub const TRAY_COLOR_SPACE_REC2020: &str = "COLOR_SPACE_REC2020";
+pub const TRAY_COLOR_SPACE_ICC: &str = "COLOR_SPACE_ICC";
+pub const TRAY_LUT_LOAD: &str = "LUT_LOAD";
+pub const TRAY_LUT_CLEAR: &str = "LUT_CLEAR";
+pub const TRAY_WAVEFORM_MODE_LUMA: &str = "WAVEFORM_MODE_LUMA";
+pub const TRAY_WAVEFORM_MODE_LINEAR: &str = "WAVEFORM_MODE_LINEAR";
+pub const TRAY_WAVEFORM_MODE_PQ: &str = "WAVEFORM_MODE_PQ";
+pub const TRAY_WAVEFORM_MODE_HLG: &str = "WAVEFORM_MODE_HLG";
+pub const TRAY_ICON_DEFAULT: &str = "TRAY_ICON_DEFAULT";
+pub const TRAY_ICON_AVERAGE_COLOR: &str = "TRAY_ICON_AVERAGE_COLOR";
+pub const TRAY_ICON_HISTOGRAM: &str = "TRAY_ICON_HISTOGRAM";
+/// (id, title, interval in milliseconds)
+pub const TRAY_REFRESH_INTERVALS: [(&str, &str, u64); 5] = [
+    ("REFRESH_INTERVAL_250", "0.25 s", 250),
+    ("REFRESH_INTERVAL_500", "0.5 s", 500),
+    ("REFRESH_INTERVAL_1000", "1 s", 1000),
+    ("REFRESH_INTERVAL_2000", "2 s", 2000),
+    ("REFRESH_INTERVAL_5000", "5 s", 5000),
+];
+pub const TRAY_WAVEFORM_MODES: [(&str, WaveformMode); 4] = [
+    (TRAY_WAVEFORM_MODE_LUMA, WaveformMode::Luma),
+    (TRAY_WAVEFORM_MODE_LINEAR, WaveformMode::Linear),
+    (TRAY_WAVEFORM_MODE_PQ, WaveformMode::Pq),
+    (TRAY_WAVEFORM_MODE_HLG, WaveformMode::Hlg),
+];
+pub const TRAY_ICON_MODES: [(&str, TrayIconMode); 3] = [
+    (TRAY_ICON_DEFAULT, TrayIconMode::Default),
+    (TRAY_ICON_AVERAGE_COLOR, TrayIconMode::AverageColor),
+    (TRAY_ICON_HISTOGRAM, TrayIconMode::Histogram),
+];
+pub const TRAY_SOURCE_PATTERNS: [(&str, TestPattern); 2] = [
+    (TRAY_SOURCE_PATTERN_COLOR_BARS, TestPattern::ColorBars),
+    (TRAY_SOURCE_PATTERN_GRAY_RAMP, TestPattern::GrayRamp),
+];
+pub const TRAY_COLOR_SPACES: [(&str, InputColorSpace); 5] = [
+    (TRAY_COLOR_SPACE_SRGB, InputColorSpace::Srgb),
+    (TRAY_COLOR_SPACE_DISPLAY_P3, InputColorSpace::DisplayP3),
+    (TRAY_COLOR_SPACE_ADOBE_RGB, InputColorSpace::AdobeRgb),
+    (TRAY_COLOR_SPACE_REC709, InputColorSpace::Rec709),
+    (TRAY_COLOR_SPACE_REC2020, InputColorSpace::Rec2020),
+];
+
+/// Title of a tray item that shows a check mark
+pub fn tray_item_title(id: &str) -> &'static str {
+    match id {
+        TRAY_AUTO_REFRESH => "Auto refresh",
+        TRAY_HOLD => "Hold",
+        TRAY_CAPTURE_FULL_SCREEN => "Full screen",
+        TRAY_CAPTURE_AREA_SETTING => "Selected area...",
+        TRAY_EXCLUDE_OWN_WINDOWS => "Exclude own windows",
+        TRAY_SOURCE_SCREEN => "Screen",
+        TRAY_SOURCE_OPEN_IMAGE => "Open image...",
+        TRAY_SOURCE_OPEN_VIDEO => "Open video...",
+        TRAY_SOURCE_CLIPBOARD => "Analyze clipboard image",
+        TRAY_SOURCE_PATTERN_COLOR_BARS => "Color bars",
+        TRAY_SOURCE_PATTERN_GRAY_RAMP => "Gray ramp",
+        TRAY_REFERENCE_OVERLAY => "Overlay",
+        TRAY_REFERENCE_SPLIT => "Split",
+        TRAY_WAVEFORM_MODE_LUMA => "Luma",
+        TRAY_WAVEFORM_MODE_LINEAR => "Linear luminance",
+        TRAY_WAVEFORM_MODE_PQ => "PQ (nits)",
+        TRAY_WAVEFORM_MODE_HLG => "HLG (nits)",
+        TRAY_COLOR_SPACE_SRGB => "sRGB",
+        TRAY_COLOR_SPACE_DISPLAY_P3 => "Display P3",
+        TRAY_COLOR_SPACE_ADOBE_RGB => "Adobe RGB",
+        TRAY_COLOR_SPACE_REC709 => "Rec.709",
+        TRAY_COLOR_SPACE_REC2020 => "Rec.2020",
+        TRAY_COLOR_SPACE_ICC => "ICC profile...",
+        TRAY_LEGAL_RANGE => "Legal range alarm",
+        TRAY_CONTROL_SOCKET => "Control socket",
+        TRAY_ICON_DEFAULT => "App icon",
+        TRAY_ICON_AVERAGE_COLOR => "Average color",
+        TRAY_ICON_HISTOGRAM => "Luma histogram",
+        _ => TRAY_REFRESH_INTERVALS
+            .iter()
+            .find(|(interval_id, ..)| *interval_id == id)
+            .map_or("", |(_, title, _)| title),
+    }
+}
@@ -5,6 +5,13 @@ import { appWindow, LogicalSize } from "@tauri-apps/api/window";
 
 import "../common/styles.css";
 
+type CaptureArea = {
+  display_id: number;
+  top_left: [number, number];
+  bottom_right: [number, number];
+  clamped: boolean;
+};
+
 export function CaptureAreaSetting() {
   appWindow.setTitle("Capture Area Setting");
   appWindow.setContentProtected(true);
@@ -18,25 +25,34 @@ export function CaptureAreaSetting() {
 
   window.addEventListener("dblclick", setCaptureArea);
   async function setCaptureArea() {
-    setFirstLineText("Capture Area has set");
-    const factor = await appWindow.scaleFactor();
-
+    // physical pixels, so that no scale factor is applied on the way
     const physicalPosition = await appWindow.outerPosition();
-    const logicalPosition = physicalPosition.toLogical(factor);
-
     const physicalSize = await appWindow.outerSize();
-    const logicalSize = physicalSize.toLogical(factor);
 
-    const x_1 = logicalPosition.x;
-    const y_1 = logicalPosition.y;
-    const x_2 = logicalPosition.x + logicalSize.width;
-    const y_2 = logicalPosition.y + logicalSize.height;
-    setSecondLineText(`[${x_1}, ${y_1}] - [${x_2}, ${y_1}]`);
+    const x_1 = physicalPosition.x;
+    const y_1 = physicalPosition.y;
+    const x_2 = physicalPosition.x + physicalSize.width;
+    const y_2 = physicalPosition.y + physicalSize.height;
 
-    await invoke("set_capture_area", {
-      topLeft: [x_1, y_1],
-      bottomRight: [x_2, y_2],
-    });
+    try {
+      // the area actually captured: rounded and cut to a single display
+      const area: CaptureArea = await invoke("set_capture_area", {
+        topLeft: [x_1, y_1],
+        bottomRight: [x_2, y_2],
+        space: "physical",
+      });
+      setFirstLineText(
+        area.clamped
+          ? "Capture Area has set (cut to the display)"
+          : "Capture Area has set"
+      );
+      setSecondLineText(
+        `[${area.top_left[0]}, ${area.top_left[1]}] - [${area.bottom_right[0]}, ${area.bottom_right[1]}]`
+      );
+    } catch (error) {
+      setFirstLineText("Capture Area has not set");
+      setSecondLineText(`${error}`);
+    }
   }
 
   let longClickTimeout: NodeJS.Timeout;
@@ -0,0 +1,146 @@
+import { render } from "solid-js/web";
+import { createSignal } from "solid-js";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { appWindow, LogicalSize } from "@tauri-apps/api/window";
+
+import "../common/styles.css";
+
+const LISTEN_EVENT_NAME: string = "event-false-color";
+
+type FalseColorMode = "off" | "false_color" | "zebra";
+
+type FalseColorSettings = {
+  mode: FalseColorMode;
+  zebra_threshold: number;
+  highlight_illegal: boolean;
+};
+
+const MODE_ORDER: FalseColorMode[] = ["false_color", "zebra", "off"];
+
+// Methods to address the memory leaks problems in Safari
+let BASE64_MARKER = ";base64,";
+let temporaryImage: string;
+let objectURL = window.URL || window.webkitURL;
+
+// Y4M files are opened as video, anything else as a still image
+function openFile(path: string) {
+  let command = path.toLowerCase().endsWith(".y4m")
+    ? "open_video_file"
+    : "open_image_file";
+  invoke(command, { path: path }).catch((error: string) =>
+    invoke("print_log", { text: error })
+  );
+}
+
+function convertDataURIToBlob(dataURI: string): Blob {
+  // Convert image (in base64) to binary data
+  let base64Index = dataURI.indexOf(BASE64_MARKER) + BASE64_MARKER.length;
+  let base64 = dataURI.substring(base64Index);
+
+  let raw = atob(base64);
+  let rawLength = raw.length;
+  let array = new Uint8Array(new ArrayBuffer(rawLength));
+
+  for (let i = 0; i < rawLength; i++) {
+    array[i] = raw.charCodeAt(i);
+  }
+
+  // Create and return a new blob object using binary data
+  return new Blob([array], { type: "image/png" });
+}
+
+export function FalseColor() {
+  const [image, setImage] = createSignal("");
+  const [modeText, setModeText] = createSignal("");
+
+  initializeWindow();
+  listenCloseWindow();
+  window.addEventListener("dblclick", () => setManualModeOn(false));
+  window.addEventListener("contextmenu", (event: MouseEvent) => {
+    event.preventDefault();
+    cycleMode();
+  });
+  window.addEventListener("keydown", (event: KeyboardEvent) => {
+    if (event.key === "g") toggleGamutWarning();
+  });
+
+  async function initializeWindow() {
+    appWindow.setTitle("False Color");
+    appWindow.setContentProtected(true);
+    appWindow.setAlwaysOnTop(true);
+    appWindow.setSize(new LogicalSize(500, 320));
+
+    await listen(LISTEN_EVENT_NAME, (event: any) => {
+      let dataURI = event.payload as string; // event.payload is payload
+      if (temporaryImage) objectURL.revokeObjectURL(temporaryImage);
+      let imageDataBlob: Blob = convertDataURIToBlob(dataURI);
+      temporaryImage = objectURL.createObjectURL(imageDataBlob);
+      setImage(temporaryImage);
+      dataURI = "";
+    });
+    await appWindow.onFileDropEvent((event) => {
+      if (event.payload.type === "drop" && event.payload.paths.length > 0) {
+        openFile(event.payload.paths[0]);
+      }
+    });
+    showSettings(await invoke("get_false_color_settings"));
+    setIsFalseColorWindowOpen(true);
+    setManualModeOn(false);
+  }
+
+  async function listenCloseWindow() {
+    await appWindow.onCloseRequested(async () => {
+      setIsFalseColorWindowOpen(false);
+    });
+  }
+
+  async function setIsFalseColorWindowOpen(open: boolean) {
+    invoke("set_is_false_color_window_open", { state: open });
+  }
+
+  async function setManualModeOn(state: boolean) {
+    invoke("set_manual_mode", { state: state });
+  }
+
+  function showSettings(settings: FalseColorSettings) {
+    setModeText(
+      `${settings.mode} / gamut warning: ${
+        settings.highlight_illegal ? "on" : "off"
+      }`
+    );
+  }
+
+  async function updateSettings(
+    update: (settings: FalseColorSettings) => FalseColorSettings
+  ) {
+    let settings = update(await invoke("get_false_color_settings"));
+    await invoke("set_false_color_settings", { settings: settings });
+    showSettings(settings);
+  }
+
+  async function cycleMode() {
+    updateSettings((settings) => ({
+      ...settings,
+      mode: MODE_ORDER[
+        (MODE_ORDER.indexOf(settings.mode) + 1) % MODE_ORDER.length
+      ],
+    }));
+  }
+
+  async function toggleGamutWarning() {
+    updateSettings((settings) => ({
+      ...settings,
+      highlight_illegal: !settings.highlight_illegal,
+    }));
+  }
+
+  return (
+    <div>
+      <img src={image()}></img>
+      <p style="font-size: 12px;">{modeText()}</p>
+    </div>
+  );
+}
+
+render(() => <FalseColor />, document.getElementById("root") as HTMLElement);
@@ -0,0 +1,105 @@
+import { render } from "solid-js/web";
+import { createSignal } from "solid-js";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { appWindow, LogicalSize } from "@tauri-apps/api/window";
+
+import "../common/styles.css";
+import "./loupe.css";
+
+const LISTEN_EVENT_NAME: string = "event-loupe";
+
+type LoupePayload = {
+  image: string;
+  position: [number, number];
+  zoom: number;
+  red: number;
+  green: number;
+  blue: number;
+};
+
+// Methods to address the memory leaks problems in Safari
+let BASE64_MARKER = ";base64,";
+let temporaryImage: string;
+let objectURL = window.URL || window.webkitURL;
+
+let zoom: number = 8;
+
+function convertDataURIToBlob(dataURI: string): Blob {
+  // Convert image (in base64) to binary data
+  let base64Index = dataURI.indexOf(BASE64_MARKER) + BASE64_MARKER.length;
+  let base64 = dataURI.substring(base64Index);
+
+  let raw = atob(base64);
+  let rawLength = raw.length;
+  let array = new Uint8Array(new ArrayBuffer(rawLength));
+
+  for (let i = 0; i < rawLength; i++) {
+    array[i] = raw.charCodeAt(i);
+  }
+
+  // Create and return a new blob object using binary data
+  return new Blob([array], { type: "image/png" });
+}
+
+function toHex(value: number): string {
+  return value.toString(16).padStart(2, "0").toUpperCase();
+}
+
+export function Loupe() {
+  const [image, setImage] = createSignal("");
+  const [pixelValue, setPixelValue] = createSignal("");
+
+  initializeWindow();
+  listenCloseWindow();
+  window.addEventListener("wheel", (event: WheelEvent) => {
+    setZoom(event.deltaY < 0 ? zoom * 2 : zoom / 2);
+  });
+  window.addEventListener("contextmenu", (event: MouseEvent) => {
+    event.preventDefault();
+  });
+
+  async function initializeWindow() {
+    appWindow.setTitle("Loupe");
+    appWindow.setContentProtected(true);
+    appWindow.setAlwaysOnTop(true);
+    appWindow.setSize(new LogicalSize(280, 320));
+
+    await listen(LISTEN_EVENT_NAME, (event: any) => {
+      let payload = event.payload as LoupePayload;
+      if (temporaryImage) objectURL.revokeObjectURL(temporaryImage);
+      let imageDataBlob: Blob = convertDataURIToBlob(payload.image);
+      temporaryImage = objectURL.createObjectURL(imageDataBlob);
+      setImage(temporaryImage);
+      setPixelValue(
+        `[${payload.position[0]}, ${payload.position[1]}] x${payload.zoom} ` +
+          `RGB(${payload.red}, ${payload.green}, ${payload.blue}) ` +
+          `#${toHex(payload.red)}${toHex(payload.green)}${toHex(payload.blue)}`
+      );
+    });
+    setIsLoupeWindowOpen(true);
+  }
+
+  async function listenCloseWindow() {
+    await appWindow.onCloseRequested(async () => {
+      setIsLoupeWindowOpen(false);
+    });
+  }
+
+  async function setIsLoupeWindowOpen(open: boolean) {
+    invoke("set_is_loupe_window_open", { state: open });
+  }
+
+  async function setZoom(value: number) {
+    zoom = await invoke("set_loupe_zoom", { zoom: Math.round(value) });
+  }
+
+  return (
+    <div>
+      <img src={image()}></img>
+      <p class="pixel-value">{pixelValue()}</p>
+    </div>
+  );
+}
+
+render(() => <Loupe />, document.getElementById("root") as HTMLElement);
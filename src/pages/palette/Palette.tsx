@@ -0,0 +1,137 @@
+import { render } from "solid-js/web";
+import { createSignal, For } from "solid-js";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { save } from "@tauri-apps/api/dialog";
+import { appWindow, LogicalSize } from "@tauri-apps/api/window";
+
+import "../common/styles.css";
+import "./palette.css";
+
+const LISTEN_EVENT_NAME: string = "event-palette";
+
+type PaletteColor = {
+  red: number;
+  green: number;
+  blue: number;
+  coverage: number;
+};
+
+type PalettePayload = {
+  image: string;
+  colors: PaletteColor[];
+};
+
+type PaletteExportFormat = "json" | "gpl" | "ase";
+
+// Methods to address the memory leaks problems in Safari
+let BASE64_MARKER = ";base64,";
+let temporaryImage: string;
+let objectURL = window.URL || window.webkitURL;
+
+function convertDataURIToBlob(dataURI: string): Blob {
+  // Convert image (in base64) to binary data
+  let base64Index = dataURI.indexOf(BASE64_MARKER) + BASE64_MARKER.length;
+  let base64 = dataURI.substring(base64Index);
+
+  let raw = atob(base64);
+  let rawLength = raw.length;
+  let array = new Uint8Array(new ArrayBuffer(rawLength));
+
+  for (let i = 0; i < rawLength; i++) {
+    array[i] = raw.charCodeAt(i);
+  }
+
+  // Create and return a new blob object using binary data
+  return new Blob([array], { type: "image/png" });
+}
+
+function toHex(color: PaletteColor): string {
+  return (
+    "#" +
+    [color.red, color.green, color.blue]
+      .map((value) => value.toString(16).padStart(2, "0").toUpperCase())
+      .join("")
+  );
+}
+
+export function Palette() {
+  const [image, setImage] = createSignal("");
+  const [colors, setColors] = createSignal<PaletteColor[]>([]);
+
+  initializeWindow();
+  listenCloseWindow();
+  window.addEventListener("dblclick", () => setManualModeOn(false));
+  window.addEventListener("contextmenu", (event: MouseEvent) => {
+    event.preventDefault();
+  });
+
+  async function initializeWindow() {
+    appWindow.setTitle("Palette");
+    appWindow.setContentProtected(true);
+    appWindow.setAlwaysOnTop(true);
+    appWindow.setSize(new LogicalSize(320, 360));
+
+    await listen(LISTEN_EVENT_NAME, (event: any) => {
+      let payload = event.payload as PalettePayload;
+      if (temporaryImage) objectURL.revokeObjectURL(temporaryImage);
+      let imageDataBlob: Blob = convertDataURIToBlob(payload.image);
+      temporaryImage = objectURL.createObjectURL(imageDataBlob);
+      setImage(temporaryImage);
+      setColors(payload.colors);
+    });
+    setIsPaletteWindowOpen(true);
+    setManualModeOn(false);
+  }
+
+  async function listenCloseWindow() {
+    await appWindow.onCloseRequested(async () => {
+      setIsPaletteWindowOpen(false);
+    });
+  }
+
+  async function setIsPaletteWindowOpen(open: boolean) {
+    invoke("set_is_palette_window_open", { state: open });
+  }
+
+  async function setManualModeOn(state: boolean) {
+    invoke("set_manual_mode", { state: state });
+  }
+
+  async function exportPalette(format: PaletteExportFormat) {
+    const path = await save({
+      defaultPath: `palette.${format}`,
+      filters: [{ name: format.toUpperCase(), extensions: [format] }],
+    });
+    if (path === null) return;
+    invoke("export_palette", { path: path, format: format }).catch(
+      (error: string) => invoke("print_log", { text: error })
+    );
+  }
+
+  return (
+    <div>
+      <img src={image()}></img>
+      <ul class="swatch-list">
+        <For each={colors()}>
+          {(color) => (
+            <li>
+              <span
+                class="swatch-chip"
+                style={{ "background-color": toHex(color) }}
+              ></span>
+              {toHex(color)} {color.coverage.toFixed(1)}%
+            </li>
+          )}
+        </For>
+      </ul>
+      <div class="export-buttons">
+        <button onClick={() => exportPalette("json")}>JSON</button>
+        <button onClick={() => exportPalette("gpl")}>GPL</button>
+        <button onClick={() => exportPalette("ase")}>ASE</button>
+      </div>
+    </div>
+  );
+}
+
+render(() => <Palette />, document.getElementById("root") as HTMLElement);
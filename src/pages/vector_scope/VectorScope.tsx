@@ -3,18 +3,42 @@ import { createSignal } from "solid-js";
 import { invoke } from "@tauri-apps/api/tauri";
 import { listen } from "@tauri-apps/api/event";
 import { appWindow, LogicalSize } from "@tauri-apps/api/window";
-import { register, unregister } from "@tauri-apps/api/globalShortcut";
 
 import "../common/styles.css";
 
-const GLOBAL_SHORTCUT_KEY: string = "CommandOrControl+Shift+R";
 const LISTEN_EVENT_NAME: string = "event-vector-scope";
+const LISTEN_LEGAL_RANGE_EVENT_NAME: string = "event-legal-range";
 
 // Methods to address the memory leaks problems in Safari
 let BASE64_MARKER = ";base64,";
 let temporaryImage: string;
 let objectURL = window.URL || window.webkitURL;
 
+let hold: boolean = false;
+let peakHold: boolean = false;
+
+function setHoldStatus(status: any) {
+  hold = !status.is_live;
+}
+
+// Y4M files are opened as video, anything else as a still image
+function openFile(path: string) {
+  let command = path.toLowerCase().endsWith(".y4m")
+    ? "open_video_file"
+    : "open_image_file";
+  invoke(command, { path: path }).catch((error: string) =>
+    invoke("print_log", { text: error })
+  );
+}
+
+async function toggleVideoPlayback() {
+  if (await invoke("is_video_playing")) {
+    invoke("pause_video");
+  } else {
+    invoke("play_video");
+  }
+}
+
 function convertDataURIToBlob(dataURI: string): Blob {
   // Convert image (in base64) to binary data
   let base64Index = dataURI.indexOf(BASE64_MARKER) + BASE64_MARKER.length;
@@ -34,11 +58,44 @@ function convertDataURIToBlob(dataURI: string): Blob {
 
 export function Capture() {
   const [image, setImage] = createSignal("");
+  const [legalRangeExceeded, setLegalRangeExceeded] = createSignal(false);
 
   initializeWindow();
-  registerGlobalShortcutKey();
   listenCloseWindow();
   window.addEventListener("dblclick", () => setManualModeOn(false));
+  window.addEventListener("keydown", (event: KeyboardEvent) => {
+    switch (event.key) {
+      case "ArrowLeft":
+        invoke("step_frame_history", { offset: -1 }).then(setHoldStatus);
+        break;
+      case "ArrowRight":
+        invoke("step_frame_history", { offset: 1 }).then(setHoldStatus);
+        break;
+      case "h":
+        invoke("set_hold", { state: !hold }).then(setHoldStatus);
+        break;
+      case "p":
+        peakHold = !peakHold;
+        invoke("set_peak_hold", { state: peakHold });
+        break;
+      case " ":
+        toggleVideoPlayback();
+        break;
+      case "v":
+        if (event.ctrlKey || event.metaKey) {
+          invoke("analyze_clipboard_image").catch((error: string) =>
+            invoke("print_log", { text: error })
+          );
+        }
+        break;
+      case ",":
+        invoke("step_video", { offset: -1 }).catch(() => {});
+        break;
+      case ".":
+        invoke("step_video", { offset: 1 }).catch(() => {});
+        break;
+    }
+  });
   window.addEventListener("contextmenu", (event: MouseEvent) => {
     event.preventDefault();
   });
@@ -49,6 +106,9 @@ export function Capture() {
     appWindow.setAlwaysOnTop(true);
     appWindow.setSize(new LogicalSize(300, 320));
 
+    await listen(LISTEN_LEGAL_RANGE_EVENT_NAME, (event: any) => {
+      setLegalRangeExceeded(event.payload.is_exceeded as boolean);
+    });
     await listen(LISTEN_EVENT_NAME, (event: any) => {
       let dataURI = event.payload as string; // event.payload is payload
       if (temporaryImage) objectURL.revokeObjectURL(temporaryImage);
@@ -57,21 +117,18 @@ export function Capture() {
       setImage(temporaryImage);
       dataURI = "";
     });
+    await appWindow.onFileDropEvent((event) => {
+      if (event.payload.type === "drop" && event.payload.paths.length > 0) {
+        openFile(event.payload.paths[0]);
+      }
+    });
     setIsVectorScopeWindowOpen(true);
     setManualModeOn(false);
   }
 
-  async function registerGlobalShortcutKey() {
-    register(GLOBAL_SHORTCUT_KEY, () => {
-      setManualModeOn(true);
-      invoke("one_shot_emit");
-    });
-  }
-
   async function listenCloseWindow() {
     await appWindow.onCloseRequested(async () => {
       setIsVectorScopeWindowOpen(false);
-      unregister(GLOBAL_SHORTCUT_KEY);
     });
   }
 
@@ -85,7 +142,12 @@ export function Capture() {
 
   return (
     <div>
-      <img src={image()}></img>
+      <img
+        src={image()}
+        style={{
+          outline: legalRangeExceeded() ? "2px solid #e61e1e" : "none",
+        }}
+      ></img>
     </div>
   );
 }
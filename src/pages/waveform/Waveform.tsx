@@ -3,21 +3,78 @@ import { createSignal } from "solid-js";
 import { invoke } from "@tauri-apps/api/tauri";
 import { listen } from "@tauri-apps/api/event";
 import { appWindow, LogicalSize } from "@tauri-apps/api/window";
-import { register, unregister } from "@tauri-apps/api/globalShortcut";
 
 import "../common/styles.css";
 import "./waveform.css";
 
-const GLOBAL_SHORTCUT_KEY: string = "CommandOrControl+Shift+V";
 const LISTEN_EVENT_NAME: string = "event-waveform";
+const LISTEN_LEGAL_RANGE_EVENT_NAME: string = "event-legal-range";
+const LISTEN_HDR_STATISTICS_EVENT_NAME: string = "event-hdr-statistics";
+const HDR_MODES: string[] = ["off", "pq", "hlg"];
 
 // Methods to address the memory leaks problems in Safari
 let BASE64_MARKER = ";base64,";
 let temporaryImage: string;
 let objectURL = window.URL || window.webkitURL;
 
+let hold: boolean = false;
+let peakHold: boolean = false;
+
 let zoomed: boolean = false;
 
+const TRANSFER_FUNCTIONS: string[] = ["srgb", "bt1886", "pq", "hlg"];
+const TRANSFER_FUNCTION_NAMES: { [key: string]: string } = {
+  srgb: "sRGB",
+  bt1886: "BT.1886",
+  pq: "PQ",
+  hlg: "HLG",
+};
+let luminanceSettings: any = { mode: "luma", transfer_function: "srgb" };
+let hdrMode: string = "off";
+
+function setLuminanceSettings(settings: any) {
+  luminanceSettings = settings;
+  invoke("set_luminance_settings", { settings: settings });
+  updateTitle();
+}
+
+function updateTitle() {
+  if (hdrMode !== "off") {
+    appWindow.setTitle(`Waveform (${hdrMode.toUpperCase()}, nits)`);
+    return;
+  }
+  appWindow.setTitle(
+    luminanceSettings.mode === "luma"
+      ? "Waveform"
+      : `Waveform (linear Y, ${
+          TRANSFER_FUNCTION_NAMES[luminanceSettings.transfer_function] ??
+          "gamma"
+        })`
+  );
+}
+
+function setHoldStatus(status: any) {
+  hold = !status.is_live;
+}
+
+// Y4M files are opened as video, anything else as a still image
+function openFile(path: string) {
+  let command = path.toLowerCase().endsWith(".y4m")
+    ? "open_video_file"
+    : "open_image_file";
+  invoke(command, { path: path }).catch((error: string) =>
+    invoke("print_log", { text: error })
+  );
+}
+
+async function toggleVideoPlayback() {
+  if (await invoke("is_video_playing")) {
+    invoke("pause_video");
+  } else {
+    invoke("play_video");
+  }
+}
+
 function convertDataURIToBlob(dataURI: string): Blob {
   // Convert image (in base64) to binary data
   let base64Index = dataURI.indexOf(BASE64_MARKER) + BASE64_MARKER.length;
@@ -37,24 +94,96 @@ function convertDataURIToBlob(dataURI: string): Blob {
 
 export function Waveform() {
   const [image, setImage] = createSignal("");
+  const [legalRangeExceeded, setLegalRangeExceeded] = createSignal(false);
+  const [hdrStatistics, setHdrStatistics] = createSignal("");
   const [width, setWidth] = createSignal("95vw");
   const [height, setHeight] = createSignal("95vh");
 
   initializeWindow();
-  registerGlobalShortcutKey();
   listenCloseWindow();
   window.addEventListener("dblclick", () => setManualModeOn(false));
+  window.addEventListener("keydown", (event: KeyboardEvent) => {
+    switch (event.key) {
+      case "ArrowLeft":
+        invoke("step_frame_history", { offset: -1 }).then(setHoldStatus);
+        break;
+      case "ArrowRight":
+        invoke("step_frame_history", { offset: 1 }).then(setHoldStatus);
+        break;
+      case "h":
+        invoke("set_hold", { state: !hold }).then(setHoldStatus);
+        break;
+      case "p":
+        peakHold = !peakHold;
+        invoke("set_peak_hold", { state: peakHold });
+        break;
+      case " ":
+        toggleVideoPlayback();
+        break;
+      case "v":
+        if (event.ctrlKey || event.metaKey) {
+          invoke("analyze_clipboard_image").catch((error: string) =>
+            invoke("print_log", { text: error })
+          );
+        }
+        break;
+      case ",":
+        invoke("step_video", { offset: -1 }).catch(() => {});
+        break;
+      case ".":
+        invoke("step_video", { offset: 1 }).catch(() => {});
+        break;
+      case "y":
+        setLuminanceSettings({
+          ...luminanceSettings,
+          mode: luminanceSettings.mode === "luma" ? "linear" : "luma",
+        });
+        break;
+      case "t":
+        setLuminanceSettings({
+          ...luminanceSettings,
+          transfer_function:
+            TRANSFER_FUNCTIONS[
+              (TRANSFER_FUNCTIONS.indexOf(luminanceSettings.transfer_function) +
+                1) %
+                TRANSFER_FUNCTIONS.length
+            ],
+        });
+        break;
+      case "d":
+        hdrMode = HDR_MODES[(HDR_MODES.indexOf(hdrMode) + 1) % HDR_MODES.length];
+        invoke("set_hdr_mode", { mode: hdrMode });
+        setHdrStatistics("");
+        updateTitle();
+        break;
+      case "c":
+        invoke("reset_hdr_statistics");
+        break;
+    }
+  });
   window.addEventListener("contextmenu", (event: MouseEvent) => {
     event.preventDefault();
     setImageSize();
   });
 
   async function initializeWindow() {
-    appWindow.setTitle("Waveform");
+    luminanceSettings = await invoke("get_luminance_settings");
+    hdrMode = await invoke("get_hdr_mode");
+    updateTitle();
     appWindow.setContentProtected(true);
     appWindow.setAlwaysOnTop(true);
     appWindow.setSize(new LogicalSize(500, 280));
 
+    await listen(LISTEN_LEGAL_RANGE_EVENT_NAME, (event: any) => {
+      setLegalRangeExceeded(event.payload.is_exceeded as boolean);
+    });
+    await listen(LISTEN_HDR_STATISTICS_EVENT_NAME, (event: any) => {
+      let statistics = event.payload;
+      setHdrStatistics(
+        `MaxCLL ${statistics.max_cll.toFixed(0)} nits / ` +
+          `MaxFALL ${statistics.max_fall.toFixed(0)} nits`
+      );
+    });
     await listen(LISTEN_EVENT_NAME, (event: any) => {
       let dataURI = event.payload as string; // event.payload is payload
       if (temporaryImage) objectURL.revokeObjectURL(temporaryImage);
@@ -63,21 +192,18 @@ export function Waveform() {
       setImage(temporaryImage);
       dataURI = "";
     });
+    await appWindow.onFileDropEvent((event) => {
+      if (event.payload.type === "drop" && event.payload.paths.length > 0) {
+        openFile(event.payload.paths[0]);
+      }
+    });
     setIsWaveformWindowOpen(true);
     setManualModeOn(false);
   }
 
-  async function registerGlobalShortcutKey() {
-    register(GLOBAL_SHORTCUT_KEY, () => {
-      setManualModeOn(true);
-      invoke("one_shot_emit");
-    });
-  }
-
   async function listenCloseWindow() {
     await appWindow.onCloseRequested(async () => {
       setIsWaveformWindowOpen(false);
-      unregister(GLOBAL_SHORTCUT_KEY);
     });
   }
 
@@ -109,8 +235,10 @@ export function Waveform() {
           height: `${height()}`,
           "-webkit-transform": `scaleY(-1)`,
           transform: `scaleY(-1)`,
+          outline: legalRangeExceeded() ? "2px solid #e61e1e" : "none",
         }}
       ></img>
+      <div class="hdr-statistics">{hdrStatistics()}</div>
     </div>
   );
 }
@@ -33,6 +33,12 @@ export default defineConfig(async () => ({
           "vector_scope/vector_scope.html"
         ),
         waveform: resolve(__dirname + PAGE_DIR, "waveform/waveform.html"),
+        loupe: resolve(__dirname + PAGE_DIR, "loupe/loupe.html"),
+        palette: resolve(__dirname + PAGE_DIR, "palette/palette.html"),
+        false_color: resolve(
+          __dirname + PAGE_DIR,
+          "false_color/false_color.html"
+        ),
         capture_area_setting: resolve(
           __dirname + PAGE_DIR,
           "capture_area_setting_window/capture_area_setting_window.html"